@@ -0,0 +1,23 @@
+use std::process::Command;
+
+/// 將 git sha 與 建置時間 寫入編譯環境變數，供 `/version` 使用
+fn main() {
+    // Heroku 建置時沒有 .git，改由 SOURCE_VERSION 提供 commit
+    let git_sha = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|sha| sha.trim().to_owned())
+        .or_else(|| std::env::var("SOURCE_VERSION").ok())
+        .unwrap_or_else(|| "unknown".into());
+
+    let build_time = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+
+    println!("cargo:rustc-env=GIT_SHA={}", git_sha);
+    println!("cargo:rustc-env=BUILD_TIME={}", build_time);
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-env-changed=SOURCE_VERSION");
+}
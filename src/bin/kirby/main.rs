@@ -0,0 +1,400 @@
+mod render;
+
+use kirby_weather::{
+    config::{Config, Source},
+    cwb::{
+        logic,
+        model::{
+            cwb::forecast::ForecastType,
+            locale::{self, Language},
+            resp::{Forecast, Position, Record},
+            unit::UnitSystem,
+            Error,
+        },
+        source::{FileSource, ReplaySource, WeatherSource},
+        upstream::Upstream,
+    },
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use render::{number, optional, render, Format, Table};
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+/// 查詢台灣天氣，直接向 CWB 請求或透過 kirby_weather 服務
+#[derive(Parser)]
+#[command(name = "kirby", version)]
+struct Cli {
+    /// kirby_weather 服務位址，例如 http://localhost:3000；
+    /// 未指定時直接向 CWB 請求，需設定 CWB_API 與 TOKEN
+    #[arg(long, global = true)]
+    server: Option<Url>,
+
+    /// 離線模式，讀取目錄中由 CWB 下載的 JSON 檔
+    #[arg(long, global = true, conflicts_with = "server")]
+    data_dir: Option<PathBuf>,
+
+    /// 輸出格式
+    #[arg(long, global = true, value_enum, default_value = "table")]
+    format: Format,
+
+    /// 單位制：metric、imperial、si
+    #[arg(long, global = true, default_value = "metric")]
+    units: UnitSystem,
+
+    /// 語系：zh-TW、en
+    #[arg(long, global = true, default_value = "zh-TW")]
+    lang: Language,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 測站即時觀測資料
+    Now {
+        /// 縣市，例如 臺北市
+        #[arg(long)]
+        city: Option<String>,
+
+        /// 鄉鎮市區，例如 中正區
+        #[arg(long)]
+        town: Option<String>,
+
+        /// 排序欄位
+        #[arg(long, value_enum)]
+        sort: Option<SortKey>,
+
+        /// 最多顯示筆數
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+
+    /// 鄉鎮市區一週預報
+    Forecast {
+        /// 鄉鎮市區，例如 板橋區
+        #[arg(long)]
+        town: String,
+
+        /// 縣市，鄉鎮名稱重複時 (例如 東區) 需指定
+        #[arg(long)]
+        city: Option<String>,
+    },
+
+    /// 距離指定座標最近的測站
+    Stations {
+        /// 緯度,經度，例如 25.0,121.5
+        #[arg(long, value_parser = parse_coordinate, allow_hyphen_values = true)]
+        near: (f32, f32),
+
+        /// 最多顯示筆數
+        #[arg(long, default_value_t = 5)]
+        limit: usize,
+    },
+}
+
+/// 排序欄位，對應 `/weather?order_by=`
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum SortKey {
+    /// 溫度，由低到高
+    Temp,
+    /// 日累積雨量，由高到低
+    Rain,
+    /// 露點溫度，由高到低
+    DewPoint,
+    /// 熱指數，由高到低
+    HeatIndex,
+    /// 濕熱指數，由高到低
+    Humidex,
+    /// 風寒指數，由低到高
+    WindChill,
+}
+
+impl SortKey {
+    fn key(self) -> &'static str {
+        match self {
+            SortKey::Temp => "TEMP",
+            SortKey::Rain => "H_24R",
+            SortKey::DewPoint => "DEW_POINT",
+            SortKey::HeatIndex => "HEAT_INDEX",
+            SortKey::Humidex => "HUMIDEX",
+            SortKey::WindChill => "WIND_CHILL",
+        }
+    }
+}
+
+fn parse_coordinate(value: &str) -> Result<(f32, f32), String> {
+    let invalid = || format!("invalid coordinate: {}, expect <lat>,<lon>", value);
+
+    let (lat, lon) = value.split_once(',').ok_or_else(invalid)?;
+    let lat = lat.trim().parse().map_err(|_| invalid())?;
+    let lon = lon.trim().parse().map_err(|_| invalid())?;
+
+    Ok((lat, lon))
+}
+
+/// 直接取得資料時使用的資料來源，設定與服務相同
+fn source(cli: &Cli) -> Result<Box<dyn WeatherSource>, Error> {
+    let config = Config::with_data_dir(cli.data_dir.clone())?;
+
+    let source: Box<dyn WeatherSource> = match &config.source {
+        Source::Cwb => Box::new(Upstream::new(&config)?),
+        Source::File(dir) => Box::new(FileSource::new(dir)),
+        Source::Replay(dir) => Box::new(ReplaySource::from_dir(dir)?),
+    };
+
+    Ok(source)
+}
+
+/// 向 kirby_weather 服務請求
+async fn get<T>(server: &Url, path: &str, query: &[(&str, &str)]) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let res = reqwest::Client::new()
+        .get(server.join(path)?)
+        .query(query)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(res.json().await?)
+}
+
+/// 未指定條件，或 名稱相同 (臺、台 視為相同)
+fn matches(value: &str, filter: &Option<String>) -> bool {
+    filter
+        .as_ref()
+        .is_none_or(|filter| locale::normalize(value) == locale::normalize(filter))
+}
+
+/// 全台測站資料，透過服務時由服務排序
+async fn records(cli: &Cli, sort: Option<SortKey>) -> Result<Vec<Record>, Error> {
+    match &cli.server {
+        Some(server) => {
+            let query: Vec<_> = sort
+                .map(|sort| ("order_by", sort.key()))
+                .into_iter()
+                .collect();
+            get(server, "/weather", &query).await
+        }
+        None => {
+            let mut records = logic::get_weather_data(source(cli)?.as_ref()).await?;
+            if let Some(cmp) = sort.and_then(|sort| logic::order_by(sort.key())) {
+                records.sort_by(cmp);
+            }
+
+            Ok(records)
+        }
+    }
+}
+
+fn station_table(cli: &Cli, records: &[Record], distances: Option<&[f32]>) -> Table {
+    let units = cli.units.units();
+
+    let mut headers = vec![
+        "city".to_owned(),
+        "town".to_owned(),
+        "station".to_owned(),
+        format!("temp ({})", units.temperature),
+        format!("dew_point ({})", units.temperature),
+        format!("heat_index ({})", units.temperature),
+        format!("humidex ({})", units.temperature),
+        format!("wind_chill ({})", units.temperature),
+        format!("wind_speed ({})", units.wind_speed),
+        format!("pressure ({})", units.pressure),
+    ];
+    if distances.is_some() {
+        headers.push("distance (km)".to_owned());
+    }
+
+    let mut table = Table::new(&headers);
+    for (index, item) in records.iter().enumerate() {
+        let mut row = vec![
+            item.city.clone(),
+            item.town.clone(),
+            item.name.clone(),
+            number(item.temperature),
+            optional(item.dew_point),
+            optional(item.heat_index),
+            optional(item.humidex),
+            optional(item.wind_chill),
+            optional(item.wind_speed),
+            optional(item.pressure),
+        ];
+        if let Some(distances) = distances {
+            row.push(number(distances[index]));
+        }
+
+        table.push(row);
+    }
+
+    table
+}
+
+async fn now(
+    cli: &Cli,
+    city: &Option<String>,
+    town: &Option<String>,
+    sort: Option<SortKey>,
+    limit: Option<usize>,
+) -> Result<String, Error> {
+    let mut records = records(cli, sort).await?;
+
+    records.retain(|item| matches(&item.city, city) && matches(&item.town, town));
+    if let Some(limit) = limit {
+        records.truncate(limit);
+    }
+
+    let records: Vec<_> = records
+        .into_iter()
+        .map(|item| item.with_units(cli.units).with_language(cli.lang))
+        .collect();
+
+    Ok(render(cli.format, &records, || {
+        station_table(cli, &records, None)
+    }))
+}
+
+#[derive(Serialize)]
+struct Nearby {
+    distance_km: f32,
+
+    #[serde(flatten)]
+    station: Record,
+}
+
+async fn stations(
+    cli: &Cli,
+    (latitude, longitude): (f32, f32),
+    limit: usize,
+) -> Result<String, Error> {
+    let near = Position {
+        latitude,
+        longitude,
+    };
+
+    let mut stations: Vec<_> = records(cli, None)
+        .await?
+        .into_iter()
+        .map(|item| Nearby {
+            distance_km: logic::distance(&near, &item.location),
+            station: item.with_units(cli.units).with_language(cli.lang),
+        })
+        .collect();
+
+    stations.sort_by(|a, b| a.distance_km.total_cmp(&b.distance_km));
+    stations.truncate(limit);
+
+    Ok(render(cli.format, &stations, || {
+        let distances: Vec<_> = stations.iter().map(|item| item.distance_km).collect();
+        let records: Vec<_> = stations.iter().map(|item| item.station.clone()).collect();
+        station_table(cli, &records, Some(&distances))
+    }))
+}
+
+async fn forecast(cli: &Cli, town: &str, city: &Option<String>) -> Result<String, Error> {
+    let city = match city {
+        Some(city) => city.clone(),
+        None => match locale::cities_of_town(town)[..] {
+            [city] => city.to_owned(),
+            [] => return Err(format!("unknown town: {}", town).into()),
+            ref cities => {
+                let cities = cities.join(", ");
+                return Err(format!("{} is in {}, specify one with --city", town, cities).into());
+            }
+        },
+    };
+
+    let forecast: Forecast = match &cli.server {
+        Some(server) => {
+            let query = [
+                ("units", cli.units.units().system),
+                ("lang", cli.lang.tag()),
+            ];
+            let forecast: Forecast = get(server, "/forecast", &query).await?;
+
+            // 服務只提供固定鄉鎮的預報
+            if forecast.name != locale::town(&city, town, cli.lang) {
+                return Err(format!(
+                    "the service only provides the forecast of {}",
+                    forecast.name
+                )
+                .into());
+            }
+
+            forecast
+        }
+        None => {
+            let forecast_type =
+                ForecastType::in_week(&city).ok_or_else(|| format!("unknown city: {}", city))?;
+
+            let locations = logic::get_city_forecast(source(cli)?.as_ref(), forecast_type).await?;
+            let location = locations
+                .iter()
+                .find(|item| locale::normalize(&item.name) == locale::normalize(town))
+                .ok_or_else(|| format!("no forecast of {} {}", city, town))?;
+
+            logic::to_forecast(location, cli.lang)
+                .ok_or_else(|| format!("no temperature forecast of {} {}", city, town))?
+                .with_units(cli.units)
+        }
+    };
+
+    let output = render(cli.format, &forecast, || {
+        let mut table = Table::new(&["element", "start_time", "end_time", "value"]);
+        for item in &forecast.descriptions {
+            table.push(vec![
+                item.element.clone(),
+                item.start_time.clone(),
+                item.end_time.clone(),
+                item.value.clone(),
+            ]);
+        }
+
+        table
+    });
+
+    if cli.format != Format::Table {
+        return Ok(output);
+    }
+
+    let unit = cli.units.units().temperature;
+    Ok(format!(
+        "{} {}\nmax {} {unit}, min {} {unit}, max daily range {} {unit}\n\n{}",
+        forecast.city,
+        forecast.name,
+        number(forecast.max_temperature),
+        number(forecast.min_temperature),
+        number(forecast.temperature_difference_per_day),
+        output,
+    ))
+}
+
+async fn run(cli: &Cli) -> Result<String, Error> {
+    match &cli.command {
+        Command::Now {
+            city,
+            town,
+            sort,
+            limit,
+        } => now(cli, city, town, *sort, *limit).await,
+        Command::Forecast { town, city } => forecast(cli, town, city).await,
+        Command::Stations { near, limit } => stations(cli, *near, *limit).await,
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    match run(&cli).await {
+        Ok(output) => print!("{}", output),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use clap::ValueEnum;
+use serde::Serialize;
+use unicode_width::UnicodeWidthStr;
+
+/// 輸出格式
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Format {
+    /// 對齊的表格
+    Table,
+    Json,
+    Csv,
+}
+
+/// 表格或 CSV 的內容，欄位皆已轉成文字
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new<S: ToString>(headers: &[S]) -> Self {
+        Table {
+            headers: headers.iter().map(ToString::to_string).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// 以顯示寬度對齊，中文字佔兩格
+    fn aligned(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|column| {
+                std::iter::once(&self.headers)
+                    .chain(&self.rows)
+                    .filter_map(|row| row.get(column))
+                    .map(|cell| cell.width())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+
+        let line = |row: &[String]| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.width())))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_owned()
+        };
+
+        let separator: Vec<_> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+        std::iter::once(line(&self.headers))
+            .chain(std::iter::once(line(&separator)))
+            .chain(self.rows.iter().map(|row| line(row)))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    fn csv(&self) -> String {
+        let escape = |cell: &String| {
+            if cell.contains([',', '"', '\n']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        };
+
+        std::iter::once(&self.headers)
+            .chain(&self.rows)
+            .map(|row| row.iter().map(escape).collect::<Vec<_>>().join(",") + "\n")
+            .collect()
+    }
+}
+
+/// 依格式輸出，JSON 直接序列化原始資料，表格與 CSV 使用 `table`
+pub fn render<T>(format: Format, data: &T, table: impl FnOnce() -> Table) -> String
+where
+    T: Serialize + ?Sized,
+{
+    match format {
+        Format::Json => {
+            serde_json::to_string_pretty(data).expect("error occurred when serializing result")
+                + "\n"
+        }
+        Format::Table => table().aligned(),
+        Format::Csv => table().csv(),
+    }
+}
+
+/// 數值欄位，表格中保留一位小數
+pub fn number(value: f32) -> String {
+    format!("{:.1}", value)
+}
+
+/// 可能缺值的數值欄位
+pub fn optional(value: Option<f32>) -> String {
+    value.map(number).unwrap_or_default()
+}
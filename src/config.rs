@@ -0,0 +1,739 @@
+//! 服務設定，來源依序為 環境變數、`.env`、設定檔
+
+use crate::cwb::{
+    logic::{MANNED_STATIONS_DATASET, WEATHER_DATA_DATASET},
+    model::cwb::weather_data::Schema,
+};
+
+use dotenv::dotenv;
+use reqwest::Url;
+use serde::Deserialize;
+use std::{
+    env, fmt, fs,
+    net::{IpAddr, SocketAddr},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+/// 離線模式未設定 `CWB_API` 時使用的預設值
+const DEFAULT_CWB_API: &str = "https://opendata.cwb.gov.tw/api";
+
+/// 環境部 (MOENV) 開放資料 API 的預設位址
+const DEFAULT_AIR_API: &str = "https://data.moenv.gov.tw/api/v2";
+
+/// 未指定 `CONFIG_FILE` 時，若存在則讀取的設定檔
+const DEFAULT_CONFIG_FILE: &str = "kirby.toml";
+
+/// 載入設定失敗的原因
+#[derive(Debug)]
+pub enum ConfigError {
+    /// 必要設定未提供
+    Missing(&'static str),
+
+    /// 設定值格式錯誤
+    Invalid {
+        key: &'static str,
+        value: String,
+        reason: String,
+    },
+
+    /// 設定檔讀取或解析失敗
+    File { path: String, reason: String },
+}
+
+impl std::error::Error for ConfigError {}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Missing(key) => write!(
+                f,
+                "{} is required, set it in the environment, .env or the config file",
+                key
+            ),
+            ConfigError::Invalid { key, value, reason } => {
+                write!(f, "invalid {}: {:?}, {}", key, value, reason)
+            }
+            ConfigError::File { path, reason } => {
+                write!(f, "failed to load config file {}: {}", path, reason)
+            }
+        }
+    }
+}
+
+/// 日誌等級，由嚴重到詳細
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" => Ok(LogLevel::Debug),
+            "trace" => Ok(LogLevel::Trace),
+            _ => Err("expect one of error, warn, info, debug, trace".into()),
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let level = match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        };
+
+        write!(f, "{}", level)
+    }
+}
+
+/// 天氣資料來源，`SOURCE`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// CWB 開放資料 API，`cwb`
+    Cwb,
+
+    /// 讀取目錄中的 JSON 檔，`file:<dir>`
+    File(PathBuf),
+
+    /// 依序重播目錄中錄製的回應，`replay:<dir>`
+    Replay(PathBuf),
+}
+
+impl FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let dir = |dir: &str| {
+            if dir.is_empty() {
+                Err("expect a directory after the colon".to_owned())
+            } else {
+                Ok(PathBuf::from(dir))
+            }
+        };
+
+        match s.split_once(':') {
+            None if s == "cwb" => Ok(Source::Cwb),
+            Some(("file", path)) => dir(path).map(Source::File),
+            Some(("replay", path)) => dir(path).map(Source::Replay),
+            _ => Err("expect cwb, file:<dir> or replay:<dir>".into()),
+        }
+    }
+}
+
+/// 設定檔內容，欄位皆可省略，環境變數優先於設定檔
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileConfig {
+    host: Option<String>,
+    port: Option<u16>,
+    cwb_api: Option<String>,
+    token: Option<String>,
+    cwb_schema: Option<String>,
+    observation_dataset: Option<String>,
+    air_api: Option<String>,
+    air_api_key: Option<String>,
+    source: Option<String>,
+    watch_interval: Option<u64>,
+    refresh_interval: Option<u64>,
+    heartbeat_interval: Option<u64>,
+    connect_timeout: Option<u64>,
+    request_timeout: Option<u64>,
+    read_timeout: Option<u64>,
+    retry_max: Option<u32>,
+    retry_base_delay_ms: Option<u64>,
+    retry_max_delay_ms: Option<u64>,
+    breaker_threshold: Option<u32>,
+    breaker_cooldown: Option<u64>,
+    cache_ttl: Option<u64>,
+    max_staleness: Option<u64>,
+    snapshot_dir: Option<String>,
+    drain_timeout: Option<u64>,
+    ready_max_age: Option<u64>,
+    log_level: Option<String>,
+    admin_token: Option<String>,
+    webhook_timeout: Option<u64>,
+    webhook_retry_max: Option<u32>,
+    webhook_retry_base_delay_ms: Option<u64>,
+    webhook_retry_max_delay_ms: Option<u64>,
+    webhook_cooldown: Option<u64>,
+}
+
+/// 服務設定，於啟動時載入並驗證一次
+#[derive(Clone)]
+pub struct Config {
+    /// 服務監聽位址，`HOST` + `PORT`
+    pub addr: SocketAddr,
+
+    /// CWB 開放資料 API 位址，`CWB_API`
+    pub cwb_api: Url,
+
+    /// CWB 開放資料 授權碼，`TOKEN`
+    pub token: String,
+
+    /// 觀測資料的 JSON 格式，`CWB_SCHEMA` (`auto`、`v1`、`v2`)，`None` 表示自動判斷
+    pub schema: Option<Schema>,
+
+    /// 觀測資料的 dataset，`OBSERVATION_DATASET`，
+    /// `O-A0001-001` 自動氣象站 或 `O-A0003-001` 有人氣象站
+    pub observation_dataset: String,
+
+    /// 環境部 (MOENV) 開放資料 API 位址，`AIR_API`
+    pub air_api: Url,
+
+    /// 環境部 開放資料 API 金鑰，`AIR_API_KEY`，未設定時 `cwb` 來源不提供紫外線與空氣品質
+    pub air_api_key: Option<String>,
+
+    /// 天氣資料來源，`SOURCE`，預設為 `cwb`
+    pub source: Source,
+
+    /// `file:<dir>` 來源檢查目錄是否有新檔案的間隔，`WATCH_INTERVAL` (秒)
+    pub watch_interval: Duration,
+
+    /// 有 `/weather/stream` 連線或註冊 webhook 時，背景更新資料的間隔，`REFRESH_INTERVAL` (秒)
+    pub refresh_interval: Duration,
+
+    /// `/weather/stream` 沒有更新時送出 heartbeat 的間隔，`HEARTBEAT_INTERVAL` (秒)
+    pub heartbeat_interval: Duration,
+
+    /// 連線至 CWB 的逾時，`CONNECT_TIMEOUT` (秒)
+    pub connect_timeout: Duration,
+
+    /// 單次 CWB 請求的逾時，`REQUEST_TIMEOUT` (秒)
+    pub request_timeout: Duration,
+
+    /// 讀取 CWB 回應時，兩次收到資料的最長間隔，`READ_TIMEOUT` (秒)
+    pub read_timeout: Duration,
+
+    /// 暫時性錯誤 (逾時、連線失敗、5xx) 的最多重試次數，`RETRY_MAX`
+    pub retry_max: u32,
+
+    /// 第一次重試前的等待時間，之後每次加倍，`RETRY_BASE_DELAY_MS` (毫秒)
+    pub retry_base_delay: Duration,
+
+    /// 重試等待時間上限，`RETRY_MAX_DELAY_MS` (毫秒)
+    pub retry_max_delay: Duration,
+
+    /// 連續失敗幾次後打開斷路器，`BREAKER_THRESHOLD`
+    pub breaker_threshold: u32,
+
+    /// 斷路器打開後暫停請求上游的時間，`BREAKER_COOLDOWN` (秒)
+    pub breaker_cooldown: Duration,
+
+    /// 上游資料快取時間，`CACHE_TTL` (秒)，0 表示不快取
+    pub cache_ttl: Duration,
+
+    /// 上游失敗時，最多可回傳多舊的快照，`MAX_STALENESS` (秒)，0 表示不使用快照
+    pub max_staleness: Duration,
+
+    /// 快照保存目錄，`SNAPSHOT_DIR`，未設定時只保存在記憶體
+    pub snapshot_dir: Option<PathBuf>,
+
+    /// 關閉時等待處理中請求完成的時間，`DRAIN_TIMEOUT` (秒)
+    pub drain_timeout: Duration,
+
+    /// 上游資料超過此時間未成功更新即視為未就緒，`READY_MAX_AGE` (秒)
+    pub ready_max_age: Duration,
+
+    /// 日誌等級，`LOG_LEVEL`
+    pub log_level: LogLevel,
+
+    /// `/admin` API 的 Bearer token，`ADMIN_TOKEN`，未設定時停用
+    pub admin_token: Option<String>,
+
+    /// 單次 webhook 傳送的逾時，`WEBHOOK_TIMEOUT` (秒)
+    pub webhook_timeout: Duration,
+
+    /// webhook 傳送失敗 (逾時、連線失敗、429、5xx) 的最多重試次數，`WEBHOOK_RETRY_MAX`
+    pub webhook_retry_max: u32,
+
+    /// webhook 第一次重試前的等待時間，之後每次加倍，`WEBHOOK_RETRY_BASE_DELAY_MS` (毫秒)
+    pub webhook_retry_base_delay: Duration,
+
+    /// webhook 重試等待時間上限，`WEBHOOK_RETRY_MAX_DELAY_MS` (毫秒)
+    pub webhook_retry_max_delay: Duration,
+
+    /// 註冊時未指定的規則冷卻時間，同一規則通知後於此期間內不再通知，`WEBHOOK_COOLDOWN` (秒)
+    pub webhook_cooldown: Duration,
+}
+
+/// 未設定時的預設值，`cwb_api` 為 CWB 正式環境，`token` 為空
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            addr: SocketAddr::from(([0, 0, 0, 0], 3000)),
+            cwb_api: DEFAULT_CWB_API.parse().unwrap(),
+            token: String::new(),
+            schema: None,
+            observation_dataset: WEATHER_DATA_DATASET.to_owned(),
+            air_api: DEFAULT_AIR_API.parse().unwrap(),
+            air_api_key: None,
+            source: Source::Cwb,
+            watch_interval: Duration::from_secs(5),
+            refresh_interval: Duration::from_secs(60),
+            heartbeat_interval: Duration::from_secs(15),
+            connect_timeout: Duration::from_secs(5),
+            request_timeout: Duration::from_secs(30),
+            read_timeout: Duration::from_secs(10),
+            retry_max: 2,
+            retry_base_delay: Duration::from_millis(200),
+            retry_max_delay: Duration::from_millis(2000),
+            breaker_threshold: 5,
+            breaker_cooldown: Duration::from_secs(30),
+            cache_ttl: Duration::from_secs(60),
+            max_staleness: Duration::from_secs(6 * 60 * 60),
+            snapshot_dir: None,
+            // Heroku 於 SIGTERM 後 30 秒強制結束，預設保留些許餘裕
+            drain_timeout: Duration::from_secs(25),
+            ready_max_age: Duration::from_secs(600),
+            log_level: LogLevel::Info,
+            admin_token: None,
+            webhook_timeout: Duration::from_secs(10),
+            webhook_retry_max: 3,
+            webhook_retry_base_delay: Duration::from_millis(1000),
+            webhook_retry_max_delay: Duration::from_millis(30_000),
+            webhook_cooldown: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Config")
+            .field("addr", &self.addr)
+            .field("cwb_api", &self.cwb_api.as_str())
+            .field("token", &"***")
+            .field("schema", &self.schema)
+            .field("observation_dataset", &self.observation_dataset)
+            .field("air_api", &self.air_api.as_str())
+            .field("air_api_key", &self.air_api_key.as_ref().map(|_| "***"))
+            .field("source", &self.source)
+            .field("watch_interval", &self.watch_interval)
+            .field("refresh_interval", &self.refresh_interval)
+            .field("heartbeat_interval", &self.heartbeat_interval)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("request_timeout", &self.request_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("retry_max", &self.retry_max)
+            .field("retry_base_delay", &self.retry_base_delay)
+            .field("retry_max_delay", &self.retry_max_delay)
+            .field("breaker_threshold", &self.breaker_threshold)
+            .field("breaker_cooldown", &self.breaker_cooldown)
+            .field("cache_ttl", &self.cache_ttl)
+            .field("max_staleness", &self.max_staleness)
+            .field("snapshot_dir", &self.snapshot_dir)
+            .field("drain_timeout", &self.drain_timeout)
+            .field("ready_max_age", &self.ready_max_age)
+            .field("log_level", &self.log_level)
+            .field("admin_token", &self.admin_token.as_ref().map(|_| "***"))
+            .field("webhook_timeout", &self.webhook_timeout)
+            .field("webhook_retry_max", &self.webhook_retry_max)
+            .field("webhook_retry_base_delay", &self.webhook_retry_base_delay)
+            .field("webhook_retry_max_delay", &self.webhook_retry_max_delay)
+            .field("webhook_cooldown", &self.webhook_cooldown)
+            .finish()
+    }
+}
+
+fn read_file(path: &str) -> Result<FileConfig, ConfigError> {
+    let error = |reason: String| ConfigError::File {
+        path: path.to_owned(),
+        reason,
+    };
+
+    let content = fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+    toml::from_str(&content).map_err(|err| error(err.to_string()))
+}
+
+/// 取得設定值，環境變數優先，其次為設定檔
+fn lookup(key: &'static str, file: Option<String>) -> Option<String> {
+    env::var(key)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+        .or(file)
+}
+
+fn parse<T>(key: &'static str, value: String) -> Result<T, ConfigError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|err: T::Err| ConfigError::Invalid {
+            key,
+            reason: err.to_string(),
+            value,
+        })
+}
+
+/// 命令列參數 `--data-dir <dir>` 或 `--data-dir=<dir>`，其他參數不屬於設定，略過
+fn data_dir(mut args: impl Iterator<Item = String>) -> Result<Option<PathBuf>, ConfigError> {
+    let mut dir = None;
+
+    while let Some(arg) = args.next() {
+        let value = match arg.strip_prefix("--data-dir") {
+            Some("") => args.next(),
+            Some(value) if value.starts_with('=') => Some(value[1..].to_owned()),
+            _ => continue,
+        };
+
+        match value.filter(|value| !value.is_empty()) {
+            Some(value) => dir = Some(PathBuf::from(value)),
+            None => {
+                return Err(ConfigError::Invalid {
+                    key: "arguments",
+                    value: "--data-dir".into(),
+                    reason: "expect a directory".into(),
+                })
+            }
+        }
+    }
+
+    Ok(dir)
+}
+
+fn number<T>(key: &'static str, file: Option<T>, default: T) -> Result<T, ConfigError>
+where
+    T: FromStr + ToString,
+    T::Err: fmt::Display,
+{
+    match lookup(key, file.map(|value| value.to_string())) {
+        Some(value) => parse(key, value),
+        None => Ok(default),
+    }
+}
+
+fn seconds(key: &'static str, file: Option<u64>, default: u64) -> Result<Duration, ConfigError> {
+    number(key, file, default).map(Duration::from_secs)
+}
+
+fn millis(key: &'static str, file: Option<u64>, default: u64) -> Result<Duration, ConfigError> {
+    number(key, file, default).map(Duration::from_millis)
+}
+
+impl Config {
+    /// 依序讀取 `.env`、環境變數 與 設定檔 (`CONFIG_FILE`，預設為 `kirby.toml`)
+    pub fn load() -> Result<Config, ConfigError> {
+        let data_dir = data_dir(env::args().skip(1))?;
+        Self::with_data_dir(data_dir)
+    }
+
+    /// 同 `load`，但不解析命令列參數，`data_dir` 對應 `--data-dir`
+    pub fn with_data_dir(data_dir: Option<PathBuf>) -> Result<Config, ConfigError> {
+        dotenv().ok();
+        let defaults = Config::default();
+
+        let file = match env::var("CONFIG_FILE") {
+            Ok(path) => read_file(&path)?,
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => read_file(DEFAULT_CONFIG_FILE)?,
+            Err(_) => FileConfig::default(),
+        };
+
+        let host: IpAddr = match lookup("HOST", file.host) {
+            Some(host) => parse("HOST", host)?,
+            None => defaults.addr.ip(),
+        };
+        let port = number("PORT", file.port, defaults.addr.port())?;
+
+        // 命令列 `--data-dir <dir>` 優先於 `SOURCE`
+        let source = match data_dir {
+            Some(dir) => Source::File(dir),
+            None => parse(
+                "SOURCE",
+                lookup("SOURCE", file.source).unwrap_or_else(|| "cwb".into()),
+            )?,
+        };
+
+        if let Source::File(dir) | Source::Replay(dir) = &source {
+            if !dir.is_dir() {
+                return Err(ConfigError::Invalid {
+                    key: "SOURCE",
+                    value: dir.display().to_string(),
+                    reason: "directory does not exist".into(),
+                });
+            }
+        }
+
+        // 離線模式不會連線至 CWB，不要求 API 位址與授權碼
+        let cwb_api: Url = match lookup("CWB_API", file.cwb_api) {
+            Some(cwb_api) => parse("CWB_API", cwb_api)?,
+            None if source != Source::Cwb => defaults.cwb_api,
+            None => return Err(ConfigError::Missing("CWB_API")),
+        };
+        if !matches!(cwb_api.scheme(), "http" | "https") {
+            return Err(ConfigError::Invalid {
+                key: "CWB_API",
+                value: cwb_api.to_string(),
+                reason: "expect an http or https url".into(),
+            });
+        }
+
+        let token = match lookup("TOKEN", file.token) {
+            Some(token) => token,
+            None if source != Source::Cwb => defaults.token,
+            None => return Err(ConfigError::Missing("TOKEN")),
+        };
+
+        let schema = match lookup("CWB_SCHEMA", file.cwb_schema) {
+            Some(schema) if schema.trim() == "auto" => None,
+            Some(schema) => Some(parse("CWB_SCHEMA", schema)?),
+            None => defaults.schema,
+        };
+
+        let observation_dataset = lookup("OBSERVATION_DATASET", file.observation_dataset)
+            .map(|dataset| dataset.trim().to_owned())
+            .unwrap_or(defaults.observation_dataset);
+        if ![WEATHER_DATA_DATASET, MANNED_STATIONS_DATASET].contains(&observation_dataset.as_str())
+        {
+            return Err(ConfigError::Invalid {
+                key: "OBSERVATION_DATASET",
+                value: observation_dataset,
+                reason: format!(
+                    "expect {} or {}",
+                    WEATHER_DATA_DATASET, MANNED_STATIONS_DATASET
+                ),
+            });
+        }
+
+        let air_api: Url = match lookup("AIR_API", file.air_api) {
+            Some(air_api) => parse("AIR_API", air_api)?,
+            None => defaults.air_api,
+        };
+        if !matches!(air_api.scheme(), "http" | "https") {
+            return Err(ConfigError::Invalid {
+                key: "AIR_API",
+                value: air_api.to_string(),
+                reason: "expect an http or https url".into(),
+            });
+        }
+        let air_api_key = lookup("AIR_API_KEY", file.air_api_key);
+
+        let watch_interval = seconds(
+            "WATCH_INTERVAL",
+            file.watch_interval,
+            defaults.watch_interval.as_secs(),
+        )?;
+        let refresh_interval = seconds(
+            "REFRESH_INTERVAL",
+            file.refresh_interval,
+            defaults.refresh_interval.as_secs(),
+        )?;
+        let heartbeat_interval = seconds(
+            "HEARTBEAT_INTERVAL",
+            file.heartbeat_interval,
+            defaults.heartbeat_interval.as_secs(),
+        )?;
+        for (key, interval) in [
+            ("WATCH_INTERVAL", watch_interval),
+            ("REFRESH_INTERVAL", refresh_interval),
+            ("HEARTBEAT_INTERVAL", heartbeat_interval),
+        ] {
+            if interval.is_zero() {
+                return Err(ConfigError::Invalid {
+                    key,
+                    value: "0".into(),
+                    reason: "interval must be greater than 0".into(),
+                });
+            }
+        }
+
+        let connect_timeout = seconds(
+            "CONNECT_TIMEOUT",
+            file.connect_timeout,
+            defaults.connect_timeout.as_secs(),
+        )?;
+        let request_timeout = seconds(
+            "REQUEST_TIMEOUT",
+            file.request_timeout,
+            defaults.request_timeout.as_secs(),
+        )?;
+        let read_timeout = seconds(
+            "READ_TIMEOUT",
+            file.read_timeout,
+            defaults.read_timeout.as_secs(),
+        )?;
+        let webhook_timeout = seconds(
+            "WEBHOOK_TIMEOUT",
+            file.webhook_timeout,
+            defaults.webhook_timeout.as_secs(),
+        )?;
+        for (key, timeout) in [
+            ("CONNECT_TIMEOUT", connect_timeout),
+            ("REQUEST_TIMEOUT", request_timeout),
+            ("READ_TIMEOUT", read_timeout),
+            ("WEBHOOK_TIMEOUT", webhook_timeout),
+        ] {
+            if timeout.is_zero() {
+                return Err(ConfigError::Invalid {
+                    key,
+                    value: "0".into(),
+                    reason: "timeout must be greater than 0".into(),
+                });
+            }
+        }
+
+        let retry_max = number("RETRY_MAX", file.retry_max, defaults.retry_max)?;
+        let retry_base_delay = millis(
+            "RETRY_BASE_DELAY_MS",
+            file.retry_base_delay_ms,
+            defaults.retry_base_delay.as_millis() as u64,
+        )?;
+        let retry_max_delay = millis(
+            "RETRY_MAX_DELAY_MS",
+            file.retry_max_delay_ms,
+            defaults.retry_max_delay.as_millis() as u64,
+        )?;
+
+        let breaker_threshold = number(
+            "BREAKER_THRESHOLD",
+            file.breaker_threshold,
+            defaults.breaker_threshold,
+        )?;
+        if breaker_threshold == 0 {
+            return Err(ConfigError::Invalid {
+                key: "BREAKER_THRESHOLD",
+                value: "0".into(),
+                reason: "threshold must be greater than 0".into(),
+            });
+        }
+        let breaker_cooldown = seconds(
+            "BREAKER_COOLDOWN",
+            file.breaker_cooldown,
+            defaults.breaker_cooldown.as_secs(),
+        )?;
+
+        let cache_ttl = seconds("CACHE_TTL", file.cache_ttl, defaults.cache_ttl.as_secs())?;
+
+        let max_staleness = seconds(
+            "MAX_STALENESS",
+            file.max_staleness,
+            defaults.max_staleness.as_secs(),
+        )?;
+        let snapshot_dir = lookup("SNAPSHOT_DIR", file.snapshot_dir).map(PathBuf::from);
+
+        let drain_timeout = seconds(
+            "DRAIN_TIMEOUT",
+            file.drain_timeout,
+            defaults.drain_timeout.as_secs(),
+        )?;
+
+        let ready_max_age = seconds(
+            "READY_MAX_AGE",
+            file.ready_max_age,
+            defaults.ready_max_age.as_secs(),
+        )?;
+
+        let log_level = match lookup("LOG_LEVEL", file.log_level) {
+            Some(level) => parse("LOG_LEVEL", level)?,
+            None => defaults.log_level,
+        };
+
+        let admin_token = lookup("ADMIN_TOKEN", file.admin_token);
+
+        let webhook_retry_max = number(
+            "WEBHOOK_RETRY_MAX",
+            file.webhook_retry_max,
+            defaults.webhook_retry_max,
+        )?;
+        let webhook_retry_base_delay = millis(
+            "WEBHOOK_RETRY_BASE_DELAY_MS",
+            file.webhook_retry_base_delay_ms,
+            defaults.webhook_retry_base_delay.as_millis() as u64,
+        )?;
+        let webhook_retry_max_delay = millis(
+            "WEBHOOK_RETRY_MAX_DELAY_MS",
+            file.webhook_retry_max_delay_ms,
+            defaults.webhook_retry_max_delay.as_millis() as u64,
+        )?;
+        let webhook_cooldown = seconds(
+            "WEBHOOK_COOLDOWN",
+            file.webhook_cooldown,
+            defaults.webhook_cooldown.as_secs(),
+        )?;
+
+        Ok(Config {
+            addr: SocketAddr::new(host, port),
+            cwb_api,
+            token,
+            schema,
+            observation_dataset,
+            air_api,
+            air_api_key,
+            source,
+            watch_interval,
+            refresh_interval,
+            heartbeat_interval,
+            connect_timeout,
+            request_timeout,
+            read_timeout,
+            retry_max,
+            retry_base_delay,
+            retry_max_delay,
+            breaker_threshold,
+            breaker_cooldown,
+            cache_ttl,
+            max_staleness,
+            snapshot_dir,
+            drain_timeout,
+            ready_max_age,
+            log_level,
+            admin_token,
+            webhook_timeout,
+            webhook_retry_max,
+            webhook_retry_base_delay,
+            webhook_retry_max_delay,
+            webhook_cooldown,
+        })
+    }
+
+    /// 遮蔽訊息中的授權碼、API 金鑰與管理 token，上游錯誤訊息可能含有帶授權碼的網址
+    ///
+    /// 離線模式的授權碼為空字串，不需遮蔽。
+    pub fn redact(&self, message: &str) -> String {
+        [
+            Some(&self.token),
+            self.air_api_key.as_ref(),
+            self.admin_token.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|secret| !secret.is_empty())
+        .fold(message.to_owned(), |message, secret| {
+            message.replace(secret.as_str(), "***")
+        })
+    }
+
+    /// CWB 開放資料 dataset 的完整路徑
+    pub fn dataset_url(&self, dataset: &str) -> String {
+        format!(
+            "{}/v1/rest/datastore/{}",
+            self.cwb_api.as_str().trim_end_matches('/'),
+            dataset
+        )
+    }
+
+    /// 環境部 開放資料 dataset 的完整路徑
+    pub fn air_dataset_url(&self, dataset: &str) -> String {
+        format!(
+            "{}/{}",
+            self.air_api.as_str().trim_end_matches('/'),
+            dataset
+        )
+    }
+}
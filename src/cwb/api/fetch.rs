@@ -0,0 +1,233 @@
+use super::super::cache::Cache;
+use super::super::logic::{self, Location};
+use super::super::model::{
+    cwb::earthquake::ReportKind,
+    resp::{AqiReading, Earthquake, Rainfall, Record, Typhoon, UvReading, Warning},
+    Error,
+};
+use super::super::snapshot::Snapshots;
+use super::super::source::{AirSource, WeatherSource};
+use super::super::status::Status;
+use crate::{config::Config, metrics};
+
+use chrono::Utc;
+use hyper::{
+    header::{HeaderValue, AGE, CONTENT_TYPE, WARNING},
+    http::Result as HttpResult,
+    Body, Request, Response, StatusCode,
+};
+use routerify::ext::RequestExt;
+use serde::Serialize;
+use std::{future::Future, sync::Arc, time::Duration};
+
+pub const X_DATA_STALE: &str = "x-data-stale";
+
+/// 取得的資料，上游失敗而改用快照時 `stale` 為快照的時間長度
+pub struct Fetched<T> {
+    pub data: Arc<T>,
+    pub stale: Option<Duration>,
+}
+
+/// 上游錯誤訊息可能含有帶授權碼的網址，記錄前先遮蔽
+fn redact(err: &Error, config: &Config) -> String {
+    config.redact(&err.to_string())
+}
+
+/// 依序使用 快取、上游 與 未超過 `MAX_STALENESS` 的快照，並記錄上游狀態
+async fn fetch<T, F>(req: &Request<Body>, dataset: &str, load: F) -> Result<Fetched<T>, Error>
+where
+    T: Serialize + Send + Sync + 'static,
+    F: Future<Output = Result<T, Error>>,
+{
+    let config = req.data::<Config>().unwrap();
+    let cache = req.data::<Arc<Cache<T>>>().unwrap();
+    let snapshots = req.data::<Arc<Snapshots<T>>>().unwrap();
+    let status = req.data::<Arc<Status>>().unwrap();
+
+    let cached = cache.get(dataset);
+    metrics::observe_cache(dataset, cached.is_some());
+    if let Some(data) = cached {
+        return Ok(Fetched { data, stale: None });
+    }
+
+    let err = match load.await {
+        Ok(data) => {
+            status.success(dataset);
+            let data = cache.insert(dataset, data);
+            snapshots.save(dataset, data.clone());
+            return Ok(Fetched { data, stale: None });
+        }
+        Err(err) => err,
+    };
+
+    status.failure(dataset, &redact(&err, config));
+
+    let snapshot = snapshots.get(dataset).and_then(|snapshot| {
+        let age = (Utc::now() - snapshot.fetched_at)
+            .to_std()
+            .unwrap_or_default();
+        (age <= config.max_staleness).then_some((snapshot, age))
+    });
+
+    match snapshot {
+        Some((snapshot, age)) => {
+            tracing::warn!(dataset, error = %redact(&err, config), age = ?age, "serving stale snapshot");
+            metrics::STALE_RESPONSES.with_label_values(&[dataset]).inc();
+
+            Ok(Fetched {
+                data: snapshot.data,
+                stale: Some(age),
+            })
+        }
+        None => Err(err),
+    }
+}
+
+/// 取得全台測站即時資料，快取、快照與上游狀態以 `config.observation_dataset` 區分
+pub async fn weather_data(req: &Request<Body>) -> Result<Fetched<Vec<Record>>, Error> {
+    let source = req.data::<Arc<dyn WeatherSource>>().unwrap();
+    let dataset = &req.data::<Config>().unwrap().observation_dataset;
+    fetch(req, dataset, logic::get_weather_data(source.as_ref())).await
+}
+
+/// 取得全台雨量站即時資料
+pub async fn rainfall(req: &Request<Body>) -> Result<Fetched<Vec<Rainfall>>, Error> {
+    let source = req.data::<Arc<dyn WeatherSource>>().unwrap();
+    fetch(
+        req,
+        logic::RAINFALL_DATASET,
+        logic::get_rainfall(source.as_ref()),
+    )
+    .await
+}
+
+/// 取得各縣市的天氣特報
+pub async fn warnings(req: &Request<Body>) -> Result<Fetched<Vec<Warning>>, Error> {
+    let source = req.data::<Arc<dyn WeatherSource>>().unwrap();
+    fetch(
+        req,
+        logic::WARNINGS_DATASET,
+        logic::get_warnings(source.as_ref()),
+    )
+    .await
+}
+
+/// 取得發布中的颱風路徑
+pub async fn typhoons(req: &Request<Body>) -> Result<Fetched<Vec<Typhoon>>, Error> {
+    let source = req.data::<Arc<dyn WeatherSource>>().unwrap();
+    fetch(
+        req,
+        logic::TYPHOON_DATASET,
+        logic::get_typhoons(source.as_ref()),
+    )
+    .await
+}
+
+/// 取得指定種類的地震報告
+pub async fn earthquakes(
+    req: &Request<Body>,
+    kind: ReportKind,
+) -> Result<Fetched<Vec<Earthquake>>, Error> {
+    let source = req.data::<Arc<dyn WeatherSource>>().unwrap();
+    fetch(
+        req,
+        kind.dataset(),
+        logic::get_earthquakes(source.as_ref(), kind),
+    )
+    .await
+}
+
+/// 紫外線與空氣品質的資料來源，`cwb` 來源未設定 `AIR_API_KEY` 時為 `None`
+fn air_source(req: &Request<Body>) -> Result<&Arc<dyn AirSource>, Error> {
+    req.data::<Option<Arc<dyn AirSource>>>()
+        .and_then(Option::as_ref)
+        .ok_or_else(|| "AIR_API_KEY is not set, air quality data is disabled".into())
+}
+
+/// 紫外線與空氣品質功能停用時回傳 `501`，請求不會因重試或快照而成功，故不以 `503` 表示
+pub fn air_disabled(req: &Request<Body>) -> Option<HttpResult<Response<Body>>> {
+    let err = air_source(req).err()?;
+
+    Some(
+        Response::builder()
+            .header(CONTENT_TYPE, "text/plain;charset=utf-8")
+            .status(StatusCode::NOT_IMPLEMENTED)
+            .body(Body::from(err.to_string())),
+    )
+}
+
+/// 取得紫外線即時監測資料
+pub async fn uv(req: &Request<Body>) -> Result<Fetched<Vec<UvReading>>, Error> {
+    let source = air_source(req)?;
+    fetch(req, logic::UV_DATASET, logic::get_uv(source.as_ref())).await
+}
+
+/// 取得空氣品質指標資料
+pub async fn air_quality(req: &Request<Body>) -> Result<Fetched<Vec<AqiReading>>, Error> {
+    let source = air_source(req)?;
+    fetch(
+        req,
+        logic::AIR_QUALITY_DATASET,
+        logic::get_air_quality(source.as_ref()),
+    )
+    .await
+}
+
+/// 取得鄉鎮天氣預報
+pub async fn weather_forecast(req: &Request<Body>) -> Result<Fetched<Vec<Location>>, Error> {
+    let source = req.data::<Arc<dyn WeatherSource>>().unwrap();
+    fetch(
+        req,
+        logic::WEATHER_FORECAST_DATASET,
+        logic::get_weather_forecast(source.as_ref()),
+    )
+    .await
+}
+
+/// 輔助資料無法取得時以空資料代替，並標示為過時 (`Age` 為 0)，不讓整個回應失敗
+pub fn or_empty<T: Default>(
+    req: &Request<Body>,
+    dataset: &str,
+    fetched: Result<Fetched<T>, Error>,
+) -> Fetched<T> {
+    fetched.unwrap_or_else(|err| {
+        let config = req.data::<Config>().unwrap();
+        tracing::warn!(dataset, error = %redact(&err, config), "serving without dataset");
+
+        Fetched {
+            data: Arc::new(T::default()),
+            stale: Some(Duration::ZERO),
+        }
+    })
+}
+
+/// 使用快照時加上 `Warning`、`X-Data-Stale` 與 `Age` (快照秒數)
+pub fn mark_stale(
+    res: HttpResult<Response<Body>>,
+    stale: Option<Duration>,
+) -> HttpResult<Response<Body>> {
+    let (mut res, age) = match (res, stale) {
+        (Ok(res), Some(age)) => (res, age),
+        (res, _) => return res,
+    };
+
+    let headers = res.headers_mut();
+    headers.insert(
+        WARNING,
+        HeaderValue::from_static("110 - \"Response is Stale\""),
+    );
+    headers.insert(X_DATA_STALE, HeaderValue::from_static("true"));
+    headers.insert(AGE, HeaderValue::from(age.as_secs()));
+
+    Ok(res)
+}
+
+/// 上游失敗且沒有可用的快照
+pub fn unavailable(err: &Error) -> HttpResult<Response<Body>> {
+    tracing::error!(error = %err, "upstream unavailable and no usable snapshot");
+
+    Response::builder()
+        .header(CONTENT_TYPE, "text/plain;charset=utf-8")
+        .status(StatusCode::SERVICE_UNAVAILABLE)
+        .body(Body::from("weather data is temporarily unavailable"))
+}
@@ -0,0 +1,22 @@
+/// 依 key 取得可篩選的欄位數值，缺值為 `None`
+pub type Field<T> = fn(&T) -> Option<f32>;
+
+/// 比較欄位數值與門檻
+pub type Check = fn(f32, f32) -> bool;
+
+/// 篩選條件，格式為 `min_<KEY>=<value>` 或 `max_<KEY>=<value>`，`field_by` 依 KEY 取得欄位，
+/// 例如 `min_HEAT_INDEX=32`、`min_HOUR_3=100`、`max_AQI=50`
+pub fn filter_by<T>(
+    key: &str,
+    field_by: fn(&str) -> Option<Field<T>>,
+) -> Option<(Check, Field<T>)> {
+    let (bound, key) = key.split_once('_')?;
+
+    let check: Check = match bound {
+        "min" => |value, threshold| value >= threshold,
+        "max" => |value, threshold| value <= threshold,
+        _ => return None,
+    };
+
+    field_by(key).map(|field| (check, field))
+}
@@ -0,0 +1,94 @@
+use super::super::logic;
+use super::super::model::{locale, resp::Air};
+use super::fetch;
+use super::filter::filter_by;
+use super::language::get_language;
+use super::response::{bad_request, response};
+
+use hyper::{http::Result, Body, Request, Response};
+use itertools::Itertools;
+use percent_encoding::percent_decode_str;
+use querystring::querify;
+
+/// 各氣象測站最近的紫外線與空氣品質監測站觀測，附近沒有監測站的測站不列入結果；
+/// 紫外線或空氣品質無法取得時以另一項回傳，並標示為過時
+pub async fn get_air(req: Request<Body>) -> Result<Response<Body>> {
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    if let Some(res) = fetch::air_disabled(&req) {
+        return res;
+    }
+
+    // 缺少紫外線或空氣品質其中一項時仍回傳另一項，以過時標示資料不完整
+    let (records, uv, air_quality) = tokio::join!(
+        async {
+            fetch::weather_data(&req)
+                .await
+                .map_err(|err| fetch::unavailable(&err))
+        },
+        async {
+            let fetched = fetch::uv(&req).await;
+            fetch::or_empty(&req, logic::UV_DATASET, fetched)
+        },
+        async {
+            let fetched = fetch::air_quality(&req).await;
+            fetch::or_empty(&req, logic::AIR_QUALITY_DATASET, fetched)
+        },
+    );
+
+    let records = match records {
+        Ok(fetched) => fetched,
+        Err(res) => return res,
+    };
+
+    let stale = records.stale.max(uv.stale).max(air_quality.stale);
+
+    let mut data: Vec<Air> = logic::to_air(&records.data, &uv.data, &air_quality.data);
+    data.retain(|item| item.reading.uv.is_some() || item.reading.air_quality.is_some());
+
+    if let Some(queries) = req.uri().query() {
+        for (key, value) in querify(queries) {
+            // 縣市名稱為中文，需先解碼
+            if key == "city" {
+                let city = locale::normalize(&percent_decode_str(value).decode_utf8_lossy());
+                data.retain(|item| locale::normalize(&item.city) == city);
+            }
+
+            if key == "order_by" {
+                if let Some(cmp) = logic::air_order_by(value) {
+                    data = data.into_iter().sorted_by(&cmp).collect();
+                }
+            }
+
+            if let Some((check, field)) = filter_by(key, logic::air_field) {
+                let threshold: f32 = match value.parse() {
+                    Ok(threshold) => threshold,
+                    Err(_) => return bad_request(format!("invalid value of {}: {}", key, value)),
+                };
+
+                data.retain(|item| {
+                    field(&item.reading).is_some_and(|value| check(value, threshold))
+                });
+            }
+
+            if key == "limit" {
+                let value: usize = match value.parse() {
+                    Ok(value) => value,
+                    Err(_) => return bad_request(format!("invalid value of limit: {}", value)),
+                };
+
+                data.truncate(value);
+            }
+        }
+    }
+
+    let data: Vec<_> = data
+        .into_iter()
+        .map(|item| item.with_language(language))
+        .collect();
+
+    fetch::mark_stale(response(data, None, language), stale)
+}
@@ -0,0 +1,74 @@
+use super::super::logic::astronomy;
+use super::super::model::resp::Position;
+use super::language::get_language;
+use super::response::{bad_request, response};
+
+use chrono::NaiveDate;
+use hyper::{http::Result, Body, Request, Response};
+use querystring::querify;
+
+/// 解析座標，超出範圍視為錯誤
+fn coordinate(key: &str, value: &str, limit: f32) -> std::result::Result<f32, String> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|value| (-limit..=limit).contains(value))
+        .ok_or_else(|| format!("invalid value of {}: {}", key, value))
+}
+
+/// 指定座標與日期的日出、日落、民用曙暮光與月相，於本機計算不需向上游請求
+///
+/// `lat`、`lon` 為必填，`date` 格式為 yyyy-MM-dd，限 1900 ~ 2100 年，未指定時為台灣時間的今天。
+pub async fn get_astro(req: Request<Body>) -> Result<Response<Body>> {
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let mut latitude = None;
+    let mut longitude = None;
+    let mut date = astronomy::today();
+
+    if let Some(queries) = req.uri().query() {
+        for (key, value) in querify(queries) {
+            if key == "lat" {
+                match coordinate(key, value, 90.0) {
+                    Ok(value) => latitude = Some(value),
+                    Err(err) => return bad_request(err),
+                }
+            }
+
+            if key == "lon" {
+                match coordinate(key, value, 180.0) {
+                    Ok(value) => longitude = Some(value),
+                    Err(err) => return bad_request(err),
+                }
+            }
+
+            if key == "date" {
+                date = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                    Ok(date) => date,
+                    Err(_) => return bad_request(format!("invalid value of date: {}", value)),
+                };
+            }
+        }
+    }
+
+    let location = match (latitude, longitude) {
+        (Some(latitude), Some(longitude)) => Position {
+            latitude,
+            longitude,
+        },
+        _ => return bad_request("lat and lon are required".to_owned()),
+    };
+
+    match astronomy::astro(date, &location) {
+        Some(astro) => response(astro.with_language(language), None, language),
+        None => bad_request(format!(
+            "date out of supported range {}-01-01 ~ {}-12-31: {}",
+            astronomy::SUPPORTED_YEARS.start(),
+            astronomy::SUPPORTED_YEARS.end(),
+            date
+        )),
+    }
+}
@@ -0,0 +1,82 @@
+use super::super::model::{cwb::earthquake::ReportKind, resp::Earthquake};
+use super::fetch;
+use super::language::get_language;
+use super::response::{bad_request, response};
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use hyper::{http::Result, Body, Request, Response};
+use percent_encoding::percent_decode_str;
+use querystring::querify;
+
+/// `since` 可為 RFC 3339 時間，或 yyyy-MM-dd 表示台灣時間當日 0 時
+fn parse_since(value: &str) -> Option<DateTime<FixedOffset>> {
+    let value = percent_decode_str(value).decode_utf8_lossy();
+
+    DateTime::parse_from_rfc3339(&value).ok().or_else(|| {
+        let taiwan = FixedOffset::east_opt(8 * 3600).unwrap();
+        NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)?
+            .and_local_timezone(taiwan)
+            .single()
+    })
+}
+
+/// 顯著有感與小區域有感地震報告，依發震時間由新到舊
+pub async fn get_earthquakes(req: Request<Body>) -> Result<Response<Body>> {
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let mut data: Vec<Earthquake> = Vec::new();
+    let mut stale = None;
+    for kind in ReportKind::ALL {
+        match fetch::earthquakes(&req, kind).await {
+            Ok(fetched) => {
+                data.extend(fetched.data.iter().cloned());
+                stale = stale.max(fetched.stale);
+            }
+            Err(err) => return fetch::unavailable(&err),
+        }
+    }
+    data.sort_by_key(|item| std::cmp::Reverse(item.origin_time));
+
+    if let Some(queries) = req.uri().query() {
+        for (key, value) in querify(queries) {
+            if key == "since" {
+                let since = match parse_since(value) {
+                    Some(since) => since,
+                    None => return bad_request(format!("invalid value of since: {}", value)),
+                };
+
+                data.retain(|item| item.origin_time >= since);
+            }
+
+            if key == "min_magnitude" {
+                let threshold: f32 = match value.parse() {
+                    Ok(threshold) => threshold,
+                    Err(_) => return bad_request(format!("invalid value of {}: {}", key, value)),
+                };
+
+                data.retain(|item| item.magnitude >= threshold);
+            }
+
+            if key == "limit" {
+                let value: usize = match value.parse() {
+                    Ok(value) => value,
+                    Err(_) => return bad_request(format!("invalid value of limit: {}", value)),
+                };
+
+                data.truncate(value);
+            }
+        }
+    }
+
+    let data: Vec<_> = data
+        .into_iter()
+        .map(|item| item.with_language(language))
+        .collect();
+
+    fetch::mark_stale(response(data, None, language), stale)
+}
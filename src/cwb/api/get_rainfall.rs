@@ -0,0 +1,122 @@
+use super::super::logic;
+use super::super::model::{
+    locale::Language,
+    resp::{RainLevel, Rainfall},
+    unit::UnitSystem,
+};
+use super::fetch;
+use super::filter::filter_by;
+use super::get_weather_data::elevation_group;
+use super::language::get_language;
+use super::response::{bad_request, response};
+use super::units::get_units;
+
+use hyper::{http::Result, Body, Request, Response};
+use itertools::Itertools;
+use querystring::querify;
+
+/// 每組取 24 小時累積雨量最高的測站，`ELEV` 依高度、`CITY` 依縣市分組；
+/// `data` 已轉換成 `units`，高度維持公尺以便分組
+fn group_by(
+    value: &str,
+    data: Vec<Rainfall>,
+    units: UnitSystem,
+    language: Language,
+) -> Result<Response<Body>> {
+    let key: fn(&Rainfall) -> Option<String> = match value {
+        "ELEV" => |item| {
+            item.altitude
+                .map(|altitude| elevation_group(altitude).to_owned())
+        },
+        "CITY" => |item| Some(item.city.clone()),
+        _ => return response(Vec::<()>::new(), Some(units), language),
+    };
+    let wettest = logic::rain_order_by("HOUR_24").unwrap();
+
+    let result: Vec<_> = data
+        .iter()
+        .filter_map(|item| key(item).map(|group| (group, item)))
+        .into_grouping_map()
+        .min_by(|_, a, b| wettest(a, b))
+        .into_iter()
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(group, item)| {
+            let item = item.clone().with_language(language);
+            (group, item)
+        })
+        .collect();
+
+    response(result, Some(units), language)
+}
+
+pub async fn get_rainfall(req: Request<Body>) -> Result<Response<Body>> {
+    let units = match get_units(&req) {
+        Ok(units) => units,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let (data, stale) = match fetch::rainfall(&req).await {
+        Ok(fetched) => (fetched.data.as_ref().clone(), fetched.stale),
+        Err(err) => return fetch::unavailable(&err),
+    };
+
+    // 先轉換單位，篩選門檻即以 `units` 比較；分級以毫米判定，不受影響
+    let mut data: Vec<_> = data
+        .into_iter()
+        .map(|item| item.with_units(units))
+        .collect();
+
+    if let Some(queries) = req.uri().query() {
+        for (key, value) in querify(queries) {
+            if key == "group_by" {
+                return fetch::mark_stale(group_by(value, data, units, language), stale);
+            }
+
+            if key == "order_by" {
+                if let Some(cmp) = logic::rain_order_by(value) {
+                    data = data.into_iter().sorted_by(&cmp).collect();
+                }
+            }
+
+            if let Some((check, field)) = filter_by(key, logic::rainfall_field) {
+                let threshold: f32 = match value.parse() {
+                    Ok(threshold) => threshold,
+                    Err(_) => return bad_request(format!("invalid value of {}: {}", key, value)),
+                };
+
+                data.retain(|item| field(item).is_some_and(|value| check(value, threshold)));
+            }
+
+            // 達到指定分級 (含) 以上的測站
+            if key == "level" {
+                let level: RainLevel = match value.parse() {
+                    Ok(level) => level,
+                    Err(err) => return bad_request(format!("{}", err)),
+                };
+
+                data.retain(|item| item.level.is_some_and(|item| item >= level));
+            }
+
+            if key == "limit" {
+                let value: usize = match value.parse() {
+                    Ok(value) => value,
+                    Err(_) => return bad_request(format!("invalid value of limit: {}", value)),
+                };
+
+                data = data.into_iter().take(value).collect();
+            }
+        }
+    }
+
+    let data: Vec<_> = data
+        .into_iter()
+        .map(|item| item.with_language(language))
+        .collect();
+
+    fetch::mark_stale(response(data, Some(units), language), stale)
+}
@@ -0,0 +1,74 @@
+use super::super::logic;
+use super::super::model::{
+    locale,
+    resp::{Typhoon, Warning},
+};
+use super::fetch;
+use super::language::get_language;
+use super::response::{bad_request, response};
+use super::units::get_units;
+
+use chrono::Utc;
+use hyper::{http::Result, Body, Request, Response};
+use percent_encoding::percent_decode_str;
+use querystring::querify;
+use serde::Serialize;
+
+/// 生效中的特報與颱風
+#[derive(Serialize)]
+struct Warnings {
+    warnings: Vec<Warning>,
+    typhoons: Vec<Typhoon>,
+}
+
+/// 各縣市生效中的特報與發布中的颱風，`city` 可指定縣市
+pub async fn get_warnings(req: Request<Body>) -> Result<Response<Body>> {
+    let units = match get_units(&req) {
+        Ok(units) => units,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let warnings = match fetch::warnings(&req).await {
+        Ok(fetched) => fetched,
+        Err(err) => return fetch::unavailable(&err),
+    };
+
+    let typhoons = match fetch::typhoons(&req).await {
+        Ok(fetched) => fetched,
+        Err(err) => return fetch::unavailable(&err),
+    };
+
+    let mut active = logic::active_warnings(&warnings.data, Utc::now().fixed_offset());
+
+    if let Some(queries) = req.uri().query() {
+        for (key, value) in querify(queries) {
+            // 縣市名稱為中文，需先解碼
+            if key == "city" {
+                let city = locale::normalize(&percent_decode_str(value).decode_utf8_lossy());
+                active.retain(|warning| locale::normalize(&warning.city) == city);
+            }
+        }
+    }
+
+    let data = Warnings {
+        warnings: active
+            .into_iter()
+            .map(|warning| warning.with_language(language))
+            .collect(),
+        typhoons: typhoons
+            .data
+            .iter()
+            .map(|typhoon| typhoon.clone().with_units(units))
+            .collect(),
+    };
+
+    fetch::mark_stale(
+        response(data, Some(units), language),
+        warnings.stale.max(typhoons.stale),
+    )
+}
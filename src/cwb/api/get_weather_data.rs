@@ -1,91 +1,182 @@
 use super::super::logic;
-use super::super::model::resp::Record;
-use std::cmp::Ordering;
-
-use hyper::{header::CONTENT_TYPE, http::Result, Body, Request, Response, StatusCode};
+use super::super::model::{
+    locale::{self, Language},
+    resp::Record,
+    unit::UnitSystem,
+};
+use super::fetch;
+use super::filter::{filter_by, Field};
+use super::language::get_language;
+use super::response::{bad_request, response};
+use super::units::get_units;
+
+use hyper::{http::Result, Body, Request, Response};
 use itertools::Itertools;
 use querystring::querify;
-use serde::Serialize;
-
-fn sort_by_h24r(a: &Record, b: &Record) -> Ordering {
-    PartialOrd::partial_cmp(&b.precipitation_per_day, &a.precipitation_per_day).unwrap()
-}
 
-fn sort_by_temp(a: &Record, b: &Record) -> Ordering {
-    PartialOrd::partial_cmp(&a.temperature, &b.temperature).unwrap()
+/// 依 key 取得可篩選的欄位數值
+pub(super) fn field_by(key: &str) -> Option<Field<Record>> {
+    let field: Field<Record> = match key {
+        "TEMP" => |item| Some(item.temperature),
+        "H_24R" => |item| Some(item.precipitation_per_day),
+        "DEW_POINT" => |item| item.dew_point,
+        "HEAT_INDEX" => |item| item.heat_index,
+        "HUMIDEX" => |item| item.humidex,
+        "WIND_CHILL" => |item| item.wind_chill,
+        _ => return None,
+    };
+
+    Some(field)
 }
 
-fn not_sort(_: &Record, _: &Record) -> Ordering {
-    Ordering::Equal
-}
-
-fn response<T>(data: Vec<T>) -> Result<Response<Body>>
-where
-    T: Serialize,
-{
-    let payload =
-        serde_json::to_string_pretty(&data).expect("error occured when serialize result payload");
-
-    Response::builder()
-        .header(CONTENT_TYPE, "application/json;charset=utf-8")
-        .status(StatusCode::OK)
-        .body(Body::from(payload))
+/// 高度分組，每 500 公尺一組，3000 公尺以上為一組
+pub(super) fn elevation_group(altitude: f32) -> &'static str {
+    match altitude as i32 {
+        0..=500 => "0-500",
+        501..=1000 => "500-1000",
+        1001..=1500 => "1000-1500",
+        1501..=2000 => "1500-2000",
+        2001..=2500 => "2000-2500",
+        2501..=3000 => "2500-3000",
+        _ => "> 3000",
+    }
 }
 
-fn group_by(value: &str, data: Vec<Record>) -> Result<Response<Body>> {
+/// `data` 已轉換成 `units`，高度維持公尺以便分組
+fn group_by(
+    value: &str,
+    data: Vec<Record>,
+    units: UnitSystem,
+    language: Language,
+) -> Result<Response<Body>> {
     let mut result = Vec::new();
 
     if value == "ELEV" {
         result = data
             .iter()
-            .into_grouping_map_by(|item| match item.altitude as i32 {
-                0..=500 => "0-500",
-                501..=1000 => "500-1000",
-                1001..=1500 => "1000-1500",
-                1501..=2000 => "1500-2000",
-                2001..=2500 => "2000-2500",
-                2501..=3000 => "2500-3000",
-                _ => "> 3000",
-            })
-            .min_by(|_, a, b| sort_by_temp(a, b))
+            .into_grouping_map_by(|item| elevation_group(item.altitude))
+            .min_by(|_, a, b| logic::sort_by_temp(a, b))
             .into_iter()
+            .map(|(group, item)| {
+                let item = item.clone().with_language(language);
+                (group, item)
+            })
             .collect();
     }
 
-    response(result)
+    response(result, Some(units), language)
 }
 
 pub async fn get_weather_data(req: Request<Body>) -> Result<Response<Body>> {
-    let mut data = logic::get_weather_data()
-        .await
-        .expect("error occured when get weather data");
+    let units = match get_units(&req) {
+        Ok(units) => units,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let (data, mut stale) = match fetch::weather_data(&req).await {
+        Ok(fetched) => (fetched.data.as_ref().clone(), fetched.stale),
+        Err(err) => return fetch::unavailable(&err),
+    };
+
+    // 先轉換單位，篩選門檻即以 `units` 比較
+    let mut data: Vec<_> = data
+        .into_iter()
+        .map(|item| item.with_units(units))
+        .collect();
 
     if let Some(queries) = req.uri().query() {
         // @TODO: change to pattern matching
         for (key, value) in querify(queries) {
             if key == "group_by" {
-                return group_by(value, data);
+                return fetch::mark_stale(group_by(value, data, units, language), stale);
             }
 
             if key == "order_by" {
-                let cmp = match value {
-                    "TEMP" => sort_by_temp,
-                    "H_24R" => sort_by_h24r,
-                    _ => not_sort,
+                if let Some(cmp) = logic::order_by(value) {
+                    data = data.into_iter().sorted_by(cmp).collect();
+                }
+            }
+
+            // 門檻與回傳數值同樣使用 `units` 指定的單位制
+            if let Some((check, field)) = filter_by(key, field_by) {
+                let threshold: f32 = match value.parse() {
+                    Ok(threshold) => threshold,
+                    Err(_) => return bad_request(format!("invalid value of {}: {}", key, value)),
+                };
+
+                data.retain(|item| field(item).is_some_and(|value| check(value, threshold)));
+            }
+
+            // `warned=true` 只保留有生效特報的縣市，`warned=false` 只保留沒有特報者
+            if key == "warned" {
+                let warned: bool = match value.parse() {
+                    Ok(warned) => warned,
+                    Err(_) => return bad_request(format!("invalid value of warned: {}", value)),
                 };
 
-                data = data.into_iter().sorted_by(cmp).collect();
+                let warnings = match fetch::warnings(&req).await {
+                    Ok(fetched) => fetched,
+                    Err(err) => return fetch::unavailable(&err),
+                };
+
+                let cities = logic::warned_cities(&warnings.data);
+                data.retain(|item| cities.contains(&locale::normalize(&item.city)) == warned);
+                stale = stale.max(warnings.stale);
+            }
+
+            // `air=true` 加上最近的紫外線與空氣品質監測站觀測
+            if key == "air" {
+                let air: bool = match value.parse() {
+                    Ok(air) => air,
+                    Err(_) => return bad_request(format!("invalid value of air: {}", value)),
+                };
+
+                if air {
+                    if let Some(res) = fetch::air_disabled(&req) {
+                        return res;
+                    }
+
+                    let uv = match fetch::uv(&req).await {
+                        Ok(fetched) => fetched,
+                        Err(err) => return fetch::unavailable(&err),
+                    };
+
+                    let air_quality = match fetch::air_quality(&req).await {
+                        Ok(fetched) => fetched,
+                        Err(err) => return fetch::unavailable(&err),
+                    };
+
+                    for item in data.iter_mut() {
+                        item.air = Some(logic::air_reading(
+                            &item.location,
+                            &uv.data,
+                            &air_quality.data,
+                        ));
+                    }
+                    stale = stale.max(uv.stale).max(air_quality.stale);
+                }
             }
 
             if key == "limit" {
-                let value: usize = value
-                    .parse()
-                    .expect("error occured when parsing limit value");
+                let value: usize = match value.parse() {
+                    Ok(value) => value,
+                    Err(_) => return bad_request(format!("invalid value of limit: {}", value)),
+                };
 
                 data = data.into_iter().take(value).collect();
             }
         }
     }
 
-    response(data)
+    let data: Vec<_> = data
+        .into_iter()
+        .map(|item| item.with_language(language))
+        .collect();
+
+    fetch::mark_stale(response(data, Some(units), language), stale)
 }
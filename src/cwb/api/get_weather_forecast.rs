@@ -1,57 +1,77 @@
-use super::super::logic;
-use super::super::model::resp::Forecast;
-
-use hyper::{header::CONTENT_TYPE, http::Result, Body, Request, Response, StatusCode};
-use serde::Serialize;
-
-fn response<T>(data: T) -> Result<Response<Body>>
-where
-    T: Serialize,
-{
-    let payload =
-        serde_json::to_string_pretty(&data).expect("error occured when serialize result payload");
-
-    Response::builder()
-        .header(CONTENT_TYPE, "application/json;charset=utf-8")
-        .status(StatusCode::OK)
-        .body(Body::from(payload))
-}
+use super::super::logic::{self, to_forecast};
+use super::super::model::locale;
+use super::fetch;
+use super::language::get_language;
+use super::response::{bad_request, response};
+use super::units::get_units;
+
+use hyper::{http::Result, Body, Request, Response, StatusCode};
+use querystring::querify;
+
+pub async fn get_weather_forecast(req: Request<Body>) -> Result<Response<Body>> {
+    let units = match get_units(&req) {
+        Ok(units) => units,
+        Err(err) => return bad_request(err.to_string()),
+    };
 
-pub async fn get_weather_forecast(_req: Request<Body>) -> Result<Response<Body>> {
-    let data = logic::get_weather_forecast()
-        .await
-        .expect("error occured when get weather data");
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let fetched = match fetch::weather_forecast(&req).await {
+        Ok(fetched) => fetched,
+        Err(err) => return fetch::unavailable(&err),
+    };
 
     // find location
-    let location = &data[0];
-    let name = location.name.clone();
-    let temperatures = &location.temperatures;
-
-    // get max temperature
-    let max = temperatures
-        .into_iter()
-        .max_by(|a, b| PartialOrd::partial_cmp(&a.1.max, &b.1.max).unwrap())
-        .map(|(_, temperature)| temperature.max)
-        .unwrap();
-
-    // get min temperature
-    let min = temperatures
-        .into_iter()
-        .min_by(|a, b| PartialOrd::partial_cmp(&a.1.min, &b.1.min).unwrap())
-        .map(|(_, temperature)| temperature.min)
-        .unwrap();
-
-    // get difference per day
-    let diff = temperatures
-        .into_iter()
-        .map(|(_, group)| group.max - group.min)
-        .reduce(f32::max)
-        .unwrap();
-
-    response(Forecast {
-        name,
-        max_temperature: max,
-        min_temperature: min,
-        temperature_difference_per_day: diff,
-    })
+    let forecast = match fetched
+        .data
+        .first()
+        .and_then(|item| to_forecast(item, language))
+    {
+        Some(forecast) => forecast,
+        None => return fetch::unavailable(&"forecast dataset has no location".into()),
+    };
+
+    let mut stale = fetched.stale;
+
+    // `warned=true` 時縣市沒有生效特報、`warned=false` 時縣市有特報，皆回傳 204
+    let warned = req.uri().query().and_then(|queries| {
+        querify(queries)
+            .into_iter()
+            .find(|(key, _)| *key == "warned")
+    });
+    if let Some((_, value)) = warned {
+        let warned: bool = match value.parse() {
+            Ok(warned) => warned,
+            Err(_) => return bad_request(format!("invalid value of warned: {}", value)),
+        };
+
+        let warnings = match fetch::warnings(&req).await {
+            Ok(fetched) => fetched,
+            Err(err) => return fetch::unavailable(&err),
+        };
+        stale = stale.max(warnings.stale);
+
+        let city = fetched
+            .data
+            .first()
+            .map(|item| locale::normalize(&item.city));
+        let is_warned =
+            city.is_some_and(|city| logic::warned_cities(&warnings.data).contains(&city));
+        if is_warned != warned {
+            return fetch::mark_stale(
+                Response::builder()
+                    .status(StatusCode::NO_CONTENT)
+                    .body(Body::empty()),
+                stale,
+            );
+        }
+    }
+
+    fetch::mark_stale(
+        response(forecast.with_units(units), Some(units), language),
+        stale,
+    )
 }
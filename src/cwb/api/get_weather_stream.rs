@@ -0,0 +1,276 @@
+use super::super::feed::{Change, Feed};
+use super::super::model::{
+    locale::{self, Language},
+    resp::{Record, RecordDelta},
+    unit::UnitSystem,
+};
+use super::fetch;
+use super::language::get_language;
+use super::response::bad_request;
+use super::units::{get_units, units_header, X_UNITS};
+use crate::{config::Config, shutdown::Shutdown};
+
+use hyper::{
+    body::{Bytes, Sender},
+    header::{CACHE_CONTROL, CONTENT_LANGUAGE, CONTENT_TYPE},
+    http::Result,
+    Body, Request, Response, StatusCode,
+};
+use percent_encoding::percent_decode_str;
+use querystring::querify;
+use routerify::ext::RequestExt;
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+const LAST_EVENT_ID: &str = "last-event-id";
+
+/// 每個連線的篩選條件，未指定者不篩選
+#[derive(Default)]
+struct Filter {
+    /// `city`，以逗號分隔的縣市
+    cities: Vec<String>,
+    /// `station`，以逗號分隔的測站名稱
+    stations: Vec<String>,
+    /// `bbox`，`<min_lon>,<min_lat>,<max_lon>,<max_lat>`
+    bbox: Option<[f32; 4]>,
+}
+
+impl Filter {
+    fn from_queries(queries: &str) -> std::result::Result<Filter, String> {
+        let mut filter = Filter::default();
+        let list = |value: &str| -> Vec<String> {
+            percent_decode_str(value)
+                .decode_utf8_lossy()
+                .split(',')
+                .map(locale::normalize)
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+
+        for (key, value) in querify(queries) {
+            // 縣市與測站名稱為中文，需先解碼
+            if key == "city" {
+                filter.cities = list(value);
+            }
+
+            if key == "station" {
+                filter.stations = list(value);
+            }
+
+            if key == "bbox" {
+                let invalid = || format!("invalid value of bbox: {}", value);
+                let bounds = percent_decode_str(value)
+                    .decode_utf8_lossy()
+                    .split(',')
+                    .map(|bound| bound.trim().parse::<f32>())
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map_err(|_| invalid())?;
+
+                match bounds[..] {
+                    [min_lon, min_lat, max_lon, max_lat]
+                        if min_lon <= max_lon && min_lat <= max_lat =>
+                    {
+                        filter.bbox = Some([min_lon, min_lat, max_lon, max_lat]);
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+
+        Ok(filter)
+    }
+
+    fn matches(&self, record: &Record) -> bool {
+        let city = self.cities.is_empty() || self.cities.contains(&locale::normalize(&record.city));
+        let station =
+            self.stations.is_empty() || self.stations.contains(&locale::normalize(&record.name));
+        let bbox = self
+            .bbox
+            .is_none_or(|[min_lon, min_lat, max_lon, max_lat]| {
+                let location = &record.location;
+                (min_lon..=max_lon).contains(&location.longitude)
+                    && (min_lat..=max_lat).contains(&location.latitude)
+            });
+
+        city && station && bbox
+    }
+}
+
+/// 單一連線的輸出設定
+struct Connection {
+    filter: Filter,
+    units: UnitSystem,
+    language: Language,
+}
+
+impl Connection {
+    fn records<'a>(&self, records: impl IntoIterator<Item = &'a Record>) -> Vec<Record> {
+        records
+            .into_iter()
+            .filter(|record| self.filter.matches(record))
+            .map(|record| {
+                record
+                    .clone()
+                    .with_units(self.units)
+                    .with_language(self.language)
+            })
+            .collect()
+    }
+
+    /// 篩選後的變動，沒有符合的測站時為 `None`
+    fn delta(&self, change: &Change) -> Option<Bytes> {
+        let delta = RecordDelta {
+            updated: self.records(&change.updated),
+            removed: change
+                .removed
+                .iter()
+                .filter(|record| self.filter.matches(record))
+                .map(|record| record.key().to_string())
+                .collect(),
+        };
+
+        if delta.updated.is_empty() && delta.removed.is_empty() {
+            return None;
+        }
+
+        Some(event(change.id, "update", &delta))
+    }
+}
+
+/// SSE 事件，資料為單行 JSON
+fn event<T>(id: u64, name: &str, data: &T) -> Bytes
+where
+    T: Serialize,
+{
+    let payload =
+        serde_json::to_string(data).expect("error occurred when serializing event payload");
+    Bytes::from(format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        id, name, payload
+    ))
+}
+
+/// 推送至連線，連線關閉、落後過多或服務關閉時結束
+async fn forward(
+    mut sender: Sender,
+    initial: Vec<Bytes>,
+    mut subscription: tokio::sync::broadcast::Receiver<Arc<Change>>,
+    connection: Connection,
+    config: &Config,
+    shutdown: Shutdown,
+) {
+    for chunk in initial {
+        if sender.send_data(chunk).await.is_err() {
+            return;
+        }
+    }
+
+    let mut heartbeat = tokio::time::interval(config.heartbeat_interval);
+    heartbeat.tick().await;
+
+    loop {
+        let chunk = tokio::select! {
+            received = subscription.recv() => match received {
+                Ok(change) => match connection.delta(&change) {
+                    Some(chunk) => chunk,
+                    None => continue,
+                },
+                // 落後超過重播緩衝時結束連線，由客戶端以 `Last-Event-ID` 重新連線
+                Err(RecvError::Lagged(_)) | Err(RecvError::Closed) => break,
+            },
+            _ = heartbeat.tick() => Bytes::from_static(b": heartbeat\n\n"),
+            _ = shutdown.wait() => break,
+        };
+
+        if sender.send_data(chunk).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// 觀測資料變動的 Server-Sent Events
+///
+/// 連線時先送出 `snapshot` 事件 (符合條件的所有測站)，之後背景更新發現變動時送出 `update` 事件，
+/// 內容為變動與消失的測站；帶有 `Last-Event-ID` 且仍在重播緩衝內時，改為補送期間的 `update`。
+/// 可用 `city`、`station`、`bbox` 篩選，並支援 `units` 與 `lang`。
+pub async fn get_weather_stream(req: Request<Body>) -> Result<Response<Body>> {
+    let units = match get_units(&req) {
+        Ok(units) => units,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let filter = match Filter::from_queries(req.uri().query().unwrap_or_default()) {
+        Ok(filter) => filter,
+        Err(err) => return bad_request(err),
+    };
+    let connection = Connection {
+        filter,
+        units,
+        language,
+    };
+
+    // 格式錯誤的 `Last-Event-ID` 視同未提供
+    let last_event_id = req
+        .headers()
+        .get(LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok());
+
+    let feed = req.data::<Arc<Feed>>().unwrap();
+    let subscription = feed.subscribe(last_event_id);
+
+    let (initial, stale) = match subscription.replay {
+        Some(changes) => (
+            changes
+                .iter()
+                .filter_map(|change| connection.delta(change))
+                .collect(),
+            None,
+        ),
+        None => {
+            let records = match fetch::weather_data(&req).await {
+                Ok(fetched) => fetched,
+                Err(err) => return fetch::unavailable(&err),
+            };
+            feed.seed(&records.data);
+
+            let snapshot = connection.records(records.data.iter());
+            (
+                vec![event(subscription.last_id, "snapshot", &snapshot)],
+                records.stale,
+            )
+        }
+    };
+
+    let config = req.data::<Config>().unwrap().clone();
+    let shutdown = req.data::<Shutdown>().unwrap().clone();
+    let (sender, body) = Body::channel();
+
+    tokio::spawn(async move {
+        forward(
+            sender,
+            initial,
+            subscription.receiver,
+            connection,
+            &config,
+            shutdown,
+        )
+        .await
+    });
+
+    let res = Response::builder()
+        .header(CONTENT_TYPE, "text/event-stream;charset=utf-8")
+        .header(CACHE_CONTROL, "no-cache")
+        .header(X_UNITS, units_header(units))
+        .header(CONTENT_LANGUAGE, language.tag())
+        .status(StatusCode::OK)
+        .body(body);
+
+    fetch::mark_stale(res, stale)
+}
@@ -0,0 +1,482 @@
+use super::super::feed::{Change, Feed};
+use super::super::model::{
+    locale::{self, Language},
+    resp::{Op, Record},
+    unit::UnitSystem,
+};
+use super::fetch;
+use super::get_weather_data::field_by;
+use super::language::get_language;
+use super::response::bad_request;
+use super::units::get_units;
+use crate::{config::Config, shutdown::Shutdown};
+
+use futures::{SinkExt, StreamExt};
+use hyper::{
+    header::{
+        HeaderValue, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION,
+        UPGRADE,
+    },
+    http::Result,
+    upgrade::Upgraded,
+    Body, Request, Response, StatusCode,
+};
+use routerify::ext::RequestExt;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tokio_tungstenite::{
+    tungstenite::{handshake::derive_accept_key, protocol::Role, Message},
+    WebSocketStream,
+};
+
+/// 每個連線可註冊的門檻規則數上限
+const MAX_RULES: usize = 32;
+
+/// 門檻規則，例如 `TEMP > 35`，數值為連線指定的單位制
+#[derive(Serialize, Debug, Clone)]
+struct Rule {
+    id: String,
+    /// 欄位，同 `/weather` 的 `min_`、`max_` 篩選，例如 `TEMP`、`H_24R`
+    field: String,
+    op: Op,
+    value: f32,
+}
+
+/// 客戶端送出的訊息
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Command {
+    /// 訂閱測站 (代碼或名稱) 與鄉鎮，之後推送其變動
+    Subscribe {
+        #[serde(default)]
+        stations: Vec<String>,
+        #[serde(default)]
+        towns: Vec<String>,
+    },
+    /// 取消訂閱
+    Unsubscribe {
+        #[serde(default)]
+        stations: Vec<String>,
+        #[serde(default)]
+        towns: Vec<String>,
+    },
+    /// 新增或取代同 `id` 的門檻規則
+    AddRule {
+        id: String,
+        field: String,
+        op: Op,
+        value: f32,
+    },
+    /// 移除門檻規則
+    RemoveRule { id: String },
+}
+
+/// 伺服器送出的訊息
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Outgoing<'a> {
+    /// 每次處理訊息後的訂閱狀態
+    Subscriptions {
+        stations: &'a BTreeSet<String>,
+        towns: &'a BTreeSet<String>,
+        rules: Vec<&'a Rule>,
+    },
+    /// 訂閱的測站資料，`id` 為觀測變動的事件編號，訂閱時送出的目前資料為 `None`；
+    /// `removed` 為不再出現的測站代碼，沒有代碼時為測站名稱
+    Update {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<u64>,
+        records: Vec<Record>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        removed: Vec<String>,
+    },
+    /// 測站數值由未達門檻變為達到門檻
+    Alert {
+        rule: &'a str,
+        field: &'a str,
+        op: Op,
+        threshold: f32,
+        value: f32,
+        record: Box<Record>,
+    },
+    /// 無法處理的訊息
+    Error { message: String },
+}
+
+impl Outgoing<'_> {
+    fn to_message(&self) -> Message {
+        let payload =
+            serde_json::to_string(self).expect("error occurred when serializing message payload");
+        Message::text(payload)
+    }
+}
+
+/// 單一連線的訂閱與規則，以及目前所知的各測站資料
+struct Session {
+    units: UnitSystem,
+    language: Language,
+    records: HashMap<String, Record>,
+    stations: BTreeSet<String>,
+    towns: BTreeSet<String>,
+    rules: BTreeMap<String, Rule>,
+    /// 已達門檻的 (規則, 測站 `Record::key`)，回到門檻外之前不重複通知
+    triggered: HashSet<(String, String)>,
+}
+
+impl Session {
+    fn new(units: UnitSystem, language: Language, records: &[Record]) -> Self {
+        Session {
+            units,
+            language,
+            records: records
+                .iter()
+                .map(|record| (record.key().to_string(), record.clone()))
+                .collect(),
+            stations: BTreeSet::new(),
+            towns: BTreeSet::new(),
+            rules: BTreeMap::new(),
+            triggered: HashSet::new(),
+        }
+    }
+
+    fn output(&self, record: &Record) -> Record {
+        record
+            .clone()
+            .with_units(self.units)
+            .with_language(self.language)
+    }
+
+    fn subscribed(&self, record: &Record) -> bool {
+        self.stations.contains(&record.id)
+            || self.stations.contains(&locale::normalize(&record.name))
+            || self.towns.contains(&locale::normalize(&record.town))
+    }
+
+    /// 有訂閱時規則只套用於訂閱的測站，否則套用於所有測站
+    fn watched(&self, record: &Record) -> bool {
+        (self.stations.is_empty() && self.towns.is_empty()) || self.subscribed(record)
+    }
+
+    fn subscriptions(&self) -> Message {
+        Outgoing::Subscriptions {
+            stations: &self.stations,
+            towns: &self.towns,
+            rules: self.rules.values().collect(),
+        }
+        .to_message()
+    }
+
+    /// 依規則檢查測站，回傳新達到門檻者的通知
+    fn evaluate<'a>(&mut self, records: impl IntoIterator<Item = &'a Record>) -> Vec<Message> {
+        let mut messages = Vec::new();
+
+        for record in records {
+            let watched = self.watched(record);
+            let converted = record.clone().with_units(self.units);
+
+            for rule in self.rules.values() {
+                let key = (rule.id.clone(), record.key().to_string());
+                let value = field_by(&rule.field).and_then(|field| field(&converted));
+                let matched =
+                    watched && value.is_some_and(|value| rule.op.check(value, rule.value));
+
+                if !matched {
+                    self.triggered.remove(&key);
+                } else if self.triggered.insert(key) {
+                    messages.push(
+                        Outgoing::Alert {
+                            rule: &rule.id,
+                            field: &rule.field,
+                            op: rule.op,
+                            threshold: rule.value,
+                            value: value.unwrap(),
+                            record: Box::new(converted.clone().with_language(self.language)),
+                        }
+                        .to_message(),
+                    );
+                }
+            }
+        }
+
+        messages
+    }
+
+    /// 處理客戶端訊息
+    fn handle(&mut self, text: &str) -> Vec<Message> {
+        let command: Command = match serde_json::from_str(text) {
+            Ok(command) => command,
+            Err(err) => {
+                return vec![Outgoing::Error {
+                    message: format!("invalid message: {}", err),
+                }
+                .to_message()]
+            }
+        };
+
+        let normalize = |items: Vec<String>| -> Vec<String> {
+            items
+                .iter()
+                .map(|item| locale::normalize(item))
+                .filter(|item| !item.is_empty())
+                .collect()
+        };
+
+        let mut messages = Vec::new();
+
+        match command {
+            Command::Subscribe { stations, towns } => {
+                self.stations.extend(normalize(stations));
+                self.towns.extend(normalize(towns));
+
+                // 訂閱時送出目前資料
+                let mut records: Vec<&Record> = self
+                    .records
+                    .values()
+                    .filter(|record| self.subscribed(record))
+                    .collect();
+                records.sort_by(|a, b| a.name.cmp(&b.name));
+
+                messages.push(
+                    Outgoing::Update {
+                        id: None,
+                        records: records
+                            .into_iter()
+                            .map(|record| self.output(record))
+                            .collect(),
+                        removed: Vec::new(),
+                    }
+                    .to_message(),
+                );
+            }
+            Command::Unsubscribe { stations, towns } => {
+                for station in normalize(stations) {
+                    self.stations.remove(&station);
+                }
+                for town in normalize(towns) {
+                    self.towns.remove(&town);
+                }
+            }
+            Command::AddRule {
+                id,
+                field,
+                op,
+                value,
+            } => {
+                if field_by(&field).is_none() {
+                    messages.push(
+                        Outgoing::Error {
+                            message: format!("unknown field: {}", field),
+                        }
+                        .to_message(),
+                    );
+                } else if self.rules.len() >= MAX_RULES && !self.rules.contains_key(&id) {
+                    messages.push(
+                        Outgoing::Error {
+                            message: format!("too many rules, at most {}", MAX_RULES),
+                        }
+                        .to_message(),
+                    );
+                } else {
+                    self.triggered.retain(|(rule, _)| *rule != id);
+                    self.rules.insert(
+                        id.clone(),
+                        Rule {
+                            id,
+                            field,
+                            op,
+                            value,
+                        },
+                    );
+                }
+            }
+            Command::RemoveRule { id } => {
+                self.rules.remove(&id);
+                self.triggered.retain(|(rule, _)| *rule != id);
+            }
+        }
+
+        messages.push(self.subscriptions());
+
+        // 訂閱或規則變動後重新檢查，已達門檻者立即通知
+        let records: Vec<Record> = self.records.values().cloned().collect();
+        messages.extend(self.evaluate(&records));
+
+        messages
+    }
+
+    /// 套用觀測資料變動，回傳訂閱測站的更新與門檻通知
+    fn apply(&mut self, change: &Change) -> Vec<Message> {
+        for record in &change.removed {
+            self.records.remove(record.key());
+            self.triggered
+                .retain(|(_, station)| station != record.key());
+        }
+        for record in &change.updated {
+            self.records
+                .insert(record.key().to_string(), record.clone());
+        }
+
+        let mut messages = Vec::new();
+
+        let records: Vec<Record> = change
+            .updated
+            .iter()
+            .filter(|record| self.subscribed(record))
+            .map(|record| self.output(record))
+            .collect();
+        let removed: Vec<String> = change
+            .removed
+            .iter()
+            .filter(|record| self.subscribed(record))
+            .map(|record| record.key().to_string())
+            .collect();
+
+        if !records.is_empty() || !removed.is_empty() {
+            messages.push(
+                Outgoing::Update {
+                    id: Some(change.id),
+                    records,
+                    removed,
+                }
+                .to_message(),
+            );
+        }
+
+        messages.extend(self.evaluate(&change.updated));
+        messages
+    }
+}
+
+/// 處理連線直到客戶端關閉、落後過多或服務關閉
+async fn serve(
+    mut socket: WebSocketStream<Upgraded>,
+    mut session: Session,
+    mut changes: Receiver<Arc<Change>>,
+    heartbeat_interval: Duration,
+    shutdown: Shutdown,
+) {
+    let mut heartbeat = tokio::time::interval(heartbeat_interval);
+    heartbeat.tick().await;
+
+    loop {
+        let messages = tokio::select! {
+            incoming = socket.next() => match incoming {
+                Some(Ok(Message::Text(text))) => session.handle(&text),
+                Some(Ok(Message::Binary(_))) => vec![Outgoing::Error {
+                    message: "expect text messages".into(),
+                }
+                .to_message()],
+                // ping 由 tungstenite 自動回覆
+                Some(Ok(Message::Ping(_) | Message::Pong(_) | Message::Frame(_))) => continue,
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+            },
+            received = changes.recv() => match received {
+                Ok(change) => session.apply(&change),
+                // 落後超過重播緩衝時結束連線，由客戶端重新連線取得最新資料
+                Err(RecvError::Lagged(_)) => {
+                    let message = Outgoing::Error {
+                        message: "connection lagged behind, reconnect to resume".into(),
+                    };
+                    let _ = socket.send(message.to_message()).await;
+                    break;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = heartbeat.tick() => vec![Message::Ping(Default::default())],
+            _ = shutdown.wait() => break,
+        };
+
+        for message in messages {
+            if socket.send(message).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    let _ = socket.close(None).await;
+}
+
+/// 是否為 WebSocket 升級請求
+fn is_upgrade(req: &Request<Body>) -> bool {
+    let header = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+            .unwrap_or_default()
+            .to_ascii_lowercase()
+    };
+
+    header(UPGRADE) == "websocket"
+        && header(CONNECTION)
+            .split(',')
+            .any(|token| token.trim() == "upgrade")
+        && header(SEC_WEBSOCKET_VERSION) == "13"
+}
+
+/// 觀測資料的 WebSocket 訂閱
+///
+/// 客戶端以 JSON 訊息訂閱、取消訂閱測站 (代碼或名稱) 與鄉鎮，並可註冊門檻規則，
+/// 例如 `{"type": "add_rule", "id": "hot", "field": "TEMP", "op": ">", "value": 35}`；
+/// 背景更新發現訂閱的測站有變動時送出 `update`，測站數值達到門檻時送出 `alert`。
+/// 連線時可用 `units` 與 `lang` 指定單位制與語系，門檻亦以該單位制比較。
+pub async fn get_weather_ws(mut req: Request<Body>) -> Result<Response<Body>> {
+    let units = match get_units(&req) {
+        Ok(units) => units,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let language = match get_language(&req) {
+        Ok(language) => language,
+        Err(err) => return bad_request(err.to_string()),
+    };
+
+    let key = match req.headers().get(SEC_WEBSOCKET_KEY) {
+        Some(key) if is_upgrade(&req) => derive_accept_key(key.as_bytes()),
+        _ => {
+            return Response::builder()
+                .status(StatusCode::UPGRADE_REQUIRED)
+                .header(UPGRADE, "websocket")
+                .body(Body::from("expect a websocket upgrade request"))
+        }
+    };
+
+    // 先訂閱再取得資料，取得期間發布的變動才不會遺漏
+    let feed = req.data::<Arc<Feed>>().unwrap();
+    let changes = feed.subscribe(None).receiver;
+
+    let records = match fetch::weather_data(&req).await {
+        Ok(fetched) => fetched,
+        Err(err) => return fetch::unavailable(&err),
+    };
+    feed.seed(&records.data);
+
+    let session = Session::new(units, language, &records.data);
+    let heartbeat_interval = req.data::<Config>().unwrap().heartbeat_interval;
+    let shutdown = req.data::<Shutdown>().unwrap().clone();
+
+    let upgrade = hyper::upgrade::on(&mut req);
+    tokio::spawn(async move {
+        let upgraded = match upgrade.await {
+            Ok(upgraded) => upgraded,
+            Err(err) => {
+                tracing::warn!(error = %err, "websocket upgrade failed");
+                return;
+            }
+        };
+
+        let socket = WebSocketStream::from_raw_socket(upgraded, Role::Server, None).await;
+        serve(socket, session, changes, heartbeat_interval, shutdown).await;
+    });
+
+    Response::builder()
+        .status(StatusCode::SWITCHING_PROTOCOLS)
+        .header(UPGRADE, "websocket")
+        .header(CONNECTION, "upgrade")
+        .header(SEC_WEBSOCKET_ACCEPT, key)
+        .body(Body::empty())
+}
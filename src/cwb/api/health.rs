@@ -0,0 +1,107 @@
+use super::super::logic;
+use super::super::refresh::Warmup;
+use super::super::status::{DatasetStatus, Status};
+use super::response::json;
+use crate::{config::Config, metrics};
+
+use chrono::Utc;
+use hyper::{header::CONTENT_TYPE, http::Result, Body, Request, Response, StatusCode};
+use routerify::ext::RequestExt;
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Arc};
+
+#[derive(Serialize)]
+struct Health {
+    status: &'static str,
+}
+
+#[derive(Serialize)]
+struct Freshness {
+    #[serde(flatten)]
+    status: DatasetStatus,
+
+    /// 距離最近一次成功取得資料的秒數
+    age_seconds: Option<i64>,
+    fresh: bool,
+}
+
+#[derive(Serialize)]
+struct Readiness<'a> {
+    ready: bool,
+    datasets: BTreeMap<&'a str, Freshness>,
+}
+
+#[derive(Serialize)]
+struct Version {
+    version: &'static str,
+    git_sha: &'static str,
+    build_time: &'static str,
+}
+
+/// 程序存活
+pub async fn healthz(_req: Request<Body>) -> Result<Response<Body>> {
+    json(StatusCode::OK, Health { status: "ok" })
+}
+
+/// 設定有效，且各 dataset 在 `READY_MAX_AGE` 內成功取得過資料
+///
+/// 只讀取上游狀態，不向上游請求；資料過舊時請背景更新，避免閒置一段時間後一直未就緒。
+pub async fn readyz(req: Request<Body>) -> Result<Response<Body>> {
+    let config = req.data::<Config>().unwrap();
+    let max_age = config.ready_max_age;
+    let max_age = chrono::Duration::from_std(max_age).unwrap_or(chrono::Duration::MAX);
+
+    let status = req.data::<Arc<Status>>().unwrap();
+
+    let datasets: BTreeMap<_, _> = [
+        config.observation_dataset.as_str(),
+        logic::WEATHER_FORECAST_DATASET,
+    ]
+    .into_iter()
+    .map(|dataset| {
+        let status = status.get(dataset);
+
+        let freshness = Freshness {
+            age_seconds: status
+                .last_success
+                .map(|time| (Utc::now() - time).num_seconds()),
+            fresh: status.is_fresh(max_age),
+            status,
+        };
+
+        (dataset, freshness)
+    })
+    .collect();
+
+    let ready = datasets.values().all(|freshness| freshness.fresh);
+    if !ready {
+        req.data::<Arc<Warmup>>().unwrap().request();
+    }
+    let code = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    json(code, Readiness { ready, datasets })
+}
+
+/// 版本資訊，git sha 與 建置時間 由 build.rs 於編譯時寫入
+pub async fn version(_req: Request<Body>) -> Result<Response<Body>> {
+    json(
+        StatusCode::OK,
+        Version {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: env!("GIT_SHA"),
+            build_time: env!("BUILD_TIME"),
+        },
+    )
+}
+
+/// Prometheus 指標
+pub async fn metrics(_req: Request<Body>) -> Result<Response<Body>> {
+    Response::builder()
+        .header(CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")
+        .status(StatusCode::OK)
+        .body(Body::from(metrics::encode()))
+}
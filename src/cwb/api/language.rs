@@ -0,0 +1,25 @@
+use super::super::model::locale::{Language, ParseLanguageError};
+
+use hyper::{header::ACCEPT_LANGUAGE, Body, Request};
+use querystring::querify;
+
+/// 取得回傳語系，`lang` query 優先，其次為 `Accept-Language` header，皆未指定時使用正體中文
+pub fn get_language(req: &Request<Body>) -> Result<Language, ParseLanguageError> {
+    let query = req
+        .uri()
+        .query()
+        .and_then(|queries| querify(queries).into_iter().find(|(key, _)| *key == "lang"));
+
+    if let Some((_, value)) = query {
+        return value.parse();
+    }
+
+    let language = req
+        .headers()
+        .get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Language::from_accept_language)
+        .unwrap_or_default();
+
+    Ok(language)
+}
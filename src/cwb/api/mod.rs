@@ -1,5 +1,38 @@
+mod get_air;
+pub use get_air::*;
+
+mod get_astro;
+pub use get_astro::*;
+
+mod get_earthquakes;
+pub use get_earthquakes::*;
+
+mod get_rainfall;
+pub use get_rainfall::*;
+
+mod get_warnings;
+pub use get_warnings::*;
+
 mod get_weather_data;
 pub use get_weather_data::*;
 
 mod get_weather_forecast;
 pub use get_weather_forecast::*;
+
+mod get_weather_stream;
+pub use get_weather_stream::*;
+
+mod get_weather_ws;
+pub use get_weather_ws::*;
+
+mod health;
+pub use health::*;
+
+mod webhooks;
+pub use webhooks::*;
+
+mod fetch;
+mod filter;
+mod language;
+mod response;
+mod units;
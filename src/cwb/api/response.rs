@@ -0,0 +1,45 @@
+use super::super::model::{locale::Language, unit::UnitSystem};
+use super::units::{units_header, X_UNITS};
+
+use hyper::{
+    header::{CONTENT_LANGUAGE, CONTENT_TYPE},
+    http::Result,
+    Body, Response, StatusCode,
+};
+use serde::Serialize;
+
+pub fn bad_request(message: String) -> Result<Response<Body>> {
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Body::from(message))
+}
+
+/// 以 JSON 回傳 `data`
+pub fn json<T>(status: StatusCode, data: T) -> Result<Response<Body>>
+where
+    T: Serialize,
+{
+    let payload = serde_json::to_string_pretty(&data)
+        .expect("error occurred when serializing result payload");
+
+    Response::builder()
+        .header(CONTENT_TYPE, "application/json;charset=utf-8")
+        .status(status)
+        .body(Body::from(payload))
+}
+
+/// 以 JSON 回傳 `data` 並標示語系；`units` 為 `None` 的資料沒有單位制之分，不加 `X-Units`
+pub fn response<T>(data: T, units: Option<UnitSystem>, language: Language) -> Result<Response<Body>>
+where
+    T: Serialize,
+{
+    let mut res = json(StatusCode::OK, data)?;
+
+    let headers = res.headers_mut();
+    headers.insert(CONTENT_LANGUAGE, language.tag().parse().unwrap());
+    if let Some(units) = units {
+        headers.insert(X_UNITS, units_header(units).parse().unwrap());
+    }
+
+    Ok(res)
+}
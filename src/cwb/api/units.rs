@@ -0,0 +1,35 @@
+use super::super::model::unit::{ParseUnitSystemError, UnitSystem};
+
+use hyper::{Body, Request};
+use querystring::querify;
+
+pub const X_UNITS: &str = "x-units";
+
+/// 取得 `units` query，未指定時使用公制
+pub fn get_units(req: &Request<Body>) -> Result<UnitSystem, ParseUnitSystemError> {
+    req.uri()
+        .query()
+        .and_then(|queries| {
+            querify(queries)
+                .into_iter()
+                .find(|(key, _)| *key == "units")
+        })
+        .map(|(_, value)| value.parse())
+        .unwrap_or(Ok(UnitSystem::default()))
+}
+
+/// 回傳資料的單位標示，放在 `X-Units` header，
+/// 例如 `system=imperial; temperature=degF; pressure=inHg; ...`
+pub fn units_header(units: UnitSystem) -> String {
+    let units = units.units();
+
+    format!(
+        "system={}; temperature={}; pressure={}; precipitation={}; wind_speed={}; elevation={}",
+        units.system,
+        units.temperature,
+        units.pressure,
+        units.precipitation,
+        units.wind_speed,
+        units.elevation
+    )
+}
@@ -0,0 +1,172 @@
+use super::super::webhook::{Registration, Webhooks};
+use super::response::json;
+use crate::config::Config;
+
+use hyper::{
+    body::HttpBody,
+    header::{AUTHORIZATION, CONTENT_TYPE, LOCATION, WWW_AUTHENTICATE},
+    http::Result,
+    Body, Request, Response, StatusCode,
+};
+use routerify::ext::RequestExt;
+use std::sync::Arc;
+
+/// 註冊內容的大小上限
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+fn error(status: StatusCode, message: String) -> Result<Response<Body>> {
+    Response::builder()
+        .header(CONTENT_TYPE, "text/plain;charset=utf-8")
+        .status(status)
+        .body(Body::from(message))
+}
+
+/// 逐字元比較全部內容，比較時間不因相同的前綴長度而不同
+fn same(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// 檢查 `Authorization: Bearer <ADMIN_TOKEN>`，未通過時回傳錯誤回應；未設定 `ADMIN_TOKEN` 時停用
+fn unauthorized(req: &Request<Body>) -> Option<Result<Response<Body>>> {
+    let config = req.data::<Config>().unwrap();
+
+    let token = match &config.admin_token {
+        Some(token) => token,
+        None => {
+            return Some(error(
+                StatusCode::FORBIDDEN,
+                "ADMIN_TOKEN is not set, admin API is disabled".into(),
+            ))
+        }
+    };
+
+    let provided = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match provided {
+        Some(provided) if same(provided.trim().as_bytes(), token.as_bytes()) => None,
+        _ => Some(
+            Response::builder()
+                .header(WWW_AUTHENTICATE, "Bearer")
+                .status(StatusCode::UNAUTHORIZED)
+                .body(Body::from("invalid admin token")),
+        ),
+    }
+}
+
+fn id(req: &Request<Body>) -> String {
+    req.param("id").cloned().unwrap_or_default()
+}
+
+fn not_found(id: &str) -> Result<Response<Body>> {
+    error(StatusCode::NOT_FOUND, format!("webhook not found: {}", id))
+}
+
+/// 讀取完整內容，超過 `MAX_BODY_SIZE` 即停止
+async fn read_body(mut body: Body) -> std::result::Result<Vec<u8>, (StatusCode, String)> {
+    let mut buffer = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk.map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()))?;
+        if buffer.len() + chunk.len() > MAX_BODY_SIZE {
+            return Err((
+                StatusCode::PAYLOAD_TOO_LARGE,
+                format!("body exceeds {} bytes", MAX_BODY_SIZE),
+            ));
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+
+    Ok(buffer)
+}
+
+/// 註冊 webhook，回傳 `201` 與含有 `secret` 的註冊結果，之後不再回傳 `secret`
+///
+/// 內容為 `{"url", "secret"?, "rules": [{"id", "field", "op", "value", "locations"?, "cooldown"?}]}`，
+/// `field` 為 `temperature`、`precipitation_per_day`、`wind_gust` 或 `pop`，數值皆為公制。
+pub async fn create_webhook(req: Request<Body>) -> Result<Response<Body>> {
+    if let Some(res) = unauthorized(&req) {
+        return res;
+    }
+
+    let webhooks = req.data::<Arc<Webhooks>>().unwrap().clone();
+
+    let body = match read_body(req.into_body()).await {
+        Ok(body) => body,
+        Err((status, message)) => return error(status, message),
+    };
+
+    let registration: Registration = match serde_json::from_slice(&body) {
+        Ok(registration) => registration,
+        Err(err) => return error(StatusCode::BAD_REQUEST, err.to_string()),
+    };
+
+    match webhooks.register(registration) {
+        Ok(registered) => {
+            let mut res = json(StatusCode::CREATED, &registered)?;
+            let location = format!("/admin/webhooks/{}", registered.webhook.id);
+            res.headers_mut()
+                .insert(LOCATION, location.parse().unwrap());
+            Ok(res)
+        }
+        Err(err) => error(StatusCode::BAD_REQUEST, err),
+    }
+}
+
+/// 已註冊的 webhook，依註冊順序
+pub async fn list_webhooks(req: Request<Body>) -> Result<Response<Body>> {
+    if let Some(res) = unauthorized(&req) {
+        return res;
+    }
+
+    let webhooks = req.data::<Arc<Webhooks>>().unwrap();
+    json(StatusCode::OK, webhooks.list())
+}
+
+/// 單一 webhook
+pub async fn get_webhook(req: Request<Body>) -> Result<Response<Body>> {
+    if let Some(res) = unauthorized(&req) {
+        return res;
+    }
+
+    let id = id(&req);
+    let webhooks = req.data::<Arc<Webhooks>>().unwrap();
+    match webhooks.get(&id) {
+        Some(webhook) => json(StatusCode::OK, webhook),
+        None => not_found(&id),
+    }
+}
+
+/// 移除 webhook 與其傳送紀錄，傳送中的通知仍會完成
+pub async fn delete_webhook(req: Request<Body>) -> Result<Response<Body>> {
+    if let Some(res) = unauthorized(&req) {
+        return res;
+    }
+
+    let id = id(&req);
+    let webhooks = req.data::<Arc<Webhooks>>().unwrap();
+    if !webhooks.remove(&id) {
+        return not_found(&id);
+    }
+
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .body(Body::empty())
+}
+
+/// webhook 的傳送紀錄，由新到舊，含每次嘗試的狀態碼或錯誤
+pub async fn get_webhook_deliveries(req: Request<Body>) -> Result<Response<Body>> {
+    if let Some(res) = unauthorized(&req) {
+        return res;
+    }
+
+    let id = id(&req);
+    let webhooks = req.data::<Arc<Webhooks>>().unwrap();
+    match webhooks.deliveries(&id) {
+        Some(deliveries) => json(StatusCode::OK, deliveries),
+        None => not_found(&id),
+    }
+}
@@ -0,0 +1,81 @@
+use crate::shutdown::Shutdown;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
+
+/// 上游資料的記憶體快取，依 dataset 分別保存，超過 ttl 即視為過期
+pub struct Cache<T> {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, Arc<T>)>>,
+}
+
+impl<T> Cache<T> {
+    pub fn new(ttl: Duration) -> Self {
+        Cache {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 取得未過期的資料
+    pub fn get(&self, key: &str) -> Option<Arc<T>> {
+        let entries = self.entries.lock().unwrap();
+
+        entries
+            .get(key)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < self.ttl)
+            .map(|(_, data)| data.clone())
+    }
+
+    pub fn insert(&self, key: &str, data: T) -> Arc<T> {
+        let data = Arc::new(data);
+
+        if !self.ttl.is_zero() {
+            let mut entries = self.entries.lock().unwrap();
+            entries.insert(key.to_owned(), (Instant::now(), data.clone()));
+        }
+
+        data
+    }
+
+    /// 移除資料，下次取得時重新向資料來源請求
+    pub fn remove(&self, key: &str) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.remove(key);
+    }
+}
+
+impl<T> Cache<T>
+where
+    T: Send + Sync + 'static,
+{
+    /// 清除過期資料
+    fn sweep(&self) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (fetched_at, _)| fetched_at.elapsed() < self.ttl);
+    }
+
+    /// 背景定期清除過期資料，收到關閉訊號時停止
+    pub fn spawn_sweeper(self: &Arc<Self>, shutdown: Shutdown) -> Option<JoinHandle<()>> {
+        if self.ttl.is_zero() {
+            return None;
+        }
+
+        let cache = Arc::clone(self);
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(cache.ttl);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => cache.sweep(),
+                    _ = shutdown.wait() => break,
+                }
+            }
+        });
+
+        Some(task)
+    }
+}
@@ -0,0 +1,276 @@
+use super::model::resp::Record;
+
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+
+/// 重播緩衝保存的變動數，亦為每個連線可落後的事件數
+pub const REPLAY_CAPACITY: usize = 128;
+
+/// 一次背景更新找出的觀測資料變動
+#[derive(Debug)]
+pub struct Change {
+    /// 事件編號，由 1 起遞增
+    pub id: u64,
+    /// 新出現或數值有變動的測站
+    pub updated: Vec<Record>,
+    /// 不再出現的測站，保留最後一筆資料供連線依縣市、範圍篩選
+    pub removed: Vec<Record>,
+}
+
+/// 訂閱時的狀態
+pub struct Subscription {
+    /// 訂閱時最新的事件編號，尚未有變動時為 0
+    pub last_id: u64,
+    /// `Last-Event-ID` 之後的變動，編號不在重播緩衝內時為 `None`，需改送完整資料
+    pub replay: Option<Vec<Arc<Change>>>,
+    /// 之後的變動
+    pub receiver: broadcast::Receiver<Arc<Change>>,
+}
+
+struct State {
+    /// 上次的各測站資料與其序列化結果，尚未取得資料時為 `None`
+    last: Option<HashMap<String, (Value, Record)>>,
+    last_id: u64,
+    replay: VecDeque<Arc<Change>>,
+}
+
+/// 觀測資料的變動推送，比較每次背景更新的結果，保存短暫的重播緩衝並廣播給 `/weather/stream`
+pub struct Feed {
+    state: Mutex<State>,
+    sender: broadcast::Sender<Arc<Change>>,
+}
+
+impl Default for Feed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 以 `Record::key` 索引，比較序列化後的結果，只計入回傳的欄位
+fn index(records: &[Record]) -> HashMap<String, (Value, Record)> {
+    records
+        .iter()
+        .map(|record| {
+            let value = serde_json::to_value(record).unwrap_or(Value::Null);
+            (record.key().to_string(), (value, record.clone()))
+        })
+        .collect()
+}
+
+impl Feed {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(REPLAY_CAPACITY);
+
+        Feed {
+            state: Mutex::new(State {
+                last: None,
+                last_id: 0,
+                replay: VecDeque::with_capacity(REPLAY_CAPACITY),
+            }),
+            sender,
+        }
+    }
+
+    /// 目前的連線數
+    pub fn subscribers(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// 尚未取得資料時，以 `records` 作為之後比較的基準
+    pub fn seed(&self, records: &[Record]) {
+        let mut state = self.state.lock().unwrap();
+
+        if state.last.is_none() {
+            state.last = Some(index(records));
+        }
+    }
+
+    /// 與上次的資料比較，有變動時保存至重播緩衝並廣播；第一次只作為比較基準
+    pub fn publish(&self, records: &[Record]) -> Option<Arc<Change>> {
+        let mut state = self.state.lock().unwrap();
+
+        let current = index(records);
+        let last = state.last.replace(current)?;
+        let current = state.last.as_ref().unwrap();
+
+        let updated: Vec<Record> = records
+            .iter()
+            .filter(
+                |record| match (last.get(record.key()), current.get(record.key())) {
+                    (Some((before, _)), Some((after, _))) => before != after,
+                    _ => true,
+                },
+            )
+            .cloned()
+            .collect();
+
+        let mut removed: Vec<Record> = last
+            .into_iter()
+            .filter(|(key, _)| !current.contains_key(key))
+            .map(|(_, (_, record))| record)
+            .collect();
+        removed.sort_by(|a, b| a.name.cmp(&b.name));
+
+        if updated.is_empty() && removed.is_empty() {
+            return None;
+        }
+
+        state.last_id += 1;
+        let change = Arc::new(Change {
+            id: state.last_id,
+            updated,
+            removed,
+        });
+
+        if state.replay.len() == REPLAY_CAPACITY {
+            state.replay.pop_front();
+        }
+        state.replay.push_back(change.clone());
+
+        // 沒有連線時傳送失敗，變動仍保存在重播緩衝
+        let _ = self.sender.send(change.clone());
+
+        Some(change)
+    }
+
+    /// 訂閱之後的變動，`last_event_id` 為 `Last-Event-ID`，重播緩衝內有其之後的所有變動時一併回傳
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        // 持有鎖時訂閱，重播與廣播之間不會漏掉變動
+        let state = self.state.lock().unwrap();
+        let receiver = self.sender.subscribe();
+
+        let replay = last_event_id
+            .filter(|id| *id <= state.last_id)
+            .filter(|id| {
+                // 編號 `id` 之後的變動皆須在緩衝內
+                let oldest = state
+                    .replay
+                    .front()
+                    .map_or(state.last_id + 1, |change| change.id);
+                id + 1 >= oldest
+            })
+            .map(|id| {
+                state
+                    .replay
+                    .iter()
+                    .filter(|change| change.id > id)
+                    .cloned()
+                    .collect()
+            });
+
+        Subscription {
+            last_id: state.last_id,
+            replay,
+            receiver,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, temperature: f32) -> Record {
+        Record {
+            temperature,
+            ..Record::sample(name)
+        }
+    }
+
+    fn names(records: &[Record]) -> Vec<&str> {
+        records.iter().map(|record| record.name.as_str()).collect()
+    }
+
+    #[test]
+    fn publishes_changed_and_removed_stations() {
+        let feed = Feed::new();
+
+        // 第一次只作為比較基準
+        assert!(feed
+            .publish(&[record("臺北", 30.0), record("板橋", 29.0)])
+            .is_none());
+        assert!(feed
+            .publish(&[record("臺北", 30.0), record("板橋", 29.0)])
+            .is_none());
+
+        let change = feed
+            .publish(&[record("臺北", 31.0), record("淡水", 28.0)])
+            .unwrap();
+        assert_eq!(change.id, 1);
+        assert_eq!(names(&change.updated), ["臺北", "淡水"]);
+        assert_eq!(names(&change.removed), ["板橋"]);
+    }
+
+    #[test]
+    fn keys_stations_by_code() {
+        let feed = Feed::new();
+        let station = |id: &str, temperature| Record {
+            id: id.into(),
+            ..record("大坑", temperature)
+        };
+
+        // 同名的兩個測站以代碼區分，各自比較
+        feed.seed(&[station("C0F9R0", 25.0), station("C1F9W0", 26.0)]);
+        let change = feed
+            .publish(&[station("C0F9R0", 25.0), station("C1F9W0", 27.0)])
+            .unwrap();
+        assert_eq!(change.updated.len(), 1);
+        assert_eq!(change.updated[0].id, "C1F9W0");
+        assert!(change.removed.is_empty());
+    }
+
+    #[test]
+    fn replays_from_last_event_id() {
+        let feed = Feed::new();
+        feed.seed(&[record("臺北", 30.0)]);
+        for temperature in [31.0, 32.0, 33.0] {
+            feed.publish(&[record("臺北", temperature)]);
+        }
+
+        let subscription = feed.subscribe(Some(1));
+        assert_eq!(subscription.last_id, 3);
+        let replay = subscription.replay.unwrap();
+        assert_eq!(
+            replay.iter().map(|change| change.id).collect::<Vec<_>>(),
+            [2, 3]
+        );
+
+        assert!(feed.subscribe(Some(3)).replay.unwrap().is_empty());
+
+        // 未提供、或編號不明 (例如服務重新啟動前的編號) 時改送完整資料
+        assert!(feed.subscribe(None).replay.is_none());
+        assert!(feed.subscribe(Some(7)).replay.is_none());
+    }
+
+    #[test]
+    fn drops_changes_beyond_replay_capacity() {
+        let feed = Feed::new();
+        feed.seed(&[record("臺北", 0.0)]);
+        for step in 1..=REPLAY_CAPACITY + 2 {
+            feed.publish(&[record("臺北", step as f32)]);
+        }
+
+        assert!(feed.subscribe(Some(1)).replay.is_none());
+        assert_eq!(
+            feed.subscribe(Some(2)).replay.unwrap().len(),
+            REPLAY_CAPACITY
+        );
+    }
+
+    #[tokio::test]
+    async fn broadcasts_to_subscribers() {
+        let feed = Feed::new();
+        feed.seed(&[record("臺北", 30.0)]);
+
+        let mut subscription = feed.subscribe(None);
+        assert_eq!(feed.subscribers(), 1);
+
+        feed.publish(&[record("臺北", 31.0)]);
+        let change = subscription.receiver.recv().await.unwrap();
+        assert_eq!(change.id, 1);
+    }
+}
@@ -0,0 +1,295 @@
+//! 於本機計算的天文資料：日出、日落、民用曙暮光與月相，不需向上游請求
+//!
+//! 日出日落採用日出方程式 (Meeus 簡化版)，台灣緯度下誤差約 1 分鐘；
+//! 月相以平均朔望月推算，誤差約半天。
+
+use super::super::model::resp::{self, DayPeriod, MoonPhase};
+use super::TimeRange;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, TimeZone, Utc};
+use std::{f64::consts::PI, ops::RangeInclusive};
+
+/// 近似公式適用的年份
+pub const SUPPORTED_YEARS: RangeInclusive<i32> = 1900..=2100;
+
+/// J2000.0 的儒略日
+const J2000: f64 = 2_451_545.0;
+
+/// Unix epoch 的儒略日
+const UNIX_EPOCH: f64 = 2_440_587.5;
+
+/// 平均朔望月，單位 日
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+
+/// 2000-01-06 18:14 UTC 的新月
+const NEW_MOON_EPOCH: f64 = 2_451_550.26;
+
+/// 黃赤交角
+const OBLIQUITY: f64 = 23.4397;
+
+/// 日出、日落時太陽中心的高度角，含大氣折射與太陽視半徑
+const SUNRISE_ALTITUDE: f64 = -0.833;
+
+/// 民用曙暮光的太陽高度角
+const CIVIL_TWILIGHT_ALTITUDE: f64 = -6.0;
+
+fn taiwan() -> FixedOffset {
+    FixedOffset::east_opt(8 * 3600).unwrap()
+}
+
+fn sin(degrees: f64) -> f64 {
+    degrees.to_radians().sin()
+}
+
+/// 超出 chrono 可表示的範圍時為 `None`
+fn to_time(julian: f64) -> Option<DateTime<FixedOffset>> {
+    let millis = ((julian - UNIX_EPOCH) * 86_400_000.0).round() as i64;
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .map(|time| time.with_timezone(&taiwan()))
+}
+
+fn julian(time: DateTime<FixedOffset>) -> f64 {
+    time.timestamp_millis() as f64 / 86_400_000.0 + UNIX_EPOCH
+}
+
+/// 單日的日出、日落與民用曙暮光，時間皆為台灣時間；極晝、極夜時為 `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SunTimes {
+    /// 民用曙光開始
+    pub civil_dawn: Option<DateTime<FixedOffset>>,
+    /// 日出
+    pub sunrise: Option<DateTime<FixedOffset>>,
+    /// 太陽過中天
+    pub solar_noon: DateTime<FixedOffset>,
+    /// 日落
+    pub sunset: Option<DateTime<FixedOffset>>,
+    /// 民用暮光結束
+    pub civil_dusk: Option<DateTime<FixedOffset>>,
+}
+
+impl SunTimes {
+    /// 日出至日落的時間長度
+    pub fn day_length(&self) -> Option<Duration> {
+        Some(self.sunset? - self.sunrise?)
+    }
+}
+
+/// 台灣時間 `date` 當日，於緯度 `lat`、經度 `lon` (東經為正) 的日出、日落；
+/// 時間超出可表示的範圍時為 `None`
+pub fn sun_times(date: NaiveDate, lat: f64, lon: f64) -> Option<SunTimes> {
+    let days = (date - NaiveDate::from_ymd_opt(2000, 1, 1).unwrap()).num_days() as f64;
+
+    // 平太陽時
+    let mean_solar_time = days - lon / 360.0;
+
+    let anomaly = (357.5291 + 0.985_600_28 * mean_solar_time).rem_euclid(360.0);
+    let center = 1.9148 * sin(anomaly) + 0.0200 * sin(2.0 * anomaly) + 0.0003 * sin(3.0 * anomaly);
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372).rem_euclid(360.0);
+
+    let transit =
+        J2000 + mean_solar_time + 0.0053 * sin(anomaly) - 0.0069 * sin(2.0 * ecliptic_longitude);
+
+    let declination = (sin(ecliptic_longitude) * sin(OBLIQUITY)).asin();
+    let latitude = lat.to_radians();
+
+    // 太陽到達 `altitude` 時的時角，單位 度
+    let hour_angle = |altitude: f64| {
+        let cos = (sin(altitude) - latitude.sin() * declination.sin())
+            / (latitude.cos() * declination.cos());
+
+        (-1.0..=1.0).contains(&cos).then(|| cos.acos().to_degrees())
+    };
+
+    let rise = |altitude| hour_angle(altitude).and_then(|angle| to_time(transit - angle / 360.0));
+    let set = |altitude| hour_angle(altitude).and_then(|angle| to_time(transit + angle / 360.0));
+
+    Some(SunTimes {
+        civil_dawn: rise(CIVIL_TWILIGHT_ALTITUDE),
+        sunrise: rise(SUNRISE_ALTITUDE),
+        solar_noon: to_time(transit)?,
+        sunset: set(SUNRISE_ALTITUDE),
+        civil_dusk: set(CIVIL_TWILIGHT_ALTITUDE),
+    })
+}
+
+/// 月相與照亮比例
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moon {
+    /// 月相
+    pub phase: MoonPhase,
+    /// 月面被照亮的比例，0 ~ 1
+    pub illumination: f64,
+    /// 月齡，距離上次新月的日數
+    pub age: f64,
+}
+
+/// `time` 時的月相
+pub fn moon(time: DateTime<FixedOffset>) -> Moon {
+    let age = (julian(time) - NEW_MOON_EPOCH).rem_euclid(SYNODIC_MONTH);
+    let fraction = age / SYNODIC_MONTH;
+
+    // 八個月相各佔 1/8 朔望月，以該月相的時刻為中心
+    let phase = match ((fraction * 8.0).round() as u8) % 8 {
+        0 => MoonPhase::NewMoon,
+        1 => MoonPhase::WaxingCrescent,
+        2 => MoonPhase::FirstQuarter,
+        3 => MoonPhase::WaxingGibbous,
+        4 => MoonPhase::FullMoon,
+        5 => MoonPhase::WaningGibbous,
+        6 => MoonPhase::LastQuarter,
+        _ => MoonPhase::WaningCrescent,
+    };
+
+    Moon {
+        phase,
+        illumination: (1.0 - (2.0 * PI * fraction).cos()) / 2.0,
+        age,
+    }
+}
+
+/// 預報時段屬於白天或夜間，時段中至少一半位於日出至日落之間即為白天
+///
+/// 時段為台灣時間，可跨日，例如 18:00 至隔日 06:00。
+pub fn period(time: &TimeRange, lat: f64, lon: f64) -> DayPeriod {
+    let mut daylight = Duration::zero();
+
+    let mut date = time.start.date();
+    while date <= time.end.date() {
+        let sun = sun_times(date, lat, lon);
+
+        if let Some((Some(sunrise), Some(sunset))) = sun.map(|sun| (sun.sunrise, sun.sunset)) {
+            let start = time.start.max(sunrise.naive_local());
+            let end = time.end.min(sunset.naive_local());
+            if start < end {
+                daylight += end - start;
+            }
+        }
+
+        date = match date.succ_opt() {
+            Some(date) => date,
+            None => break,
+        };
+    }
+
+    if daylight * 2 >= time.end - time.start {
+        DayPeriod::Day
+    } else {
+        DayPeriod::Night
+    }
+}
+
+/// 台灣時間 `date` 當日於 `location` 的天文資料，月相取當日正午；
+/// `date` 不在 `SUPPORTED_YEARS` 內時為 `None`
+pub fn astro(date: NaiveDate, location: &resp::Position) -> Option<resp::Astro> {
+    if !SUPPORTED_YEARS.contains(&date.year()) {
+        return None;
+    }
+
+    let sun = sun_times(date, location.latitude as f64, location.longitude as f64)?;
+    let noon = date
+        .and_hms_opt(12, 0, 0)?
+        .and_local_timezone(taiwan())
+        .single()?;
+    let moon = moon(noon);
+
+    Some(resp::Astro {
+        date,
+        location: location.clone(),
+        day_length_minutes: sun.day_length().map(|length| length.num_minutes()),
+        civil_dawn: sun.civil_dawn,
+        sunrise: sun.sunrise,
+        solar_noon: sun.solar_noon,
+        sunset: sun.sunset,
+        civil_dusk: sun.civil_dusk,
+        moon_phase: moon.phase,
+        moon_phase_name: None,
+        moon_illumination: moon.illumination as f32,
+        moon_age: moon.age as f32,
+    })
+}
+
+/// 目前的台灣日期
+pub fn today() -> NaiveDate {
+    Utc::now().with_timezone(&taiwan()).date_naive()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDateTime, Timelike};
+
+    const TAIPEI: (f64, f64) = (25.0377, 121.5149);
+
+    fn minutes(time: DateTime<FixedOffset>) -> u32 {
+        time.hour() * 60 + time.minute()
+    }
+
+    fn time(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn sunrise_and_sunset_in_taipei() {
+        let (lat, lon) = TAIPEI;
+
+        // 2026-10-19 臺北 日出約 05:51、日落約 17:24
+        let sun = sun_times(NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(), lat, lon).unwrap();
+        assert!(minutes(sun.sunrise.unwrap()).abs_diff(5 * 60 + 51) <= 2);
+        assert!(minutes(sun.sunset.unwrap()).abs_diff(17 * 60 + 24) <= 2);
+        assert!(sun.civil_dawn.unwrap() < sun.sunrise.unwrap());
+        assert!(sun.civil_dusk.unwrap() > sun.sunset.unwrap());
+
+        // 夏至白天最長
+        let summer = sun_times(NaiveDate::from_ymd_opt(2026, 6, 21).unwrap(), lat, lon).unwrap();
+        assert!(summer.day_length().unwrap() > sun.day_length().unwrap());
+    }
+
+    #[test]
+    fn rejects_dates_outside_supported_years() {
+        let (lat, lon) = TAIPEI;
+        let location = resp::Position {
+            latitude: lat as f32,
+            longitude: lon as f32,
+        };
+
+        assert!(astro(NaiveDate::MIN, &location).is_none());
+        assert!(astro(NaiveDate::from_ymd_opt(2101, 1, 1).unwrap(), &location).is_none());
+        assert!(astro(NaiveDate::from_ymd_opt(2100, 12, 31).unwrap(), &location).is_some());
+
+        // 超出 chrono 範圍時不 panic
+        assert!(to_time(f64::MAX).is_none());
+    }
+
+    #[test]
+    fn moon_phases() {
+        // 2024-04-08 日全食為新月，2024-01-25 為滿月
+        let new_moon = moon(DateTime::parse_from_rfc3339("2024-04-09T02:21:00+08:00").unwrap());
+        assert_eq!(new_moon.phase, MoonPhase::NewMoon);
+        assert!(new_moon.illumination < 0.02);
+
+        let full_moon = moon(DateTime::parse_from_rfc3339("2024-01-26T01:54:00+08:00").unwrap());
+        assert_eq!(full_moon.phase, MoonPhase::FullMoon);
+        assert!(full_moon.illumination > 0.98);
+    }
+
+    #[test]
+    fn splits_slots_at_sunrise_and_sunset() {
+        let (lat, lon) = TAIPEI;
+        let slot = |start, end| period(&(time(start)..time(end)), lat, lon);
+
+        assert_eq!(slot("2026-10-19 06:00", "2026-10-19 18:00"), DayPeriod::Day);
+        assert_eq!(
+            slot("2026-10-19 18:00", "2026-10-20 06:00"),
+            DayPeriod::Night
+        );
+
+        // 日落 17:24，15:00 ~ 18:00 大半在日落前
+        assert_eq!(slot("2026-10-19 15:00", "2026-10-19 18:00"), DayPeriod::Day);
+        // 日出 05:51，03:00 ~ 06:00 幾乎都在日出前
+        assert_eq!(
+            slot("2026-10-19 03:00", "2026-10-19 06:00"),
+            DayPeriod::Night
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use super::super::model::{
+    epa::{air_quality, uv},
+    resp::{self, UvLevel},
+    Error,
+};
+use super::super::source::AirSource;
+
+/// 紫外線即時監測 dataset (環境部)
+pub const UV_DATASET: &str = "uv_s_01";
+
+/// 空氣品質指標 dataset (環境部)
+pub const AIR_QUALITY_DATASET: &str = "aqx_p_432";
+
+/// 監測站與氣象測站的距離上限，單位 公里，超過時視為附近沒有監測站
+pub const MAX_AIR_DISTANCE: f32 = 25.0;
+
+/// 兩點間的大圓距離，單位 公里
+pub fn distance(a: &resp::Position, b: &resp::Position) -> f32 {
+    const EARTH_RADIUS: f32 = 6371.0;
+
+    let (lat1, lat2) = (a.latitude.to_radians(), b.latitude.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (b.longitude - a.longitude).to_radians();
+
+    let h = (d_lat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS * h.sqrt().asin()
+}
+
+/// 依四捨五入後的紫外線指數判定曝曬級數
+pub fn uv_level(uvi: f32) -> UvLevel {
+    match uvi.round() as i32 {
+        ..=2 => UvLevel::Low,
+        3..=5 => UvLevel::Moderate,
+        6..=7 => UvLevel::High,
+        8..=10 => UvLevel::VeryHigh,
+        _ => UvLevel::Extreme,
+    }
+}
+
+/// `order_by` 與 `min_`、`max_` 篩選可用的欄位
+pub fn air_field(key: &str) -> Option<fn(&resp::AirReading) -> Option<f32>> {
+    let field: fn(&resp::AirReading) -> Option<f32> = match key {
+        "UVI" => |item| item.uv.as_ref().map(|uv| uv.uvi),
+        "AQI" => |item| item.air_quality.as_ref()?.aqi.map(f32::from),
+        "PM2_5" => |item| item.air_quality.as_ref()?.pm2_5,
+        "PM10" => |item| item.air_quality.as_ref()?.pm10,
+        _ => return None,
+    };
+
+    Some(field)
+}
+
+fn position(lat: f64, lon: f64) -> resp::Position {
+    resp::Position {
+        latitude: lat as f32,
+        longitude: lon as f32,
+    }
+}
+
+/// 將環境部紫外線資料 轉換成 指定回傳格式，紫外線指數缺值的監測站不列入結果
+pub fn to_uv_readings(data: &uv::Data) -> Vec<resp::UvReading> {
+    data.records
+        .iter()
+        .filter_map(|site| {
+            let uvi = site.uvi?;
+
+            Some(resp::UvReading {
+                site: site.name.clone(),
+                city: site.county.clone(),
+                location: position(site.lat, site.lon),
+                distance_km: None,
+                uvi,
+                level: uv_level(uvi),
+                level_name: None,
+                published_at: site.published_at,
+            })
+        })
+        .collect()
+}
+
+/// 將環境部空氣品質資料 轉換成 指定回傳格式，指標與濃度皆缺值的監測站不列入結果
+pub fn to_aqi_readings(data: &air_quality::Data) -> Vec<resp::AqiReading> {
+    data.records
+        .iter()
+        .filter(|site| site.aqi.is_some() || site.pm2_5.is_some() || site.pm10.is_some())
+        .map(|site| resp::AqiReading {
+            site: site.name.clone(),
+            city: site.county.clone(),
+            location: position(site.lat, site.lon),
+            distance_km: None,
+            aqi: site.aqi,
+            status: site.status.clone(),
+            pollutant: site.pollutant.clone(),
+            pm2_5: site.pm2_5,
+            pm10: site.pm10,
+            published_at: site.published_at,
+        })
+        .collect()
+}
+
+/// `MAX_AIR_DISTANCE` 內距離 `location` 最近者，以及其距離
+fn nearest<'a, T>(
+    location: &resp::Position,
+    items: &'a [T],
+    position: impl Fn(&T) -> &resp::Position,
+) -> Option<(&'a T, f32)> {
+    items
+        .iter()
+        .map(|item| (item, distance(location, position(item))))
+        .filter(|(_, distance)| *distance <= MAX_AIR_DISTANCE)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+/// 距離 `location` 最近的紫外線與空氣品質監測站觀測，兩者分別配對
+pub fn air_reading(
+    location: &resp::Position,
+    uv: &[resp::UvReading],
+    air_quality: &[resp::AqiReading],
+) -> resp::AirReading {
+    resp::AirReading {
+        uv: nearest(location, uv, |item| &item.location).map(|(item, distance)| resp::UvReading {
+            distance_km: Some(distance),
+            ..item.clone()
+        }),
+        air_quality: nearest(location, air_quality, |item| &item.location).map(
+            |(item, distance)| resp::AqiReading {
+                distance_km: Some(distance),
+                ..item.clone()
+            },
+        ),
+    }
+}
+
+/// 將各氣象測站與最近的監測站觀測配對
+pub fn to_air(
+    records: &[resp::Record],
+    uv: &[resp::UvReading],
+    air_quality: &[resp::AqiReading],
+) -> Vec<resp::Air> {
+    records
+        .iter()
+        .map(|record| resp::Air {
+            city: record.city.clone(),
+            town: record.town.clone(),
+            name: record.name.clone(),
+            location: record.location.clone(),
+            reading: air_reading(&record.location, uv, air_quality),
+        })
+        .collect()
+}
+
+/// 取得紫外線即時監測資料
+pub async fn get_uv(source: &dyn AirSource) -> Result<Vec<resp::UvReading>, Error> {
+    let data = source.uv().await?;
+    Ok(to_uv_readings(&data))
+}
+
+/// 取得空氣品質指標資料
+pub async fn get_air_quality(source: &dyn AirSource) -> Result<Vec<resp::AqiReading>, Error> {
+    let data = source.air_quality().await?;
+    Ok(to_aqi_readings(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::source::ReplaySource;
+    use super::*;
+
+    fn fixtures() -> ReplaySource {
+        ReplaySource::new()
+            .record(
+                UV_DATASET,
+                include_str!("../../../tests/fixtures/uv_s_01.json"),
+            )
+            .record(
+                AIR_QUALITY_DATASET,
+                include_str!("../../../tests/fixtures/aqx_p_432.json"),
+            )
+    }
+
+    #[test]
+    fn exposure_levels_follow_rounded_index() {
+        assert_eq!(uv_level(0.0), UvLevel::Low);
+        assert_eq!(uv_level(2.4), UvLevel::Low);
+        assert_eq!(uv_level(2.5), UvLevel::Moderate);
+        assert_eq!(uv_level(7.4), UvLevel::High);
+        assert_eq!(uv_level(10.0), UvLevel::VeryHigh);
+        assert_eq!(uv_level(11.2), UvLevel::Extreme);
+    }
+
+    #[tokio::test]
+    async fn pairs_the_nearest_site_within_range() {
+        let source = fixtures();
+        let uv = get_uv(&source).await.unwrap();
+        let air_quality = get_air_quality(&source).await.unwrap();
+
+        // 臺北 測站
+        let taipei = resp::Position {
+            latitude: 25.0377,
+            longitude: 121.5149,
+        };
+        let reading = air_reading(&taipei, &uv, &air_quality);
+        assert_eq!(reading.uv.unwrap().site, "臺北");
+        let air_quality_site = reading.air_quality.unwrap();
+        assert_eq!(air_quality_site.site, "中山");
+        assert!(air_quality_site
+            .distance_km
+            .is_some_and(|value| value < 5.0));
+
+        // 玉山 測站，附近沒有監測站
+        let yushan = resp::Position {
+            latitude: 23.4876,
+            longitude: 120.9595,
+        };
+        let reading = air_reading(&yushan, &uv, &air_quality);
+        assert!(reading.uv.is_none());
+        assert!(reading.air_quality.is_none());
+    }
+}
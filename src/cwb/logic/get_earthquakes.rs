@@ -0,0 +1,48 @@
+use super::super::model::{
+    cwb::earthquake::{self, ReportKind},
+    resp, Error,
+};
+use super::super::source::WeatherSource;
+
+/// 將 CWB 地震報告 轉換成 指定回傳格式
+pub fn to_earthquakes(data: &earthquake::Data) -> Vec<resp::Earthquake> {
+    data.records
+        .earthquakes
+        .iter()
+        .map(|report| resp::Earthquake {
+            number: report.number,
+            origin_time: report.origin_time,
+            magnitude: report.magnitude,
+            depth: report.depth,
+            epicenter: report.epicenter.location.clone(),
+            location: resp::Position {
+                latitude: report.epicenter.lat as f32,
+                longitude: report.epicenter.lon as f32,
+            },
+            max_intensity: report
+                .intensities
+                .iter()
+                .map(|(_, intensity)| *intensity)
+                .max(),
+            intensities: report
+                .intensities
+                .iter()
+                .map(|(city, intensity)| resp::CountyIntensity {
+                    city: city.clone(),
+                    intensity: *intensity,
+                })
+                .collect(),
+            content: report.content.clone(),
+            web: report.web.clone(),
+        })
+        .collect()
+}
+
+/// 取得指定種類的地震報告
+pub async fn get_earthquakes(
+    source: &dyn WeatherSource,
+    kind: ReportKind,
+) -> Result<Vec<resp::Earthquake>, Error> {
+    let data = source.earthquakes(kind).await?;
+    Ok(to_earthquakes(&data))
+}
@@ -0,0 +1,155 @@
+use super::super::model::{
+    cwb::{rainfall, weather_data},
+    resp::{self, RainLevel},
+    Error,
+};
+use super::super::source::WeatherSource;
+use crate::metrics;
+
+/// 雨量站即時資料 dataset
+pub const RAINFALL_DATASET: &str = "O-A0002-001";
+
+/// 豪雨分級的門檻，單位 毫米：(分級, 1 小時, 3 小時, 24 小時)，任一時段達到即符合
+///
+/// 依 CWB 雨量分級定義，由重到輕排列。
+pub const RAIN_LEVELS: [(RainLevel, Option<f32>, Option<f32>, f32); 4] = [
+    (RainLevel::ExtremelyTorrentialRain, None, None, 500.0),
+    (RainLevel::TorrentialRain, None, Some(200.0), 350.0),
+    (RainLevel::ExtremelyHeavyRain, None, Some(100.0), 200.0),
+    (RainLevel::HeavyRain, Some(40.0), None, 80.0),
+];
+
+/// 依 1 小時、3 小時、24 小時累積雨量判定豪雨分級，未達 大雨 標準為 `None`
+pub fn rain_level(accumulation: &rainfall::Accumulation) -> Option<RainLevel> {
+    let reaches = |value: Option<f32>, threshold: Option<f32>| {
+        value
+            .zip(threshold)
+            .is_some_and(|(value, threshold)| value >= threshold)
+    };
+
+    RAIN_LEVELS
+        .iter()
+        .find(|(_, hour_1, hour_3, hour_24)| {
+            reaches(accumulation.hour_1, *hour_1)
+                || reaches(accumulation.hour_3, *hour_3)
+                || reaches(accumulation.hour_24, Some(*hour_24))
+        })
+        .map(|(level, ..)| *level)
+}
+
+/// `order_by` 與 `min_`、`max_` 篩選可用的欄位，名稱同 CWB 舊版格式
+pub fn rainfall_field(key: &str) -> Option<fn(&resp::Rainfall) -> Option<f32>> {
+    let field: fn(&resp::Rainfall) -> Option<f32> = match key {
+        "MIN_10" => |item| item.min_10,
+        "RAIN" => |item| item.hour_1,
+        "HOUR_3" => |item| item.hour_3,
+        "HOUR_6" => |item| item.hour_6,
+        "HOUR_12" => |item| item.hour_12,
+        "HOUR_24" => |item| item.hour_24,
+        "NOW" => |item| item.today,
+        _ => return None,
+    };
+
+    Some(field)
+}
+
+/// 縣市、鄉鎮 為必要欄位，缺值的測站不列入結果；各時段雨量皆可缺值
+fn to_rainfall(item: &rainfall::Record) -> Option<resp::Rainfall> {
+    let parameter = |name: weather_data::ParameterName| {
+        item.parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .map(|parameter| parameter.value.clone())
+    };
+
+    let accumulation = &item.rainfall;
+    let level = rain_level(accumulation);
+
+    Some(resp::Rainfall {
+        city: parameter(weather_data::ParameterName::City)?,
+        town: parameter(weather_data::ParameterName::Town)?,
+        name: item.name.clone(),
+        altitude: item.elevation,
+        location: resp::Position {
+            latitude: item.lat as f32,
+            longitude: item.lon as f32,
+        },
+        min_10: accumulation.min_10,
+        hour_1: accumulation.hour_1,
+        hour_3: accumulation.hour_3,
+        hour_6: accumulation.hour_6,
+        hour_12: accumulation.hour_12,
+        hour_24: accumulation.hour_24,
+        today: accumulation.today,
+        level,
+        level_name: None,
+    })
+}
+
+/// 將 CWB 雨量站資料 轉換成 指定回傳格式，縣市、鄉鎮缺值或無法解析的測站不列入結果
+pub fn to_rainfalls(data: &rainfall::Data) -> Vec<resp::Rainfall> {
+    let locations: Vec<_> =
+        tracing::info_span!("transform", dataset = RAINFALL_DATASET).in_scope(|| {
+            data.records
+                .locations
+                .iter()
+                .flat_map(to_rainfall)
+                .collect()
+        });
+
+    super::get_weather_data::warn_invalid(RAINFALL_DATASET, &data.records.invalid);
+
+    let dropped = data.records.locations.len() - locations.len() + data.records.invalid.len();
+    metrics::STATIONS_DROPPED.inc_by(dropped as u64);
+    tracing::debug!(stations = locations.len(), dropped, "transformed");
+
+    locations
+}
+
+/// 取得全台雨量站即時資料
+pub async fn get_rainfall(source: &dyn WeatherSource) -> Result<Vec<resp::Rainfall>, Error> {
+    let data = source.rainfall().await?;
+    Ok(to_rainfalls(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accumulation(hour_1: f32, hour_3: f32, hour_24: f32) -> rainfall::Accumulation {
+        rainfall::Accumulation {
+            hour_1: Some(hour_1),
+            hour_3: Some(hour_3),
+            hour_24: Some(hour_24),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn classifies_heavy_rain() {
+        assert_eq!(rain_level(&accumulation(10.0, 20.0, 79.5)), None);
+        assert_eq!(
+            rain_level(&accumulation(40.0, 45.0, 50.0)),
+            Some(RainLevel::HeavyRain)
+        );
+        assert_eq!(
+            rain_level(&accumulation(60.0, 100.0, 120.0)),
+            Some(RainLevel::ExtremelyHeavyRain)
+        );
+        assert_eq!(
+            rain_level(&accumulation(30.0, 80.0, 350.0)),
+            Some(RainLevel::TorrentialRain)
+        );
+        assert_eq!(
+            rain_level(&accumulation(30.0, 80.0, 520.0)),
+            Some(RainLevel::ExtremelyTorrentialRain)
+        );
+
+        // 缺值的時段不列入判定
+        let missing = rainfall::Accumulation {
+            hour_3: Some(210.0),
+            ..Default::default()
+        };
+        assert_eq!(rain_level(&missing), Some(RainLevel::TorrentialRain));
+    }
+}
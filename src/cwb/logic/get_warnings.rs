@@ -0,0 +1,178 @@
+use super::super::model::{
+    cwb::{typhoon, warning},
+    locale, resp, Error,
+};
+use super::super::source::WeatherSource;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::HashSet;
+
+/// 天氣特報 dataset
+pub const WARNINGS_DATASET: &str = "W-C0033-001";
+
+/// 颱風路徑 dataset
+pub const TYPHOON_DATASET: &str = "W-C0034-005";
+
+/// 將 CWB 特報資料 轉換成 指定回傳格式，沒有特報的縣市不列入結果
+pub fn to_warnings(data: &warning::Data) -> Vec<resp::Warning> {
+    data.records
+        .location
+        .iter()
+        .filter(|location| !location.hazards.is_empty())
+        .map(|location| {
+            let mut hazards: Vec<_> = location
+                .hazards
+                .iter()
+                .map(|hazard| resp::Hazard {
+                    phenomenon: hazard.phenomena.clone(),
+                    significance: hazard.significance.clone(),
+                    start_time: hazard.start_time,
+                    end_time: hazard.end_time,
+                })
+                .collect();
+            hazards.sort_by_key(|hazard| hazard.start_time);
+
+            resp::Warning {
+                city: location.name.clone(),
+                hazards,
+            }
+        })
+        .collect()
+}
+
+/// 只保留 `now` 時生效中的特報，沒有生效特報的縣市不列入結果
+///
+/// 特報資料會快取，結束時間已過的特報可能仍在資料中，故於回應前再篩選一次。
+pub fn active_warnings(
+    warnings: &[resp::Warning],
+    now: DateTime<FixedOffset>,
+) -> Vec<resp::Warning> {
+    warnings
+        .iter()
+        .map(|warning| resp::Warning {
+            city: warning.city.clone(),
+            hazards: warning
+                .hazards
+                .iter()
+                .filter(|hazard| hazard.start_time <= now && now < hazard.end_time)
+                .cloned()
+                .collect(),
+        })
+        .filter(|warning| !warning.hazards.is_empty())
+        .collect()
+}
+
+/// 目前有生效特報的縣市，名稱以 `locale::normalize` 統一
+pub fn warned_cities(warnings: &[resp::Warning]) -> HashSet<String> {
+    active_warnings(warnings, Utc::now().fixed_offset())
+        .iter()
+        .map(|warning| locale::normalize(&warning.city))
+        .collect()
+}
+
+/// 將 CWB 颱風路徑 轉換成 指定回傳格式，沒有分析位置的熱帶氣旋不列入結果，無法解析的位置略過
+pub fn to_typhoons(data: &typhoon::Data) -> Vec<resp::Typhoon> {
+    let to_fix = |fix: &typhoon::Fix| resp::TyphoonFix {
+        time: fix.time,
+        location: resp::Position {
+            latitude: fix.lat as f32,
+            longitude: fix.lon as f32,
+        },
+        max_wind_speed: fix.max_wind_speed,
+        max_gust_speed: fix.max_gust_speed,
+        pressure: fix.pressure,
+        radius_15ms: fix.radius_15ms,
+    };
+
+    data.records
+        .tropical_cyclones
+        .tropical_cyclone
+        .iter()
+        .filter_map(|cyclone| {
+            super::get_weather_data::warn_invalid(TYPHOON_DATASET, &cyclone.invalid);
+
+            let current = cyclone.analysis.last()?;
+
+            Some(resp::Typhoon {
+                name: cyclone.name.clone(),
+                name_zh: cyclone.name_zh.clone(),
+                current: to_fix(current),
+                moving_direction: current.moving_direction.clone(),
+                moving_speed: current.moving_speed,
+                forecast: cyclone.forecast.iter().map(to_fix).collect(),
+            })
+        })
+        .collect()
+}
+
+/// 取得各縣市的天氣特報
+pub async fn get_warnings(source: &dyn WeatherSource) -> Result<Vec<resp::Warning>, Error> {
+    let data = source.warnings().await?;
+    Ok(to_warnings(&data))
+}
+
+/// 取得發布中的颱風路徑
+pub async fn get_typhoons(source: &dyn WeatherSource) -> Result<Vec<resp::Typhoon>, Error> {
+    let data = source.typhoons().await?;
+    Ok(to_typhoons(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(value: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(value).unwrap()
+    }
+
+    #[test]
+    fn keeps_hazards_in_their_validity_window() {
+        let hazard = |start: &str, end: &str| resp::Hazard {
+            phenomenon: "大雨".into(),
+            significance: "特報".into(),
+            start_time: time(start),
+            end_time: time(end),
+        };
+        let warnings = [
+            resp::Warning {
+                city: "臺北市".into(),
+                hazards: vec![hazard(
+                    "2026-10-19T08:00:00+08:00",
+                    "2026-10-19T14:00:00+08:00",
+                )],
+            },
+            resp::Warning {
+                city: "新北市".into(),
+                hazards: vec![
+                    hazard("2026-10-19T08:00:00+08:00", "2026-10-20T08:00:00+08:00"),
+                    hazard("2026-10-19T20:00:00+08:00", "2026-10-20T08:00:00+08:00"),
+                ],
+            },
+        ];
+
+        // 結束時間當下已不生效
+        let active = active_warnings(&warnings, time("2026-10-19T14:00:00+08:00"));
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].city, "新北市");
+        assert_eq!(active[0].hazards.len(), 1);
+    }
+
+    #[test]
+    fn skips_only_invalid_typhoon_fixes() {
+        let json = include_str!("../../../tests/fixtures/W-C0034-005.json").replacen(
+            r#""pressure": "940""#,
+            r#""pressure": "deep""#,
+            1,
+        );
+        let data: typhoon::Data = serde_json::from_str(&json).unwrap();
+
+        let kong_rey = &data.records.tropical_cyclones.tropical_cyclone[0];
+        assert_eq!(kong_rey.analysis.len(), 1);
+        assert_eq!(kong_rey.invalid[0].station, "KONG-REY");
+        assert_eq!(kong_rey.invalid[0].element, "pressure");
+
+        let typhoons = to_typhoons(&data);
+        assert_eq!(typhoons.len(), 2);
+        assert_eq!(typhoons[0].current.pressure, Some(935.0));
+    }
+}
@@ -1,108 +1,156 @@
 use super::super::model::{cwb, resp, Error};
-use crate::env;
+use super::super::source::WeatherSource;
+use super::meteorology;
+use crate::metrics;
 use cwb::weather_data;
 use rayon::prelude::*;
-use reqwest::Url;
 
 fn get_parameter_by(
     name: weather_data::ParameterName,
-    list: &Vec<weather_data::Parameter>,
+    list: &[weather_data::Parameter],
 ) -> Option<String> {
     list.iter()
         .find(|item| item.name == name)
         .map(|item| item.value.clone())
 }
 
-fn get_weather_data_by(
-    name: weather_data::WeatherElementName,
-    list: &Vec<weather_data::WeatherElement>,
-) -> Option<String> {
-    list.iter()
-        .find(|item| item.name == name)
-        .map(|item| item.value.clone())
-}
+/// 溫度、高度、日累積雨量、縣市、鄉鎮 為必要欄位，缺值的測站不列入結果
+fn to_location(item: &weather_data::Record) -> Option<resp::Record> {
+    let observation = &item.observation;
 
-/// -99 皆表示 該時刻因故無資料。
-fn check_data_is_valid(data: f32) -> Result<f32, Error> {
-    if data == -99.0 {
-        Err("invalid data".into())
-    } else {
-        Ok(data)
-    }
-}
+    let city = get_parameter_by(weather_data::ParameterName::City, &item.parameters)?;
+    let town = get_parameter_by(weather_data::ParameterName::Town, &item.parameters)?;
+
+    let temperature = observation.temperature?;
+    let altitude = observation.elevation?;
+    let precipitation_per_day = observation.precipitation_per_day?;
 
-fn to_location(item: &weather_data::Record) -> Result<resp::Record, Error> {
-    let name = item.name.clone();
-    let latitude = item.lat.parse()?;
-    let longitude = item.lon.parse()?;
-
-    let city = get_parameter_by(weather_data::ParameterName::City, &item.parameters)
-        .ok_or("city not found")?;
-
-    let town = get_parameter_by(weather_data::ParameterName::Town, &item.parameters)
-        .ok_or("town not found")?;
-
-    let temperature = get_weather_data_by(
-        weather_data::WeatherElementName::Temperature,
-        &item.weather_elements,
-    )
-    .ok_or("temperature not found")?
-    .parse()
-    .map(check_data_is_valid)??;
-
-    let altitude = get_weather_data_by(
-        weather_data::WeatherElementName::Elevation,
-        &item.weather_elements,
-    )
-    .ok_or("elevation not found")?
-    .parse()
-    .map(check_data_is_valid)??;
-
-    let precipitation_per_day = get_weather_data_by(
-        weather_data::WeatherElementName::PrecipitationPerDay,
-        &item.weather_elements,
-    )
-    .ok_or("precipitation per day not found")?
-    .parse()
-    .map(check_data_is_valid)??;
-
-    Ok(resp::Record {
-        name,
+    // 濕度、風速、氣壓 非必要欄位，缺值時 衍生氣象量 不計算
+    let humidity = observation.humidity;
+    let wind_speed = observation.wind_speed;
+    let pressure = observation.pressure;
+
+    let dew_point = humidity.and_then(|humidity| meteorology::dew_point(temperature, humidity));
+    let heat_index = humidity.and_then(|humidity| meteorology::heat_index(temperature, humidity));
+    let humidex = dew_point.map(|dew_point| meteorology::humidex(temperature, dew_point));
+    let wind_chill = wind_speed.and_then(|speed| meteorology::wind_chill(temperature, speed));
+
+    Some(resp::Record {
+        name: item.name.clone(),
+        id: item.id.clone(),
         city,
         town,
         altitude,
         temperature,
         precipitation_per_day,
         location: resp::Position {
-            latitude,
-            longitude,
+            latitude: item.lat as f32,
+            longitude: item.lon as f32,
         },
+        pressure,
+        wind_speed,
+        wind_gust: observation.max_wind_gust_speed,
+        dew_point,
+        heat_index,
+        humidex,
+        wind_chill,
+        weather: observation.weather.clone(),
+        visibility: observation.visibility.clone(),
+        sunshine_duration: observation.sunshine_duration,
+        air: None,
     })
 }
 
+/// 全台測站即時資料 dataset
+pub const WEATHER_DATA_DATASET: &str = "O-A0001-001";
+
+/// 有人氣象站即時資料 dataset，另有 天氣現象、能見度、日照時數
+pub const MANNED_STATIONS_DATASET: &str = "O-A0003-001";
+
+/// 記錄反序列化時略過的測站或項目，標示測站與項目名稱
+pub(super) fn warn_invalid(dataset: &str, invalid: &[weather_data::InvalidValue]) {
+    for err in invalid {
+        tracing::warn!(
+            dataset,
+            station = %err.station,
+            element = err.element,
+            value = %err.value,
+            reason = %err.reason,
+            "invalid value dropped"
+        );
+    }
+}
+
+/// 將 CWB 測站資料 轉換成 指定回傳格式，必要欄位缺值或無法解析的測站 不列入結果
+pub fn to_records(data: &weather_data::Data) -> Vec<resp::Record> {
+    let locations: Vec<_> = tracing::info_span!("transform", dataset = WEATHER_DATA_DATASET)
+        .in_scope(|| {
+            data.records
+                .locations
+                .par_iter()
+                .flat_map(to_location)
+                .collect()
+        });
+
+    warn_invalid(WEATHER_DATA_DATASET, &data.records.invalid);
+
+    let dropped = data.records.locations.len() - locations.len() + data.records.invalid.len();
+    metrics::STATIONS_DROPPED.inc_by(dropped as u64);
+    tracing::debug!(stations = locations.len(), dropped, "transformed");
+
+    locations
+}
+
 /// 取得全台測站即時資料
-pub async fn get_weather_data() -> Result<Vec<resp::Record>, Error> {
-    let api = env::get_cwb_api();
-    let token = env::get_token();
-
-    let url = Url::parse_with_params(
-        &format!("{}/v1/rest/datastore/O-A0001-001", api),
-        [("Authorization", token)],
-    )?;
-
-    // 打 API
-    let res = reqwest::get(url.as_str()).await?;
-
-    // 解析 API 資料 變成 json
-    let data = res.json::<weather_data::Data>().await?;
-
-    // 將 原本的 location 轉換成 指定回傳格式
-    let locations: Vec<_> = data
-        .records
-        .locations
-        .par_iter()
-        .flat_map(to_location)
-        .collect();
-
-    Ok(locations)
+pub async fn get_weather_data(source: &dyn WeatherSource) -> Result<Vec<resp::Record>, Error> {
+    let data = source.observations().await?;
+    Ok(to_records(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::source::ReplaySource;
+    use super::*;
+
+    fn fixtures() -> ReplaySource {
+        ReplaySource::new().record(
+            WEATHER_DATA_DATASET,
+            include_str!("../../../tests/fixtures/O-A0001-001.json"),
+        )
+    }
+
+    #[tokio::test]
+    async fn drops_stations_with_missing_values() {
+        let records = get_weather_data(&fixtures()).await.unwrap();
+
+        let names: Vec<_> = records.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["臺北", "玉山", "板橋", "阿里山"]);
+    }
+
+    #[tokio::test]
+    async fn optional_values_and_derived_quantities() {
+        let records = get_weather_data(&fixtures()).await.unwrap();
+
+        let taipei = &records[0];
+        assert_eq!(taipei.altitude, 5.3);
+        assert_eq!(taipei.precipitation_per_day, 12.5);
+        assert_eq!(taipei.pressure, Some(1008.0));
+        assert!(taipei.dew_point.is_some());
+        assert!(taipei.wind_chill.is_none());
+
+        // 板橋 濕度、風速為 -99
+        let banqiao = &records[2];
+        assert_eq!(banqiao.wind_speed, None);
+        assert_eq!(banqiao.dew_point, None);
+        assert_eq!(banqiao.humidex, None);
+
+        // 玉山 2°C、風速 8 m/s
+        assert!(records[1].wind_chill.is_some_and(|value| value < 2.0));
+    }
+
+    #[tokio::test]
+    async fn source_errors_are_returned() {
+        let source = ReplaySource::new().record(WEATHER_DATA_DATASET, "{}");
+        assert!(get_weather_data(&source).await.is_err());
+    }
 }
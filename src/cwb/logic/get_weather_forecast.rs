@@ -1,25 +1,57 @@
 use std::{collections::HashMap, ops::Range};
 
-use super::super::model::{cwb::forecast, resp::Temperature, Error};
-use crate::env;
+use super::super::model::{
+    cwb::forecast,
+    locale::{self, Language},
+    resp::{self, DayPeriod, Temperature},
+    Error,
+};
+use super::super::source::WeatherSource;
+use super::astronomy;
+use crate::metrics;
 
 use chrono::{NaiveDate, NaiveDateTime, ParseResult};
 use forecast::WeatherElementName;
-use reqwest::Url;
+use serde::{Deserialize, Serialize};
 
+/// 預報時段，不含結束時間
 pub type TimeRange = Range<NaiveDateTime>;
+/// 時段與該時段的溫度
 pub type TemperatureBetween = (TimeRange, Temperature);
+/// 時段與該時段的降雨機率 (%)
+pub type ChanceBetween = (TimeRange, f32);
 
-#[derive(Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+/// 單日的最高、最低溫
 pub struct TemperatureGroup {
     pub max: Temperature,
     pub min: Temperature,
 }
 
-#[derive(Debug)]
+/// 文字型態的預報，例如 天氣現象、天氣預報綜合描述
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Description {
+    pub element: WeatherElementName,
+    pub time: TimeRange,
+    /// 依鄉鎮的日出、日落判定的白天或夜間，座標不明時為 `None`
+    #[serde(default)]
+    pub period: Option<DayPeriod>,
+    pub value: String,
+}
+
+/// 整理過的單一鄉鎮預報，尚未套用語系與單位
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Location {
+    pub city: String,
     pub name: String,
+    /// 鄉鎮座標，部分 dataset 沒有
+    #[serde(default)]
+    pub location: Option<resp::Position>,
     pub temperatures: HashMap<NaiveDate, TemperatureGroup>,
+    pub descriptions: Vec<Description>,
+    /// 各時段的降雨機率，依時間排序
+    #[serde(default)]
+    pub precipitation_chances: Vec<ChanceBetween>,
 }
 
 impl Location {
@@ -31,14 +63,7 @@ impl Location {
         let temperatures = &mut self.temperatures;
 
         for date in [range.start.date(), range.end.date()] {
-            if !temperatures.contains_key(&date) {
-                temperatures.insert(date.clone(), TemperatureGroup::default());
-            }
-
-            let group = match temperatures.get_mut(&date) {
-                Some(group) => group,
-                None => return,
-            };
+            let group = temperatures.entry(date).or_default();
 
             match name {
                 WeatherElementName::MinTemperature => {
@@ -61,76 +86,326 @@ fn parse_time(time: &str) -> ParseResult<NaiveDateTime> {
     NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S")
 }
 
-fn handle_temperature(item: forecast::Time) -> Result<TemperatureBetween, Error> {
-    let start =
-        parse_time(&item.start_time).expect("parsing error occured when serialize weather endtime");
+/// 時間或溫度缺值、格式錯誤時，該時段略過
+fn handle_temperature(item: forecast::Time) -> Option<TemperatureBetween> {
+    let start = parse_time(&item.start_time).ok()?;
+    let end = parse_time(&item.end_time).ok()?;
+    let temperature = item.value.first()?.value.trim().parse().ok()?;
+
+    Some((TimeRange { start, end }, temperature))
+}
+
+/// 降雨機率缺值時為空白，該時段略過
+fn handle_chance(item: forecast::Time) -> Option<ChanceBetween> {
+    let start = parse_time(&item.start_time).ok()?;
+    let end = parse_time(&item.end_time).ok()?;
+    let chance = item.value.first()?.value.trim().parse().ok()?;
+
+    Some((TimeRange { start, end }, chance))
+}
+
+fn handle_description(
+    name: &WeatherElementName,
+    position: Option<&resp::Position>,
+    item: forecast::Time,
+) -> Option<Description> {
+    let start = parse_time(&item.start_time).ok()?;
+    let end = parse_time(&item.end_time).ok()?;
 
-    let end =
-        parse_time(&item.end_time).expect("parsing error occured when serialize weather endtime");
+    // 天氣現象 第一個值為文字描述，第二個值為天氣現象編號
+    let value = item.value.into_iter().next()?.value;
 
-    let temperature = item
-        .value
-        .get(0)
-        .map(|item| item.value.parse())
-        .expect("weather element value is empty")
-        .expect("parsing error occured when serialize weather temperature");
+    let time = TimeRange { start, end };
+    let period = position.map(|position| {
+        astronomy::period(&time, position.latitude as f64, position.longitude as f64)
+    });
 
-    Ok((TimeRange { start, end }, temperature))
+    Some(Description {
+        element: name.clone(),
+        time,
+        period,
+        value,
+    })
 }
 
-fn to_record(item: forecast::Location) -> Location {
+/// 鄉鎮座標，缺值或格式錯誤時為 `None`
+fn position(item: &forecast::Location) -> Option<resp::Position> {
+    Some(resp::Position {
+        latitude: item.lat.as_deref()?.trim().parse().ok()?,
+        longitude: item.lon.as_deref()?.trim().parse().ok()?,
+    })
+}
+
+/// 整理單一鄉鎮的預報，並回傳略過的溫度時段數
+fn to_record(city: &str, item: forecast::Location) -> (Location, usize) {
     let mut location = Location {
+        city: city.to_owned(),
+        location: position(&item),
         name: item.name,
         temperatures: HashMap::new(),
+        descriptions: Vec::new(),
+        precipitation_chances: Vec::new(),
     };
+    let mut dropped = 0;
 
     for element in item.weather_elements {
         match element.name {
             WeatherElementName::MinTemperature | WeatherElementName::MaxTemperature => {
-                element
+                let slots = element.time.len();
+                let temperatures: Vec<_> = element
                     .time
                     .into_iter()
-                    .flat_map(handle_temperature)
-                    .for_each(|group| location.append_temperature(&element.name, group));
+                    .filter_map(handle_temperature)
+                    .collect();
+                dropped += slots - temperatures.len();
+
+                for group in temperatures {
+                    location.append_temperature(&element.name, group);
+                }
+            }
+            WeatherElementName::WeatherPhenomenon | WeatherElementName::WeatherDescription => {
+                let descriptions = element.time.into_iter().filter_map(|item| {
+                    handle_description(&element.name, location.location.as_ref(), item)
+                });
+
+                location.descriptions.extend(descriptions);
+            }
+            WeatherElementName::ProbabilityOfPrecipitationIn12Hours
+            | WeatherElementName::ProbabilityOfPrecipitationIn6Hours => {
+                let chances = element.time.into_iter().filter_map(handle_chance);
+                location.precipitation_chances.extend(chances);
             }
             _ => (),
         };
     }
 
     location
+        .precipitation_chances
+        .sort_by_key(|(time, _)| time.start);
+
+    (location, dropped)
 }
 
-/// 全台各鄉鎮市區預報
-pub async fn get_weather_forecast() -> Result<Vec<Location>, Error> {
-    let api = env::get_cwb_api();
-    let token = env::get_token();
-
-    let api = &format!("{}/v1/rest/datastore/F-D0047-093", api);
-    let url = Url::parse_with_params(
-        api,
-        [
-            ("Authorization", token),
-            (
-                "locationId",
-                forecast::ForecastType::NewTaipeiCityInWeek.to_string(),
-            ),
-            ("limit", "1".to_owned()),
-        ],
-    )?;
-
-    // 打 API
-    let res = reqwest::get(url.as_str()).await?;
-
-    // 解析 API 資料 變成 json
-    let data = res.json::<forecast::Response>().await?;
-
-    // 將 原本的 location 轉換成 指定回傳格式
-    let locations = data
-        .records
-        .locations
-        .into_iter()
-        .flat_map(|wrapper| wrapper.location.into_iter().map(to_record))
+/// 彙整單一鄉鎮的預報：整週最高、最低溫，單日最大溫差，以及依語系翻譯的文字描述
+///
+/// 沒有任何溫度資料時回傳 `None`。
+pub fn to_forecast(location: &Location, language: Language) -> Option<resp::Forecast> {
+    let temperatures = &location.temperatures;
+
+    // get max temperature
+    let max = temperatures
+        .values()
+        .map(|group| group.max)
+        .reduce(f32::max)?;
+
+    // get min temperature
+    let min = temperatures
+        .values()
+        .map(|group| group.min)
+        .reduce(f32::min)?;
+
+    // get difference per day
+    let diff = temperatures
+        .values()
+        .map(|group| group.max - group.min)
+        .reduce(f32::max)?;
+
+    // 文字型態的預報 依語系翻譯
+    let descriptions = location
+        .descriptions
+        .iter()
+        .map(|item| {
+            let (zh, en) = locale::element_name(&item.element);
+            let element = match language {
+                Language::ZhTw => zh,
+                Language::En => en,
+            };
+
+            let value = match item.element {
+                WeatherElementName::WeatherPhenomenon => locale::phenomenon(&item.value, language),
+                _ => locale::description(&item.value, language),
+            };
+
+            resp::Description {
+                element: element.to_owned(),
+                start_time: item.time.start.to_string(),
+                end_time: item.time.end.to_string(),
+                period: item.period,
+                value,
+            }
+        })
         .collect();
 
-    Ok(locations)
+    Some(resp::Forecast {
+        city: locale::city(&location.city, language),
+        name: locale::town(&location.city, &location.name, language),
+        max_temperature: max,
+        min_temperature: min,
+        temperature_difference_per_day: diff,
+        descriptions,
+    })
+}
+
+/// 全台各鄉鎮市區預報 dataset
+pub const WEATHER_FORECAST_DATASET: &str = "F-D0047-093";
+
+/// 服務回傳預報的縣市
+pub const WEATHER_FORECAST_TYPE: forecast::ForecastType =
+    forecast::ForecastType::NewTaipeiCityInWeek;
+
+/// 將 CWB 預報資料 轉換成 各鄉鎮的 溫度 與 文字描述，回傳略過的溫度時段數
+fn transform(data: forecast::Response) -> (Vec<Location>, usize) {
+    tracing::info_span!("transform", dataset = WEATHER_FORECAST_DATASET).in_scope(|| {
+        let mut locations = Vec::new();
+        let mut dropped = 0;
+
+        for wrapper in data.records.locations {
+            for item in wrapper.location {
+                let (location, skipped) = to_record(&wrapper.name, item);
+                locations.push(location);
+                dropped += skipped;
+            }
+        }
+
+        if dropped > 0 {
+            tracing::warn!(dropped, "malformed forecast temperatures dropped");
+        }
+        metrics::STATIONS_DROPPED.inc_by(dropped as u64);
+
+        (locations, dropped)
+    })
+}
+
+/// 將 CWB 預報資料 轉換成 各鄉鎮的 溫度 與 文字描述，格式錯誤的溫度時段略過
+pub fn to_locations(data: forecast::Response) -> Vec<Location> {
+    transform(data).0
+}
+
+/// 指定縣市各鄉鎮的預報，格式錯誤的溫度時段略過，不影響其他鄉鎮
+pub async fn get_city_forecast(
+    source: &dyn WeatherSource,
+    forecast_type: forecast::ForecastType,
+) -> Result<Vec<Location>, Error> {
+    let data = source.forecast(forecast_type).await?;
+    Ok(to_locations(data))
+}
+
+/// 服務回傳的鄉鎮預報，只取 `WEATHER_FORECAST_TYPE` 的第一個鄉鎮
+pub async fn get_weather_forecast(source: &dyn WeatherSource) -> Result<Vec<Location>, Error> {
+    let data = source.first_town_forecast(WEATHER_FORECAST_TYPE).await?;
+
+    // 略過時段會改變整週的最高、最低溫，回傳錯誤改用上次的快照
+    match transform(data) {
+        (locations, 0) => Ok(locations),
+        (_, dropped) => Err(format!("{} forecast temperatures are malformed", dropped).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::source::ReplaySource;
+    use super::*;
+
+    fn fixtures() -> ReplaySource {
+        ReplaySource::new().record(
+            WEATHER_FORECAST_DATASET,
+            include_str!("../../../tests/fixtures/F-D0047-093.json"),
+        )
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[tokio::test]
+    async fn groups_temperatures_by_date() {
+        let locations = get_weather_forecast(&fixtures()).await.unwrap();
+
+        assert_eq!(locations.len(), 1);
+        let location = &locations[0];
+        assert_eq!(location.city, "新北市");
+        assert_eq!(location.name, "板橋區");
+
+        let temperatures = &location.temperatures;
+        assert_eq!(temperatures.len(), 3);
+        assert_eq!(
+            (temperatures[&date(19)].min, temperatures[&date(19)].max),
+            (22.0, 27.0)
+        );
+        assert_eq!(
+            (temperatures[&date(20)].min, temperatures[&date(20)].max),
+            (20.0, 29.0)
+        );
+        assert_eq!(
+            (temperatures[&date(21)].min, temperatures[&date(21)].max),
+            (19.0, 30.0)
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_text_descriptions() {
+        let locations = get_weather_forecast(&fixtures()).await.unwrap();
+
+        let descriptions = &locations[0].descriptions;
+        assert_eq!(descriptions.len(), 8);
+        assert_eq!(
+            descriptions[0].element,
+            WeatherElementName::WeatherPhenomenon
+        );
+        assert_eq!(descriptions[0].value, "多雲");
+        assert_eq!(
+            descriptions[4].element,
+            WeatherElementName::WeatherDescription
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_precipitation_chances() {
+        let locations = get_weather_forecast(&fixtures()).await.unwrap();
+
+        let chances: Vec<f32> = locations[0]
+            .precipitation_chances
+            .iter()
+            .map(|(_, chance)| *chance)
+            .collect();
+        assert_eq!(chances, [20.0, 10.0, 60.0, 0.0]);
+        assert_eq!(
+            locations[0].precipitation_chances[0].0.start.date(),
+            date(19)
+        );
+    }
+
+    #[tokio::test]
+    async fn skips_malformed_temperatures() {
+        let malformed =
+            include_str!("../../../tests/fixtures/F-D0047-093.json").replacen("\"22\"", "\" \"", 1);
+        let source = ReplaySource::new()
+            .record(WEATHER_FORECAST_DATASET, malformed.clone())
+            .record(WEATHER_FORECAST_DATASET, malformed);
+        let before = metrics::STATIONS_DROPPED.get();
+
+        let locations = get_city_forecast(&source, WEATHER_FORECAST_TYPE)
+            .await
+            .unwrap();
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].temperatures[&date(21)].max, 30.0);
+        assert_eq!(locations[0].precipitation_chances.len(), 4);
+        assert!(metrics::STATIONS_DROPPED.get() > before);
+
+        // 服務回傳的預報改用快照
+        assert!(get_weather_forecast(&source).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn splits_slots_into_day_and_night() {
+        let locations = get_weather_forecast(&fixtures()).await.unwrap();
+
+        let location = &locations[0];
+        assert!(location.location.is_some());
+
+        // 19 日 18:00 ~ 20 日 06:00 為夜間，20 日 06:00 ~ 18:00 為白天
+        let descriptions = &location.descriptions;
+        assert_eq!(descriptions[0].period, Some(DayPeriod::Night));
+        assert_eq!(descriptions[1].period, Some(DayPeriod::Day));
+    }
 }
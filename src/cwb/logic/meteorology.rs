@@ -0,0 +1,83 @@
+//! 由觀測值推算的衍生氣象量
+
+use super::super::model::resp::Temperature;
+
+/// Magnus 公式係數 (Alduchov & Eskridge, 1996)
+const MAGNUS_A: f32 = 17.62;
+const MAGNUS_B: f32 = 243.12;
+
+fn celsius_to_fahrenheit(c: f32) -> f32 {
+    c * 9.0 / 5.0 + 32.0
+}
+
+fn fahrenheit_to_celsius(f: f32) -> f32 {
+    (f - 32.0) * 5.0 / 9.0
+}
+
+/// 露點溫度，使用 Magnus 公式，相對濕度以實數 0-1.0 表示
+pub fn dew_point(temperature: Temperature, humidity: f32) -> Option<Temperature> {
+    if humidity <= 0.0 || humidity > 1.0 {
+        return None;
+    }
+
+    let gamma = humidity.ln() + MAGNUS_A * temperature / (MAGNUS_B + temperature);
+
+    Some(MAGNUS_B * gamma / (MAGNUS_A - gamma))
+}
+
+/// 熱指數 (酷熱指數)，依美國國家氣象局 (NWS) 的 Rothfusz 迴歸式計算
+///
+/// 先以簡化公式估算，若結果低於華氏 80 度則直接採用；
+/// 否則改用完整迴歸式並依濕度做修正。
+pub fn heat_index(temperature: Temperature, humidity: f32) -> Option<Temperature> {
+    if !(0.0..=1.0).contains(&humidity) {
+        return None;
+    }
+
+    let t = celsius_to_fahrenheit(temperature);
+    let rh = humidity * 100.0;
+
+    let simple = 0.5 * (t + 61.0 + (t - 68.0) * 1.2 + rh * 0.094);
+    if (simple + t) / 2.0 < 80.0 {
+        return Some(fahrenheit_to_celsius(simple));
+    }
+
+    let mut hi = -42.379 + 2.049_015_2 * t + 10.143_333 * rh
+        - 0.224_755_4 * t * rh
+        - 0.006_837_83 * t * t
+        - 0.054_817_17 * rh * rh
+        + 0.001_228_74 * t * t * rh
+        + 0.000_852_82 * t * rh * rh
+        - 0.000_001_99 * t * t * rh * rh;
+
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
+        hi -= ((13.0 - rh) / 4.0) * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
+        hi += ((rh - 85.0) / 10.0) * ((87.0 - t) / 5.0);
+    }
+
+    Some(fahrenheit_to_celsius(hi))
+}
+
+/// 濕熱指數 (Humidex)，加拿大環境部定義，以露點溫度計算
+pub fn humidex(temperature: Temperature, dew_point: Temperature) -> Temperature {
+    let kelvin = dew_point + 273.15;
+    let vapour_pressure = 6.11 * (5417.753 * (1.0 / 273.16 - 1.0 / kelvin)).exp();
+
+    temperature + 0.5555 * (vapour_pressure - 10.0)
+}
+
+/// 風寒指數，採用 2001 年 北美 (JAG/TI) 公式
+///
+/// 僅在 溫度 ≤ 10 度 且 風速 > 4.8 公里/時 時有定義，其餘回傳 `None`。
+pub fn wind_chill(temperature: Temperature, wind_speed: f32) -> Option<Temperature> {
+    let speed = wind_speed * 3.6;
+
+    if temperature > 10.0 || speed <= 4.8 {
+        return None;
+    }
+
+    let factor = speed.powf(0.16);
+
+    Some(13.12 + 0.6215 * temperature - 11.37 * factor + 0.3965 * temperature * factor)
+}
@@ -1,5 +1,26 @@
+//! 取得 CWB 資料並整理成服務回傳的資料結構
+
+mod get_air;
+pub use get_air::*;
+
+mod get_earthquakes;
+pub use get_earthquakes::*;
+
+mod get_rainfall;
+pub use get_rainfall::*;
+
+mod get_warnings;
+pub use get_warnings::*;
+
 mod get_weather_data;
 pub use get_weather_data::*;
 
 mod get_weather_forecast;
 pub use get_weather_forecast::*;
+
+pub mod astronomy;
+
+pub mod meteorology;
+
+mod order;
+pub use order::*;
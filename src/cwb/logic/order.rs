@@ -0,0 +1,81 @@
+use super::super::model::resp::{Air, Rainfall, Record};
+use super::{air_field, rainfall_field};
+use std::cmp::Ordering;
+
+/// 測站排序函式
+pub type Compare = fn(&Record, &Record) -> Ordering;
+
+/// 日累積雨量，由高到低
+pub fn sort_by_h24r(a: &Record, b: &Record) -> Ordering {
+    PartialOrd::partial_cmp(&b.precipitation_per_day, &a.precipitation_per_day).unwrap()
+}
+
+/// 溫度，由低到高
+pub fn sort_by_temp(a: &Record, b: &Record) -> Ordering {
+    PartialOrd::partial_cmp(&a.temperature, &b.temperature).unwrap()
+}
+
+/// 由高到低排序，無資料的測站排在最後
+fn descending(a: Option<f32>, b: Option<f32>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => PartialOrd::partial_cmp(&b, &a).unwrap(),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// 由低到高排序，無資料的測站排在最後
+fn ascending(a: Option<f32>, b: Option<f32>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => PartialOrd::partial_cmp(&a, &b).unwrap(),
+        _ => descending(a, b),
+    }
+}
+
+/// 露點溫度，由高到低
+pub fn sort_by_dew_point(a: &Record, b: &Record) -> Ordering {
+    descending(a.dew_point, b.dew_point)
+}
+
+/// 熱指數，由高到低
+pub fn sort_by_heat_index(a: &Record, b: &Record) -> Ordering {
+    descending(a.heat_index, b.heat_index)
+}
+
+/// 濕熱指數，由高到低
+pub fn sort_by_humidex(a: &Record, b: &Record) -> Ordering {
+    descending(a.humidex, b.humidex)
+}
+
+/// 風寒指數，由低到高
+pub fn sort_by_wind_chill(a: &Record, b: &Record) -> Ordering {
+    ascending(a.wind_chill, b.wind_chill)
+}
+
+/// `order_by` 的排序方式，`TEMP`、`WIND_CHILL` 由低到高，其餘由高到低
+pub fn order_by(key: &str) -> Option<Compare> {
+    let cmp: Compare = match key {
+        "TEMP" => sort_by_temp,
+        "H_24R" => sort_by_h24r,
+        "DEW_POINT" => sort_by_dew_point,
+        "HEAT_INDEX" => sort_by_heat_index,
+        "HUMIDEX" => sort_by_humidex,
+        "WIND_CHILL" => sort_by_wind_chill,
+        _ => return None,
+    };
+
+    Some(cmp)
+}
+
+/// 雨量站 `order_by` 的排序方式，皆由高到低，無資料的測站排在最後
+pub fn rain_order_by(key: &str) -> Option<impl Fn(&Rainfall, &Rainfall) -> Ordering> {
+    let field = rainfall_field(key)?;
+    Some(move |a: &Rainfall, b: &Rainfall| descending(field(a), field(b)))
+}
+
+/// 紫外線與空氣品質 `order_by` 的排序方式，皆由高到低，無資料的測站排在最後
+pub fn air_order_by(key: &str) -> Option<impl Fn(&Air, &Air) -> Ordering> {
+    let field = air_field(key)?;
+    Some(move |a: &Air, b: &Air| descending(field(&a.reading), field(&b.reading)))
+}
@@ -1,12 +1,55 @@
+//! CWB 開放資料：資料模型、取得與整理資料的函式，以及 HTTP 服務的路由
+
 mod api;
+mod cache;
+mod feed;
 pub mod logic;
-mod model;
+pub mod model;
+mod refresh;
+mod snapshot;
+pub mod source;
+mod status;
+pub mod upstream;
+mod webhook;
 
+use crate::{
+    config::{Config, Source},
+    logging, metrics,
+    shutdown::Shutdown,
+};
+use cache::Cache;
+use feed::Feed;
 use hyper::{
     http::{Error, Result},
-    Body, Request, Response, StatusCode,
+    Body, Method, Request, Response, StatusCode,
 };
-use routerify::Router;
+use model::cwb::earthquake::ReportKind;
+use refresh::{Refresher, Warmup};
+use routerify::{Middleware, Router, RouterBuilder};
+use snapshot::Snapshots;
+use source::{AirSource, FileSource, ReplaySource, WeatherSource};
+use status::Status;
+use std::{future::Future, sync::Arc};
+use upstream::Upstream;
+use webhook::Webhooks;
+
+/// 註冊路由，並將同一個路由樣式記錄為指標與存取日誌的 `route`
+trait Route {
+    fn route<H, R>(self, method: Method, pattern: &'static str, handler: H) -> Self
+    where
+        H: Fn(Request<Body>) -> R + Send + Sync + 'static,
+        R: Future<Output = Result<Response<Body>>> + Send + 'static;
+}
+
+impl Route for RouterBuilder<Body, Error> {
+    fn route<H, R>(self, method: Method, pattern: &'static str, handler: H) -> Self
+    where
+        H: Fn(Request<Body>) -> R + Send + Sync + 'static,
+        R: Future<Output = Result<Response<Body>>> + Send + 'static,
+    {
+        self.add(pattern, vec![method], metrics::matched(pattern, handler))
+    }
+}
 
 async fn not_found(_: Request<Body>) -> Result<Response<Body>> {
     Response::builder()
@@ -14,10 +57,178 @@ async fn not_found(_: Request<Body>) -> Result<Response<Body>> {
         .body(Body::from("NOT FOUND"))
 }
 
-pub fn service() -> Router<Body, Error> {
+/// HTTP 服務的路由，依 `config.source` 選擇資料來源
+pub fn service(config: Config, shutdown: Shutdown) -> Router<Body, Error> {
+    let observations = Arc::new(Cache::<Vec<model::resp::Record>>::new(config.cache_ttl));
+    let rainfalls = Arc::new(Cache::<Vec<model::resp::Rainfall>>::new(config.cache_ttl));
+    let warnings = Arc::new(Cache::<Vec<model::resp::Warning>>::new(config.cache_ttl));
+    let typhoons = Arc::new(Cache::<Vec<model::resp::Typhoon>>::new(config.cache_ttl));
+    let earthquakes = Arc::new(Cache::<Vec<model::resp::Earthquake>>::new(config.cache_ttl));
+    let forecasts = Arc::new(Cache::<Vec<logic::Location>>::new(config.cache_ttl));
+    let uv = Arc::new(Cache::<Vec<model::resp::UvReading>>::new(config.cache_ttl));
+    let air_quality = Arc::new(Cache::<Vec<model::resp::AqiReading>>::new(config.cache_ttl));
+
+    observations.spawn_sweeper(shutdown.clone());
+    rainfalls.spawn_sweeper(shutdown.clone());
+    warnings.spawn_sweeper(shutdown.clone());
+    typhoons.spawn_sweeper(shutdown.clone());
+    earthquakes.spawn_sweeper(shutdown.clone());
+    forecasts.spawn_sweeper(shutdown.clone());
+    uv.spawn_sweeper(shutdown.clone());
+    air_quality.spawn_sweeper(shutdown.clone());
+
+    let observation_snapshots = Arc::new(Snapshots::<Vec<model::resp::Record>>::new(
+        config.snapshot_dir.clone(),
+        &[config.observation_dataset.as_str()],
+    ));
+    let rainfall_snapshots = Arc::new(Snapshots::<Vec<model::resp::Rainfall>>::new(
+        config.snapshot_dir.clone(),
+        &[logic::RAINFALL_DATASET],
+    ));
+    let warning_snapshots = Arc::new(Snapshots::<Vec<model::resp::Warning>>::new(
+        config.snapshot_dir.clone(),
+        &[logic::WARNINGS_DATASET],
+    ));
+    let typhoon_snapshots = Arc::new(Snapshots::<Vec<model::resp::Typhoon>>::new(
+        config.snapshot_dir.clone(),
+        &[logic::TYPHOON_DATASET],
+    ));
+    let earthquake_snapshots = Arc::new(Snapshots::<Vec<model::resp::Earthquake>>::new(
+        config.snapshot_dir.clone(),
+        &ReportKind::ALL.map(|kind| kind.dataset()),
+    ));
+    let forecast_snapshots = Arc::new(Snapshots::<Vec<logic::Location>>::new(
+        config.snapshot_dir.clone(),
+        &[logic::WEATHER_FORECAST_DATASET],
+    ));
+    let uv_snapshots = Arc::new(Snapshots::<Vec<model::resp::UvReading>>::new(
+        config.snapshot_dir.clone(),
+        &[logic::UV_DATASET],
+    ));
+    let air_quality_snapshots = Arc::new(Snapshots::<Vec<model::resp::AqiReading>>::new(
+        config.snapshot_dir.clone(),
+        &[logic::AIR_QUALITY_DATASET],
+    ));
+
+    // 離線來源由同一目錄或錄製提供紫外線與空氣品質，`cwb` 來源須另設 `AIR_API_KEY`
+    let (source, air): (Arc<dyn WeatherSource>, Option<Arc<dyn AirSource>>) = match &config.source {
+        Source::Cwb => {
+            let upstream =
+                Arc::new(Upstream::new(&config).expect("failed to build upstream client"));
+            let air = config
+                .air_api_key
+                .is_some()
+                .then(|| upstream.clone() as Arc<dyn AirSource>);
+
+            (upstream, air)
+        }
+        Source::File(dir) => {
+            let source = Arc::new(FileSource::new(dir));
+
+            // 目錄出現新的檔案時清除快取，下次請求即讀取新檔
+            source.spawn_watcher(config.watch_interval, shutdown.clone(), {
+                let observations = observations.clone();
+                let rainfalls = rainfalls.clone();
+                let warnings = warnings.clone();
+                let typhoons = typhoons.clone();
+                let earthquakes = earthquakes.clone();
+                let forecasts = forecasts.clone();
+                let uv = uv.clone();
+                let air_quality = air_quality.clone();
+                move |dataset| {
+                    observations.remove(dataset);
+                    rainfalls.remove(dataset);
+                    warnings.remove(dataset);
+                    typhoons.remove(dataset);
+                    earthquakes.remove(dataset);
+                    forecasts.remove(dataset);
+                    uv.remove(dataset);
+                    air_quality.remove(dataset);
+                }
+            });
+
+            (source.clone(), Some(source))
+        }
+        Source::Replay(dir) => {
+            let source =
+                Arc::new(ReplaySource::from_dir(dir).expect("failed to load replay recordings"));
+
+            (source.clone(), Some(source))
+        }
+    };
+
+    // 有 `/weather/stream` 或 `/weather/ws` 連線時於背景更新觀測資料並推送變動，
+    // 有註冊 webhook 時一併更新其規則需要的資料並比對
+    let status = Arc::new(Status::default());
+    let feed = Arc::new(Feed::new());
+    let webhooks =
+        Arc::new(Webhooks::new(&config, shutdown.clone()).expect("failed to build webhook client"));
+    let warmup = Arc::new(Warmup::default());
+    Refresher {
+        config: config.clone(),
+        source: source.clone(),
+        status: status.clone(),
+        observations: observations.clone(),
+        observation_snapshots: observation_snapshots.clone(),
+        forecasts: forecasts.clone(),
+        forecast_snapshots: forecast_snapshots.clone(),
+        feed: feed.clone(),
+        webhooks: webhooks.clone(),
+        warmup: warmup.clone(),
+    }
+    .spawn(shutdown.clone());
+
     Router::builder()
-        .get("/weather", api::get_weather_data)
-        .get("/forecast", api::get_weather_forecast)
+        .data(config)
+        .data(shutdown)
+        .data(source)
+        .data(air)
+        .data(observations)
+        .data(rainfalls)
+        .data(warnings)
+        .data(typhoons)
+        .data(earthquakes)
+        .data(forecasts)
+        .data(uv)
+        .data(air_quality)
+        .data(observation_snapshots)
+        .data(rainfall_snapshots)
+        .data(warning_snapshots)
+        .data(typhoon_snapshots)
+        .data(earthquake_snapshots)
+        .data(forecast_snapshots)
+        .data(uv_snapshots)
+        .data(air_quality_snapshots)
+        .data(status)
+        .data(feed)
+        .data(webhooks)
+        .data(warmup)
+        .middleware(Middleware::pre(logging::start))
+        .middleware(Middleware::pre(metrics::start))
+        .middleware(Middleware::post_with_info(metrics::finish))
+        .middleware(Middleware::post_with_info(logging::finish))
+        .route(Method::GET, "/healthz", api::healthz)
+        .route(Method::GET, "/readyz", api::readyz)
+        .route(Method::GET, "/version", api::version)
+        .route(Method::GET, "/metrics", api::metrics)
+        .route(Method::GET, "/weather", api::get_weather_data)
+        .route(Method::GET, "/weather/stream", api::get_weather_stream)
+        .route(Method::GET, "/weather/ws", api::get_weather_ws)
+        .route(Method::GET, "/rain", api::get_rainfall)
+        .route(Method::GET, "/warnings", api::get_warnings)
+        .route(Method::GET, "/earthquakes", api::get_earthquakes)
+        .route(Method::GET, "/air", api::get_air)
+        .route(Method::GET, "/astro", api::get_astro)
+        .route(Method::GET, "/forecast", api::get_weather_forecast)
+        .route(Method::POST, "/admin/webhooks", api::create_webhook)
+        .route(Method::GET, "/admin/webhooks", api::list_webhooks)
+        .route(Method::GET, "/admin/webhooks/:id", api::get_webhook)
+        .route(Method::DELETE, "/admin/webhooks/:id", api::delete_webhook)
+        .route(
+            Method::GET,
+            "/admin/webhooks/:id/deliveries",
+            api::get_webhook_deliveries,
+        )
         .any(not_found)
         .build()
         .unwrap()
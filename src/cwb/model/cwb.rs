@@ -1,7 +1,12 @@
+//! CWB 開放資料 API 回應的原始結構，欄位與 JSON 對應
+
+/// 自動氣象站 (O-A0001-001) 與 有人氣象站 (O-A0003-001) 的觀測資料
 pub mod weather_data {
+    use chrono::{DateTime, FixedOffset, NaiveDateTime, NaiveTime};
     use serde::{Deserialize, Serialize};
 
-    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// 測站所在地的參數名稱
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
     pub enum ParameterName {
         #[serde(alias = "CITY")]
         City, // 縣市
@@ -14,9 +19,14 @@ pub mod weather_data {
 
         #[serde(alias = "TOWN_SN")]
         TownID, // 鄉鎮編號
+
+        /// 測站屬性，例如 自動站、局屬，雨量站資料才有
+        #[serde(alias = "ATTRIBUTE")]
+        Attribute,
     }
 
-    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    /// 觀測項目名稱
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
     pub enum WeatherElementName {
         /// 高度，單位 公尺
         #[serde(alias = "ELEV")]
@@ -73,9 +83,47 @@ pub mod weather_data {
         /// 本日最低溫發生時間，hhmm (小時分鐘)
         #[serde(alias = "D_TNT")]
         MinTemperaturePerDayOccurTime,
+
+        /// 天氣現象，僅 O-A0003-001 有人站
+        #[serde(alias = "Weather")]
+        Weather,
+
+        /// 能見度，單位 公里，僅 O-A0003-001 有人站
+        #[serde(alias = "VIS")]
+        Visibility,
+
+        /// 本日日照時數，單位 小時，僅 O-A0003-001 有人站
+        #[serde(alias = "SUN")]
+        SunshineDuration,
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    impl WeatherElementName {
+        /// CWB 原始資料中的項目名稱，例如 `TEMP`
+        pub fn code(&self) -> &'static str {
+            match self {
+                WeatherElementName::Elevation => "ELEV",
+                WeatherElementName::WindDirection => "WDIR",
+                WeatherElementName::WindSpeed => "WDSD",
+                WeatherElementName::Temperature => "TEMP",
+                WeatherElementName::Humidity => "HUMD",
+                WeatherElementName::Pressure => "PRES",
+                WeatherElementName::PrecipitationPerDay => "H_24R",
+                WeatherElementName::MaxWindGustPerHourSpeed => "H_FX",
+                WeatherElementName::MaxWindGustPerHourDirection => "H_XD",
+                WeatherElementName::MaxWindGustPerHourOccurTime => "H_FXT",
+                WeatherElementName::MaxTemperaturePerDay => "D_TX",
+                WeatherElementName::MaxTemperaturePerDayOccurTime => "D_TXT",
+                WeatherElementName::MinTemperaturePerDay => "D_TN",
+                WeatherElementName::MinTemperaturePerDayOccurTime => "D_TNT",
+                WeatherElementName::Weather => "Weather",
+                WeatherElementName::Visibility => "VIS",
+                WeatherElementName::SunshineDuration => "SUN",
+            }
+        }
+    }
+
+    /// 單一觀測項目的原始文字，缺值時 CWB 以 `-99` 或 `-999` 表示
+    #[derive(Serialize, Deserialize, Debug, Clone)]
     pub struct WeatherElement {
         #[serde(alias = "elementName")]
         pub name: WeatherElementName,
@@ -84,7 +132,8 @@ pub mod weather_data {
         pub value: String,
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    /// 測站所在地的參數
+    #[derive(Serialize, Deserialize, Debug, Clone)]
     pub struct Parameter {
         #[serde(alias = "parameterName")]
         pub name: ParameterName,
@@ -93,36 +142,1875 @@ pub mod weather_data {
         pub value: String,
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    /// 測站的觀測值，缺值 (`-99`、`-999`) 或 未提供 皆為 `None`
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct Observation {
+        /// 高度，單位 公尺
+        pub elevation: Option<f32>,
+        /// 風向，單位 度，0 表示無風
+        pub wind_direction: Option<f32>,
+        /// 風速，單位 公尺/秒
+        pub wind_speed: Option<f32>,
+        /// 溫度，單位 攝氏
+        pub temperature: Option<f32>,
+        /// 相對濕度，0-1.0
+        pub humidity: Option<f32>,
+        /// 測站氣壓，單位 百帕
+        pub pressure: Option<f32>,
+        /// 日累積雨量，單位 毫米
+        pub precipitation_per_day: Option<f32>,
+        /// 小時最大陣風風速，單位 公尺/秒
+        pub max_wind_gust_speed: Option<f32>,
+        /// 小時最大陣風風向，單位 度
+        pub max_wind_gust_direction: Option<f32>,
+        /// 小時最大陣風時間
+        pub max_wind_gust_time: Option<DateTime<FixedOffset>>,
+        /// 本日最高溫，單位 攝氏
+        pub max_temperature: Option<f32>,
+        /// 本日最高溫發生時間
+        pub max_temperature_time: Option<NaiveTime>,
+        /// 本日最低溫，單位 攝氏
+        pub min_temperature: Option<f32>,
+        /// 本日最低溫發生時間
+        pub min_temperature_time: Option<NaiveTime>,
+        /// 天氣現象，例如 多雲，僅有人站
+        pub weather: Option<String>,
+        /// 能見度，例如 `>30`、`10-15`，單位 公里，僅有人站
+        pub visibility: Option<String>,
+        /// 本日日照時數，單位 小時，僅有人站
+        pub sunshine_duration: Option<f32>,
+    }
+
+    /// 單一測站的觀測資料，數值欄位於反序列化時驗證
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawRecord", into = "RawRecord")]
     pub struct Record {
-        pub lat: String, // 座標 緯度
-        pub lon: String, // 座標 經度
+        pub lat: f64, // 座標 緯度
+        pub lon: f64, // 座標 經度
 
-        #[serde(alias = "locationName")]
         pub name: String, // 測站名稱
 
-        #[serde(alias = "parameter")]
+        /// 測站代碼，例如 `C07014`，舊版錄製資料可能沒有
+        pub id: String,
+
         pub parameters: Vec<Parameter>,
 
+        /// 觀測值
+        pub observation: Observation,
+    }
+
+    /// CWB 原始格式，所有數值皆為文字
+    #[derive(Serialize, Deserialize)]
+    struct RawRecord {
+        lat: String,
+        lon: String,
+
+        #[serde(alias = "locationName")]
+        name: String,
+
+        #[serde(alias = "stationId", default)]
+        id: String,
+
+        #[serde(alias = "parameter")]
+        parameters: Vec<Parameter>,
+
         #[serde(alias = "weatherElement")]
-        pub weather_elements: Vec<WeatherElement>,
+        weather_elements: Vec<WeatherElement>,
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    /// 無法解析的觀測值，標示測站與項目
+    #[derive(Debug, Clone)]
+    pub struct InvalidValue {
+        pub station: String,
+        /// 項目名稱，例如 `TEMP`、`lat`
+        pub element: &'static str,
+        /// 原始文字
+        pub value: String,
+        /// 錯誤原因
+        pub reason: String,
+    }
+
+    impl std::error::Error for InvalidValue {}
+
+    impl std::fmt::Display for InvalidValue {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(
+                f,
+                "station {}: invalid {} {:?}, {}",
+                self.station, self.element, self.value, self.reason
+            )
+        }
+    }
+
+    /// 逐一轉換，無法解析的項目略過並回傳其錯誤，不影響其他項目
+    pub(super) fn lenient<R, T>(raw: Vec<R>) -> (Vec<T>, Vec<InvalidValue>)
+    where
+        T: TryFrom<R, Error = InvalidValue>,
+    {
+        let mut items = Vec::with_capacity(raw.len());
+        let mut invalid = Vec::new();
+
+        for raw in raw {
+            match T::try_from(raw) {
+                Ok(item) => items.push(item),
+                Err(err) => invalid.push(err),
+            }
+        }
+
+        (items, invalid)
+    }
+
+    /// CWB 以 -99、-999 表示 該時刻因故無資料
+    fn is_missing(value: &str) -> bool {
+        matches!(value.trim().parse::<f32>(), Ok(value) if value == -99.0 || value == -999.0)
+    }
+
+    /// 缺值回傳 `None`，其餘以 `parse` 解析
+    pub(super) fn optional<T, E: std::fmt::Display>(
+        value: &str,
+        parse: impl FnOnce(&str) -> Result<T, E>,
+    ) -> Result<Option<T>, String> {
+        if is_missing(value) {
+            return Ok(None);
+        }
+
+        parse(value.trim()).map(Some).map_err(|err| err.to_string())
+    }
+
+    pub(super) fn number(value: &str) -> Result<Option<f32>, String> {
+        optional(value, str::parse::<f32>)
+    }
+
+    /// yyyy-MM-ddThh:mm:ss+08:00
+    fn timestamp(value: &str) -> Result<Option<DateTime<FixedOffset>>, String> {
+        optional(value, DateTime::parse_from_rfc3339)
+    }
+
+    /// yyyy-MM-ddThh:mm:ss+08:00，或未標示時區的 yyyy-MM-dd hh:mm:ss (台灣時間)
+    pub(super) fn local_time(value: &str) -> Result<Option<DateTime<FixedOffset>>, String> {
+        let taiwan = FixedOffset::east_opt(8 * 3600).unwrap();
+
+        optional(value, |value| {
+            DateTime::parse_from_rfc3339(value).or_else(|_| {
+                NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+                    .map(|time| time.and_local_timezone(taiwan).unwrap())
+            })
+        })
+    }
+
+    fn text(value: &str) -> Result<Option<String>, String> {
+        optional(value, |value| Ok::<_, String>(value.to_owned()))
+    }
+
+    /// hhmm (小時分鐘)
+    fn hhmm(value: &str) -> Result<Option<NaiveTime>, String> {
+        optional(value, |value| NaiveTime::parse_from_str(value, "%H%M"))
+    }
+
+    impl TryFrom<RawRecord> for Record {
+        type Error = InvalidValue;
+
+        fn try_from(raw: RawRecord) -> Result<Self, Self::Error> {
+            let invalid = |element: &'static str, value: &str, reason: String| InvalidValue {
+                station: raw.name.clone(),
+                element,
+                value: value.to_owned(),
+                reason,
+            };
+
+            let coordinate = |element: &'static str, value: &str| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|err| invalid(element, value, err.to_string()))
+            };
+
+            let lat = coordinate("lat", &raw.lat)?;
+            let lon = coordinate("lon", &raw.lon)?;
+
+            let mut observation = Observation::default();
+            for item in &raw.weather_elements {
+                let value = item.value.as_str();
+                let error = |reason| invalid(item.name.code(), value, reason);
+
+                match item.name {
+                    WeatherElementName::Elevation => {
+                        observation.elevation = number(value).map_err(error)?
+                    }
+                    WeatherElementName::WindDirection => {
+                        observation.wind_direction = number(value).map_err(error)?
+                    }
+                    WeatherElementName::WindSpeed => {
+                        observation.wind_speed = number(value).map_err(error)?
+                    }
+                    WeatherElementName::Temperature => {
+                        observation.temperature = number(value).map_err(error)?
+                    }
+                    WeatherElementName::Humidity => {
+                        observation.humidity = number(value).map_err(error)?
+                    }
+                    WeatherElementName::Pressure => {
+                        observation.pressure = number(value).map_err(error)?
+                    }
+                    WeatherElementName::PrecipitationPerDay => {
+                        observation.precipitation_per_day = number(value).map_err(error)?
+                    }
+                    WeatherElementName::MaxWindGustPerHourSpeed => {
+                        observation.max_wind_gust_speed = number(value).map_err(error)?
+                    }
+                    WeatherElementName::MaxWindGustPerHourDirection => {
+                        observation.max_wind_gust_direction = number(value).map_err(error)?
+                    }
+                    WeatherElementName::MaxWindGustPerHourOccurTime => {
+                        observation.max_wind_gust_time = timestamp(value).map_err(error)?
+                    }
+                    WeatherElementName::MaxTemperaturePerDay => {
+                        observation.max_temperature = number(value).map_err(error)?
+                    }
+                    WeatherElementName::MaxTemperaturePerDayOccurTime => {
+                        observation.max_temperature_time = hhmm(value).map_err(error)?
+                    }
+                    WeatherElementName::MinTemperaturePerDay => {
+                        observation.min_temperature = number(value).map_err(error)?
+                    }
+                    WeatherElementName::MinTemperaturePerDayOccurTime => {
+                        observation.min_temperature_time = hhmm(value).map_err(error)?
+                    }
+                    WeatherElementName::Weather => {
+                        observation.weather = text(value).map_err(error)?
+                    }
+                    WeatherElementName::Visibility => {
+                        observation.visibility = text(value).map_err(error)?
+                    }
+                    WeatherElementName::SunshineDuration => {
+                        observation.sunshine_duration = number(value).map_err(error)?
+                    }
+                }
+            }
+
+            Ok(Record {
+                lat,
+                lon,
+                name: raw.name,
+                id: raw.id,
+                parameters: raw.parameters,
+                observation,
+            })
+        }
+    }
+
+    impl From<Record> for RawRecord {
+        fn from(record: Record) -> Self {
+            let observation = record.observation;
+
+            let numbers = [
+                (WeatherElementName::Elevation, observation.elevation),
+                (
+                    WeatherElementName::WindDirection,
+                    observation.wind_direction,
+                ),
+                (WeatherElementName::WindSpeed, observation.wind_speed),
+                (WeatherElementName::Temperature, observation.temperature),
+                (WeatherElementName::Humidity, observation.humidity),
+                (WeatherElementName::Pressure, observation.pressure),
+                (
+                    WeatherElementName::PrecipitationPerDay,
+                    observation.precipitation_per_day,
+                ),
+                (
+                    WeatherElementName::MaxWindGustPerHourSpeed,
+                    observation.max_wind_gust_speed,
+                ),
+                (
+                    WeatherElementName::MaxWindGustPerHourDirection,
+                    observation.max_wind_gust_direction,
+                ),
+                (
+                    WeatherElementName::MaxTemperaturePerDay,
+                    observation.max_temperature,
+                ),
+                (
+                    WeatherElementName::MinTemperaturePerDay,
+                    observation.min_temperature,
+                ),
+                (
+                    WeatherElementName::SunshineDuration,
+                    observation.sunshine_duration,
+                ),
+            ]
+            .into_iter()
+            .map(|(name, value)| (name, value.map(|value| value.to_string())));
+
+            let times = [
+                (
+                    WeatherElementName::MaxWindGustPerHourOccurTime,
+                    observation.max_wind_gust_time.map(|time| time.to_rfc3339()),
+                ),
+                (
+                    WeatherElementName::MaxTemperaturePerDayOccurTime,
+                    observation
+                        .max_temperature_time
+                        .map(|time| time.format("%H%M").to_string()),
+                ),
+                (
+                    WeatherElementName::MinTemperaturePerDayOccurTime,
+                    observation
+                        .min_temperature_time
+                        .map(|time| time.format("%H%M").to_string()),
+                ),
+                (WeatherElementName::Weather, observation.weather),
+                (WeatherElementName::Visibility, observation.visibility),
+            ];
+
+            // 缺值以 -99 表示，與 CWB 相同
+            let weather_elements = numbers
+                .chain(times)
+                .map(|(name, value)| WeatherElement {
+                    name,
+                    value: value.unwrap_or_else(|| "-99".to_owned()),
+                })
+                .collect();
+
+            RawRecord {
+                lat: record.lat.to_string(),
+                lon: record.lon.to_string(),
+                name: record.name,
+                id: record.id,
+                parameters: record.parameters,
+                weather_elements,
+            }
+        }
+    }
+
+    /// CWA 新版格式 (`records.Station[]`) 的數值，可能為數字或文字
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Scalar {
+        Number(f64),
+        Text(String),
+    }
+
+    impl Scalar {
+        pub(super) fn text(&self) -> String {
+            match self {
+                Scalar::Number(value) => value.to_string(),
+                Scalar::Text(value) => value.clone(),
+            }
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Coordinate {
+        coordinate_name: String,
+        station_latitude: Scalar,
+        station_longitude: Scalar,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    pub(super) struct GeoInfo {
+        coordinates: Vec<Coordinate>,
+        pub(super) station_altitude: Option<Scalar>,
+        county_name: String,
+        county_code: Option<Scalar>,
+        town_name: String,
+        town_code: Option<Scalar>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct OccurredAt {
+        wind_direction: Option<Scalar>,
+        date_time: Option<String>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct GustInfo {
+        peak_gust_speed: Option<Scalar>,
+        #[serde(rename = "Occurred_at")]
+        occurred_at: Option<OccurredAt>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct TemperatureInfo {
+        air_temperature: Option<Scalar>,
+        #[serde(rename = "Occurred_at")]
+        occurred_at: Option<OccurredAt>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Extreme {
+        temperature_info: Option<TemperatureInfo>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct DailyExtreme {
+        daily_high: Option<Extreme>,
+        daily_low: Option<Extreme>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Now {
+        precipitation: Option<Scalar>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct StationElements {
+        weather: Option<Scalar>,
+        visibility_description: Option<Scalar>,
+        sunshine_duration: Option<Scalar>,
+        now: Option<Now>,
+        wind_direction: Option<Scalar>,
+        wind_speed: Option<Scalar>,
+        air_temperature: Option<Scalar>,
+        relative_humidity: Option<Scalar>,
+        air_pressure: Option<Scalar>,
+        gust_info: Option<GustInfo>,
+        daily_extreme: Option<DailyExtreme>,
+    }
+
+    impl GeoInfo {
+        /// 測站座標，優先使用 WGS84
+        pub(super) fn position(&self, station: &str) -> Result<(f64, f64), InvalidValue> {
+            let invalid = |element: &'static str, value: String, reason: String| InvalidValue {
+                station: station.to_owned(),
+                element,
+                value,
+                reason,
+            };
+
+            let coordinate = self
+                .coordinates
+                .iter()
+                .find(|item| item.coordinate_name == "WGS84")
+                .or(self.coordinates.first())
+                .ok_or_else(|| invalid("Coordinates", String::new(), "no coordinates".into()))?;
+
+            let degrees = |element: &'static str, value: &Scalar| {
+                let value = value.text();
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|err| invalid(element, value.clone(), err.to_string()))
+            };
+            let lat = degrees("StationLatitude", &coordinate.station_latitude)?;
+            let lon = degrees("StationLongitude", &coordinate.station_longitude)?;
+
+            Ok((lat, lon))
+        }
+
+        /// 所在縣市、鄉鎮，與舊版格式的 `parameter` 相同
+        pub(super) fn parameters(&self) -> Vec<Parameter> {
+            let mut parameters = vec![
+                Parameter {
+                    name: ParameterName::City,
+                    value: self.county_name.clone(),
+                },
+                Parameter {
+                    name: ParameterName::Town,
+                    value: self.town_name.clone(),
+                },
+            ];
+            for (name, code) in [
+                (ParameterName::CityID, &self.county_code),
+                (ParameterName::TownID, &self.town_code),
+            ] {
+                if let Some(code) = code {
+                    parameters.push(Parameter {
+                        name,
+                        value: code.text(),
+                    });
+                }
+            }
+
+            parameters
+        }
+    }
+
+    /// CWA 新版格式的單一測站，巢狀的 `GeoInfo` 與 `WeatherElement`
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Station {
+        station_name: String,
+        #[serde(default)]
+        station_id: String,
+        geo_info: GeoInfo,
+        weather_element: StationElements,
+    }
+
+    impl TryFrom<Station> for Record {
+        type Error = InvalidValue;
+
+        fn try_from(raw: Station) -> Result<Self, Self::Error> {
+            let invalid = |element: &'static str, value: String, reason: String| InvalidValue {
+                station: raw.station_name.clone(),
+                element,
+                value,
+                reason,
+            };
+
+            // 依序解析，未提供的項目視為缺值
+            let number_of = |element: &'static str, value: Option<&Scalar>| {
+                value
+                    .map(|value| {
+                        let value = value.text();
+                        number(&value).map_err(|reason| invalid(element, value, reason))
+                    })
+                    .transpose()
+                    .map(Option::flatten)
+            };
+            let text_of = |element: &'static str, value: Option<&Scalar>| {
+                value
+                    .map(|value| {
+                        let value = value.text();
+                        text(&value).map_err(|reason| invalid(element, value, reason))
+                    })
+                    .transpose()
+                    .map(Option::flatten)
+            };
+            let time_of = |element: &'static str, occurred_at: Option<&OccurredAt>| {
+                occurred_at
+                    .and_then(|occurred_at| occurred_at.date_time.clone())
+                    .map(|value| {
+                        timestamp(&value).map_err(|reason| invalid(element, value, reason))
+                    })
+                    .transpose()
+                    .map(Option::flatten)
+            };
+
+            let geo = &raw.geo_info;
+            let (lat, lon) = geo.position(&raw.station_name)?;
+            let parameters = geo.parameters();
+
+            let element = &raw.weather_element;
+            let gust = element.gust_info.as_ref();
+            let high = element
+                .daily_extreme
+                .as_ref()
+                .and_then(|extreme| extreme.daily_high.as_ref())
+                .and_then(|high| high.temperature_info.as_ref());
+            let low = element
+                .daily_extreme
+                .as_ref()
+                .and_then(|extreme| extreme.daily_low.as_ref())
+                .and_then(|low| low.temperature_info.as_ref());
+
+            let max_temperature_time = time_of(
+                "DailyHigh.Occurred_at",
+                high.and_then(|high| high.occurred_at.as_ref()),
+            )?;
+            let min_temperature_time = time_of(
+                "DailyLow.Occurred_at",
+                low.and_then(|low| low.occurred_at.as_ref()),
+            )?;
+
+            let observation = Observation {
+                elevation: number_of("StationAltitude", geo.station_altitude.as_ref())?,
+                wind_direction: number_of("WindDirection", element.wind_direction.as_ref())?,
+                wind_speed: number_of("WindSpeed", element.wind_speed.as_ref())?,
+                temperature: number_of("AirTemperature", element.air_temperature.as_ref())?,
+                // 新版格式的相對濕度為百分比
+                humidity: number_of("RelativeHumidity", element.relative_humidity.as_ref())?
+                    .map(|humidity| humidity / 100.0),
+                pressure: number_of("AirPressure", element.air_pressure.as_ref())?,
+                precipitation_per_day: number_of(
+                    "Now.Precipitation",
+                    element
+                        .now
+                        .as_ref()
+                        .and_then(|now| now.precipitation.as_ref()),
+                )?,
+                max_wind_gust_speed: number_of(
+                    "PeakGustSpeed",
+                    gust.and_then(|gust| gust.peak_gust_speed.as_ref()),
+                )?,
+                max_wind_gust_direction: number_of(
+                    "Occurred_at.WindDirection",
+                    gust.and_then(|gust| gust.occurred_at.as_ref())
+                        .and_then(|occurred_at| occurred_at.wind_direction.as_ref()),
+                )?,
+                max_wind_gust_time: time_of(
+                    "Occurred_at.DateTime",
+                    gust.and_then(|gust| gust.occurred_at.as_ref()),
+                )?,
+                max_temperature: number_of(
+                    "DailyHigh.AirTemperature",
+                    high.and_then(|high| high.air_temperature.as_ref()),
+                )?,
+                max_temperature_time: max_temperature_time.map(|time| time.time()),
+                min_temperature: number_of(
+                    "DailyLow.AirTemperature",
+                    low.and_then(|low| low.air_temperature.as_ref()),
+                )?,
+                min_temperature_time: min_temperature_time.map(|time| time.time()),
+                weather: text_of("Weather", element.weather.as_ref())?,
+                visibility: text_of(
+                    "VisibilityDescription",
+                    element.visibility_description.as_ref(),
+                )?,
+                sunshine_duration: number_of(
+                    "SunshineDuration",
+                    element.sunshine_duration.as_ref(),
+                )?,
+            };
+
+            Ok(Record {
+                lat,
+                lon,
+                name: raw.station_name,
+                id: raw.station_id,
+                parameters,
+                observation,
+            })
+        }
+    }
+
+    /// 觀測資料的 JSON 格式
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub enum Schema {
+        /// 舊版 `records.location[].weatherElement[]`，以 `elementName`、`elementValue` 表示
+        #[default]
+        V1,
+
+        /// CWA 新版 `records.Station[]`，巢狀的 `GeoInfo` 與 `WeatherElement`
+        V2,
+    }
+
+    /// 無法辨識的格式名稱
+    #[derive(Debug)]
+    pub struct ParseSchemaError(String);
+
+    impl std::error::Error for ParseSchemaError {}
+
+    impl std::fmt::Display for ParseSchemaError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "unknown schema: {}, expect one of v1, v2", self.0)
+        }
+    }
+
+    impl std::str::FromStr for Schema {
+        type Err = ParseSchemaError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "v1" => Ok(Schema::V1),
+                "v2" => Ok(Schema::V2),
+                _ => Err(ParseSchemaError(s.to_owned())),
+            }
+        }
+    }
+
+    impl std::fmt::Display for Schema {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            match self {
+                Schema::V1 => write!(f, "v1"),
+                Schema::V2 => write!(f, "v2"),
+            }
+        }
+    }
+
+    /// 兩種格式擇一，`records.location` 為舊版，`records.Station` 為新版
+    #[derive(Deserialize)]
+    struct RawRecordGroup {
+        #[serde(alias = "location")]
+        locations: Option<Vec<RawRecord>>,
+
+        #[serde(rename = "Station")]
+        stations: Option<Vec<Station>>,
+    }
+
+    impl TryFrom<RawRecordGroup> for RecordGroup {
+        type Error = String;
+
+        fn try_from(raw: RawRecordGroup) -> Result<Self, Self::Error> {
+            let (locations, invalid, schema) = match (raw.locations, raw.stations) {
+                (Some(locations), None) => {
+                    let (locations, invalid) = lenient(locations);
+                    (locations, invalid, Schema::V1)
+                }
+                (None, Some(stations)) => {
+                    let (locations, invalid) = lenient(stations);
+                    (locations, invalid, Schema::V2)
+                }
+                (Some(_), Some(_)) => {
+                    return Err("both records.location and records.Station found".into())
+                }
+                (None, None) => return Err("missing records.location or records.Station".into()),
+            };
+
+            Ok(RecordGroup {
+                locations,
+                invalid,
+                schema,
+            })
+        }
+    }
+
+    /// 所有測站，不論原始格式皆轉成相同結構
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawRecordGroup")]
     pub struct RecordGroup {
+        pub locations: Vec<Record>,
+
+        /// 無法解析而略過的測站
+        #[serde(skip)]
+        pub invalid: Vec<InvalidValue>,
+
+        /// 原始資料的格式
+        #[serde(skip)]
+        pub schema: Schema,
+    }
+
+    /// O-A0001-001 回應
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    pub struct Data {
+        pub records: RecordGroup,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn record(elements: &str) -> Result<Record, serde_json::Error> {
+            serde_json::from_str(&format!(
+                r#"{{
+                    "lat": "25.0377", "lon": "121.5149", "locationName": "臺北",
+                    "parameter": [], "weatherElement": [{}]
+                }}"#,
+                elements
+            ))
+        }
+
+        #[test]
+        fn parses_typed_values() {
+            let record = record(
+                r#"{"elementName": "TEMP", "elementValue": "30.5"},
+                {"elementName": "HUMD", "elementValue": "-99"},
+                {"elementName": "PRES", "elementValue": "-999.0"},
+                {"elementName": "H_FXT", "elementValue": "2026-10-19T13:40:00+08:00"},
+                {"elementName": "D_TXT", "elementValue": "1320"}"#,
+            )
+            .unwrap();
+
+            assert_eq!(record.lat, 25.0377);
+            assert_eq!(record.observation.temperature, Some(30.5));
+            assert_eq!(record.observation.humidity, None);
+            assert_eq!(record.observation.pressure, None);
+            assert_eq!(record.observation.elevation, None);
+            assert_eq!(
+                record.observation.max_wind_gust_time.unwrap().to_rfc3339(),
+                "2026-10-19T13:40:00+08:00"
+            );
+            assert_eq!(
+                record.observation.max_temperature_time,
+                NaiveTime::from_hms_opt(13, 20, 0)
+            );
+        }
+
+        #[test]
+        fn names_station_and_element_in_errors() {
+            let err = record(r#"{"elementName": "TEMP", "elementValue": "hot"}"#).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with(r#"station 臺北: invalid TEMP "hot""#));
+
+            let err = record(r#"{"elementName": "D_TNT", "elementValue": "2561"}"#).unwrap_err();
+            assert!(err
+                .to_string()
+                .starts_with(r#"station 臺北: invalid D_TNT "2561""#));
+        }
+
+        #[test]
+        fn detects_the_newer_layout() {
+            let group: RecordGroup = serde_json::from_str(
+                r#"{"Station": [{
+                    "StationName": "臺北",
+                    "GeoInfo": {
+                        "Coordinates": [
+                            {"CoordinateName": "TWD67", "StationLatitude": 25.0359, "StationLongitude": 121.5066},
+                            {"CoordinateName": "WGS84", "StationLatitude": 25.0377, "StationLongitude": 121.5149}
+                        ],
+                        "StationAltitude": "5.3", "CountyName": "臺北市", "TownName": "中正區"
+                    },
+                    "WeatherElement": {"AirTemperature": 30.5, "RelativeHumidity": 70, "AirPressure": -99}
+                }]}"#,
+            )
+            .unwrap();
+
+            assert_eq!(group.schema, Schema::V2);
+
+            let record = &group.locations[0];
+            assert_eq!(record.lat, 25.0377);
+            assert_eq!(record.observation.elevation, Some(5.3));
+            assert_eq!(record.observation.humidity, Some(0.7));
+            assert_eq!(record.observation.pressure, None);
+
+            let group = serde_json::from_str::<RecordGroup>(
+                r#"{"Station": [{
+                    "StationName": "臺北",
+                    "GeoInfo": {"Coordinates": [], "CountyName": "臺北市", "TownName": "中正區"},
+                    "WeatherElement": {}
+                }]}"#,
+            )
+            .unwrap();
+            assert!(group.locations.is_empty());
+            assert!(group.invalid[0]
+                .to_string()
+                .starts_with("station 臺北: invalid Coordinates"));
+        }
+
+        #[test]
+        fn skips_only_invalid_stations() {
+            let group: RecordGroup = serde_json::from_str(
+                r#"{"location": [
+                    {"lat": "25.0377", "lon": "121.5149", "locationName": "臺北", "parameter": [],
+                     "weatherElement": [{"elementName": "TEMP", "elementValue": "hot"}]},
+                    {"lat": "23.4876", "lon": "120.9595", "locationName": "玉山", "parameter": [],
+                     "weatherElement": [{"elementName": "TEMP", "elementValue": "2.0"}]}
+                ]}"#,
+            )
+            .unwrap();
+
+            assert_eq!(group.locations.len(), 1);
+            assert_eq!(group.locations[0].name, "玉山");
+            assert_eq!(group.invalid.len(), 1);
+            assert_eq!(group.invalid[0].station, "臺北");
+            assert_eq!(group.invalid[0].element, "TEMP");
+        }
+
+        #[test]
+        fn serializes_back_to_cwb_format() {
+            let record = record(
+                r#"{"elementName": "TEMP", "elementValue": "30.5"},
+                {"elementName": "D_TNT", "elementValue": "0540"}"#,
+            )
+            .unwrap();
+
+            let json = serde_json::to_value(&record).unwrap();
+            assert_eq!(json["lat"], "25.0377");
+
+            let parsed: Record = serde_json::from_value(json).unwrap();
+            assert_eq!(parsed.observation, record.observation);
+        }
+    }
+}
+
+/// 雨量站 (O-A0002-001) 的累積雨量
+pub mod rainfall {
+    use super::weather_data::{lenient, number, GeoInfo, InvalidValue, Parameter, Scalar, Schema};
+    use serde::Deserialize;
+
+    /// 各時段的累積雨量，單位 毫米，缺值 (`-99`、`-999`) 或 未提供 皆為 `None`
+    #[derive(Debug, Clone, Default, PartialEq)]
+    pub struct Accumulation {
+        /// 10 分鐘累積雨量
+        pub min_10: Option<f32>,
+        /// 1 小時累積雨量
+        pub hour_1: Option<f32>,
+        /// 3 小時累積雨量
+        pub hour_3: Option<f32>,
+        /// 6 小時累積雨量
+        pub hour_6: Option<f32>,
+        /// 12 小時累積雨量
+        pub hour_12: Option<f32>,
+        /// 24 小時累積雨量
+        pub hour_24: Option<f32>,
+        /// 本日 (午夜起) 累積雨量
+        pub today: Option<f32>,
+    }
+
+    /// 單一雨量站的資料，數值欄位於反序列化時驗證
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawRecord")]
+    pub struct Record {
+        /// 座標 緯度
+        pub lat: f64,
+        /// 座標 經度
+        pub lon: f64,
+
+        pub name: String,
+
+        /// 所在縣市、鄉鎮
+        pub parameters: Vec<Parameter>,
+
+        /// 高度，單位 公尺
+        pub elevation: Option<f32>,
+
+        /// 累積雨量
+        pub rainfall: Accumulation,
+    }
+
+    /// 雨量值，`-998` 表示 近期無降雨，視為 0
+    fn amount(value: &str) -> Result<Option<f32>, String> {
+        match value.trim().parse::<f32>() {
+            Ok(-998.0) => Ok(Some(0.0)),
+            _ => number(value),
+        }
+    }
+
+    #[derive(Deserialize)]
+    struct RawElement {
+        #[serde(alias = "elementName")]
+        name: String,
+
+        #[serde(alias = "elementValue")]
+        value: String,
+    }
+
+    /// CWB 舊版格式，所有數值皆為文字
+    #[derive(Deserialize)]
+    struct RawRecord {
+        lat: String,
+        lon: String,
+
+        #[serde(alias = "locationName")]
+        name: String,
+
+        #[serde(alias = "parameter")]
+        parameters: Vec<Parameter>,
+
+        #[serde(alias = "weatherElement")]
+        weather_elements: Vec<RawElement>,
+    }
+
+    impl TryFrom<RawRecord> for Record {
+        type Error = InvalidValue;
+
+        fn try_from(raw: RawRecord) -> Result<Self, Self::Error> {
+            let invalid = |element: &'static str, value: &str, reason: String| InvalidValue {
+                station: raw.name.clone(),
+                element,
+                value: value.to_owned(),
+                reason,
+            };
+
+            let coordinate = |element: &'static str, value: &str| {
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|err| invalid(element, value, err.to_string()))
+            };
+
+            let mut elevation = None;
+            let mut rainfall = Accumulation::default();
+
+            for item in &raw.weather_elements {
+                let (element, slot, parse): (_, _, fn(&str) -> _) = match item.name.as_str() {
+                    "ELEV" => ("ELEV", &mut elevation, number),
+                    "MIN_10" => ("MIN_10", &mut rainfall.min_10, amount),
+                    "RAIN" => ("RAIN", &mut rainfall.hour_1, amount),
+                    "HOUR_3" => ("HOUR_3", &mut rainfall.hour_3, amount),
+                    "HOUR_6" => ("HOUR_6", &mut rainfall.hour_6, amount),
+                    "HOUR_12" => ("HOUR_12", &mut rainfall.hour_12, amount),
+                    "HOUR_24" => ("HOUR_24", &mut rainfall.hour_24, amount),
+                    "NOW" => ("NOW", &mut rainfall.today, amount),
+                    // latest_2days、latest_3days 等其他項目不使用
+                    _ => continue,
+                };
+
+                *slot =
+                    parse(&item.value).map_err(|reason| invalid(element, &item.value, reason))?;
+            }
+
+            Ok(Record {
+                lat: coordinate("lat", &raw.lat)?,
+                lon: coordinate("lon", &raw.lon)?,
+                name: raw.name.clone(),
+                parameters: raw.parameters,
+                elevation,
+                rainfall,
+            })
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Precipitation {
+        precipitation: Option<Scalar>,
+    }
+
+    /// CWA 新版格式的累積雨量，各時段名稱大小寫不一
+    #[derive(Deserialize)]
+    struct RainfallElements {
+        #[serde(rename = "Past10Min")]
+        min_10: Option<Precipitation>,
+        #[serde(rename = "Past1hr", alias = "Past1Hr")]
+        hour_1: Option<Precipitation>,
+        #[serde(rename = "Past3hr", alias = "Past3Hr")]
+        hour_3: Option<Precipitation>,
+        #[serde(rename = "Past6Hr", alias = "Past6hr")]
+        hour_6: Option<Precipitation>,
+        #[serde(rename = "Past12hr", alias = "Past12Hr")]
+        hour_12: Option<Precipitation>,
+        #[serde(rename = "Past24hr", alias = "Past24Hr")]
+        hour_24: Option<Precipitation>,
+        #[serde(rename = "Now")]
+        today: Option<Precipitation>,
+    }
+
+    /// CWA 新版格式的單一測站
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct Station {
+        station_name: String,
+        geo_info: GeoInfo,
+        rainfall_element: RainfallElements,
+    }
+
+    impl TryFrom<Station> for Record {
+        type Error = InvalidValue;
+
+        fn try_from(raw: Station) -> Result<Self, Self::Error> {
+            let invalid = |element: &'static str, value: String, reason: String| InvalidValue {
+                station: raw.station_name.clone(),
+                element,
+                value,
+                reason,
+            };
+
+            let value_of =
+                |element: &'static str,
+                 value: Option<&Scalar>,
+                 parse: fn(&str) -> Result<Option<f32>, String>| {
+                    value
+                        .map(|value| {
+                            let value = value.text();
+                            parse(&value).map_err(|reason| invalid(element, value, reason))
+                        })
+                        .transpose()
+                        .map(Option::flatten)
+                };
+            let amount_of = |element: &'static str, value: &Option<Precipitation>| {
+                let value = value.as_ref().and_then(|item| item.precipitation.as_ref());
+                value_of(element, value, amount)
+            };
+
+            let geo = &raw.geo_info;
+            let (lat, lon) = geo.position(&raw.station_name)?;
+
+            let element = &raw.rainfall_element;
+            let rainfall = Accumulation {
+                min_10: amount_of("Past10Min", &element.min_10)?,
+                hour_1: amount_of("Past1hr", &element.hour_1)?,
+                hour_3: amount_of("Past3hr", &element.hour_3)?,
+                hour_6: amount_of("Past6Hr", &element.hour_6)?,
+                hour_12: amount_of("Past12hr", &element.hour_12)?,
+                hour_24: amount_of("Past24hr", &element.hour_24)?,
+                today: amount_of("Now", &element.today)?,
+            };
+
+            Ok(Record {
+                lat,
+                lon,
+                name: raw.station_name.clone(),
+                parameters: geo.parameters(),
+                elevation: value_of("StationAltitude", geo.station_altitude.as_ref(), number)?,
+                rainfall,
+            })
+        }
+    }
+
+    /// 兩種格式擇一，`records.location` 為舊版，`records.Station` 為新版
+    #[derive(Deserialize)]
+    struct RawRecordGroup {
         #[serde(alias = "location")]
+        locations: Option<Vec<RawRecord>>,
+
+        #[serde(rename = "Station")]
+        stations: Option<Vec<Station>>,
+    }
+
+    impl TryFrom<RawRecordGroup> for RecordGroup {
+        type Error = String;
+
+        fn try_from(raw: RawRecordGroup) -> Result<Self, Self::Error> {
+            let (locations, invalid, schema) = match (raw.locations, raw.stations) {
+                (Some(locations), None) => {
+                    let (locations, invalid) = lenient(locations);
+                    (locations, invalid, Schema::V1)
+                }
+                (None, Some(stations)) => {
+                    let (locations, invalid) = lenient(stations);
+                    (locations, invalid, Schema::V2)
+                }
+                (Some(_), Some(_)) => {
+                    return Err("both records.location and records.Station found".into())
+                }
+                (None, None) => return Err("missing records.location or records.Station".into()),
+            };
+
+            Ok(RecordGroup {
+                locations,
+                invalid,
+                schema,
+            })
+        }
+    }
+
+    /// 所有雨量站，不論原始格式皆轉成相同結構
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawRecordGroup")]
+    pub struct RecordGroup {
+        /// 雨量站資料
         pub locations: Vec<Record>,
+
+        /// 無法解析而略過的雨量站
+        pub invalid: Vec<InvalidValue>,
+
+        /// 原始資料的格式
+        pub schema: Schema,
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    /// O-A0002-001 回應
+    #[derive(Deserialize, Debug, Clone)]
     pub struct Data {
+        /// 雨量資料
         pub records: RecordGroup,
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn parses_both_layouts() {
+            let data: Data = serde_json::from_str(
+                r#"{"records": {"location": [{
+                    "lat": "25.0377", "lon": "121.5149", "locationName": "臺北",
+                    "parameter": [{"parameterName": "ATTRIBUTE", "parameterValue": "自動站"}],
+                    "weatherElement": [
+                        {"elementName": "ELEV", "elementValue": "5.3"},
+                        {"elementName": "MIN_10", "elementValue": "-998.00"},
+                        {"elementName": "RAIN", "elementValue": "42.5"},
+                        {"elementName": "HOUR_24", "elementValue": "-99"},
+                        {"elementName": "latest_3days", "elementValue": "120.0"}
+                    ]
+                }]}}"#,
+            )
+            .unwrap();
+
+            let record = &data.records.locations[0];
+            assert_eq!(data.records.schema, Schema::V1);
+            assert_eq!(record.elevation, Some(5.3));
+            assert_eq!(record.rainfall.min_10, Some(0.0));
+            assert_eq!(record.rainfall.hour_1, Some(42.5));
+            assert_eq!(record.rainfall.hour_24, None);
+            assert_eq!(record.rainfall.hour_3, None);
+
+            let data: Data = serde_json::from_str(
+                r#"{"records": {"Station": [{
+                    "StationName": "臺北",
+                    "GeoInfo": {
+                        "Coordinates": [{"CoordinateName": "WGS84", "StationLatitude": 25.0377, "StationLongitude": 121.5149}],
+                        "StationAltitude": "5.3", "CountyName": "臺北市", "TownName": "中正區"
+                    },
+                    "RainfallElement": {
+                        "Now": {"Precipitation": 12.0},
+                        "Past10Min": {"Precipitation": -998},
+                        "Past1hr": {"Precipitation": 42.5},
+                        "Past6Hr": {"Precipitation": "-99"}
+                    }
+                }]}}"#,
+            )
+            .unwrap();
+
+            let record = &data.records.locations[0];
+            assert_eq!(data.records.schema, Schema::V2);
+            assert_eq!(record.rainfall.min_10, Some(0.0));
+            assert_eq!(record.rainfall.hour_1, Some(42.5));
+            assert_eq!(record.rainfall.hour_6, None);
+            assert_eq!(record.rainfall.today, Some(12.0));
+        }
+
+        #[test]
+        fn names_station_and_element_in_errors() {
+            let data = serde_json::from_str::<Data>(
+                r#"{"records": {"location": [{
+                    "lat": "25.0377", "lon": "121.5149", "locationName": "臺北",
+                    "parameter": [],
+                    "weatherElement": [{"elementName": "HOUR_3", "elementValue": "heavy"}]
+                }]}}"#,
+            )
+            .unwrap();
+
+            assert!(data.records.locations.is_empty());
+            assert!(data.records.invalid[0]
+                .to_string()
+                .starts_with(r#"station 臺北: invalid HOUR_3 "heavy""#));
+        }
+    }
+}
+
+/// 天氣特報 (W-C0033-001)，各縣市目前發布的災害性天氣特報
+pub mod warning {
+    use super::weather_data::{local_time, InvalidValue};
+    use chrono::{DateTime, FixedOffset};
+    use serde::Deserialize;
+
+    /// 單一特報
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Hazard {
+        /// 天氣現象，例如 大雨、陸上強風
+        pub phenomena: String,
+        /// 特報種類，例如 特報、警報
+        pub significance: String,
+        /// 生效時間
+        pub start_time: DateTime<FixedOffset>,
+        pub end_time: DateTime<FixedOffset>,
+    }
+
+    /// 單一縣市的特報，`name` 為縣市名稱，沒有特報時 `hazards` 為空
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawLocation")]
+    pub struct Location {
+        pub name: String,
+        /// 發布中的特報
+        pub hazards: Vec<Hazard>,
+    }
+
+    #[derive(Deserialize)]
+    struct RawInfo {
+        phenomena: String,
+        significance: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RawValidTime {
+        start_time: String,
+        end_time: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RawHazard {
+        info: RawInfo,
+        valid_time: RawValidTime,
+    }
+
+    #[derive(Deserialize)]
+    struct RawHazardConditions {
+        #[serde(default)]
+        hazards: Vec<RawHazard>,
+    }
+
+    /// CWB 原始格式，`hazardConditions` 於沒有特報時可能為 `null`
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RawLocation {
+        location_name: String,
+        hazard_conditions: Option<RawHazardConditions>,
+    }
+
+    impl TryFrom<RawLocation> for Location {
+        type Error = InvalidValue;
+
+        fn try_from(raw: RawLocation) -> Result<Self, Self::Error> {
+            let time = |element: &'static str, value: &str| match local_time(value) {
+                Ok(Some(time)) => Ok(time),
+                result => Err(InvalidValue {
+                    station: raw.location_name.clone(),
+                    element,
+                    value: value.to_owned(),
+                    reason: result.err().unwrap_or_else(|| "missing".into()),
+                }),
+            };
+
+            let hazards = raw
+                .hazard_conditions
+                .iter()
+                .flat_map(|conditions| &conditions.hazards)
+                .map(|hazard| {
+                    Ok(Hazard {
+                        phenomena: hazard.info.phenomena.clone(),
+                        significance: hazard.info.significance.clone(),
+                        start_time: time("startTime", &hazard.valid_time.start_time)?,
+                        end_time: time("endTime", &hazard.valid_time.end_time)?,
+                    })
+                })
+                .collect::<Result<_, _>>()?;
+
+            Ok(Location {
+                name: raw.location_name.clone(),
+                hazards,
+            })
+        }
+    }
+
+    /// 所有縣市
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Records {
+        /// 各縣市的特報
+        pub location: Vec<Location>,
+    }
+
+    /// W-C0033-001 回應
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Data {
+        /// 特報資料
+        pub records: Records,
+    }
+}
+
+/// 颱風路徑 (W-C0034-005)，熱帶氣旋的分析與預報位置
+pub mod typhoon {
+    use super::weather_data::{local_time, number, InvalidValue, Scalar};
+    use chrono::{DateTime, Duration, FixedOffset};
+    use serde::Deserialize;
+
+    /// 單一時間點的中心位置與強度，缺值為 `None`
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Fix {
+        /// 分析時間，預報點為 預報發布時間 加上 預報時距
+        pub time: DateTime<FixedOffset>,
+        /// 中心緯度
+        pub lat: f64,
+        /// 中心經度
+        pub lon: f64,
+        /// 近中心最大風速，單位 公尺/秒
+        pub max_wind_speed: Option<f32>,
+        /// 近中心最大陣風，單位 公尺/秒
+        pub max_gust_speed: Option<f32>,
+        /// 中心氣壓，單位 百帕
+        pub pressure: Option<f32>,
+        /// 移動速度，單位 公里/小時
+        pub moving_speed: Option<f32>,
+        /// 移動方向，例如 WNW
+        pub moving_direction: Option<String>,
+        /// 七級風暴風半徑，單位 公里
+        pub radius_15ms: Option<f32>,
+    }
+
+    /// 單一熱帶氣旋
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(from = "RawCyclone")]
+    pub struct Cyclone {
+        /// 國際命名，例如 KONG-REY，熱帶性低氣壓尚未命名時為編號
+        pub name: String,
+        /// 中文名稱，例如 康芮
+        pub name_zh: Option<String>,
+        /// 分析位置，依時間排序，最後一筆為目前位置
+        pub analysis: Vec<Fix>,
+        /// 預報位置，依時間排序
+        pub forecast: Vec<Fix>,
+        /// 無法解析而略過的位置
+        pub invalid: Vec<InvalidValue>,
+    }
+
+    #[derive(Deserialize)]
+    struct RawCircle {
+        radius: Option<Scalar>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RawFix {
+        fix_time: Option<String>,
+        init_time: Option<String>,
+        tau: Option<Scalar>,
+        coordinate: String,
+        max_wind_speed: Option<Scalar>,
+        max_gust_speed: Option<Scalar>,
+        pressure: Option<Scalar>,
+        moving_speed: Option<Scalar>,
+        moving_direction: Option<Scalar>,
+        #[serde(rename = "circleOf15Ms")]
+        circle_of_15ms: Option<RawCircle>,
+    }
+
+    #[derive(Deserialize)]
+    struct RawFixes {
+        #[serde(default)]
+        fix: Vec<RawFix>,
+    }
+
+    /// CWB 原始格式，CWA 改名後部分欄位由 `cwb` 改為 `cwa` 開頭
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct RawCyclone {
+        typhoon_name: Option<String>,
+        #[serde(alias = "cwbTyphoonName")]
+        cwa_typhoon_name: Option<String>,
+        #[serde(alias = "cwbTdNo")]
+        cwa_td_no: Option<Scalar>,
+        analysis_data: Option<RawFixes>,
+        forecast_data: Option<RawFixes>,
+    }
+
+    impl From<RawCyclone> for Cyclone {
+        fn from(raw: RawCyclone) -> Self {
+            let name_zh = raw
+                .cwa_typhoon_name
+                .clone()
+                .filter(|name| !name.trim().is_empty());
+            let name = raw
+                .typhoon_name
+                .clone()
+                .filter(|name| !name.trim().is_empty())
+                .or_else(|| raw.cwa_td_no.as_ref().map(|no| format!("TD{}", no.text())))
+                .or_else(|| name_zh.clone())
+                .unwrap_or_default();
+
+            let invalid = |element: &'static str, value: String, reason: String| InvalidValue {
+                station: name.clone(),
+                element,
+                value,
+                reason,
+            };
+
+            // 未提供的項目可能為空字串，視為缺值
+            let number_of = |element: &'static str, value: Option<&Scalar>| {
+                value
+                    .map(Scalar::text)
+                    .filter(|value| !value.trim().is_empty())
+                    .map(|value| number(&value).map_err(|reason| invalid(element, value, reason)))
+                    .transpose()
+                    .map(Option::flatten)
+            };
+
+            let fix = |raw: &RawFix| {
+                // 分析點為 fixTime，預報點為 initTime + tau 小時
+                let (element, value) = match (&raw.fix_time, &raw.init_time) {
+                    (Some(time), _) => ("fixTime", time.clone()),
+                    (None, Some(time)) => ("initTime", time.clone()),
+                    (None, None) => ("fixTime", String::new()),
+                };
+                let time = match local_time(&value) {
+                    Ok(Some(time)) => time,
+                    result => {
+                        let reason = result.err().unwrap_or_else(|| "missing".into());
+                        return Err(invalid(element, value, reason));
+                    }
+                };
+                let tau = number_of("tau", raw.tau.as_ref())?.unwrap_or_default();
+
+                // lon,lat
+                let (lon, lat) = raw
+                    .coordinate
+                    .split_once(',')
+                    .and_then(|(lon, lat)| {
+                        Some((lon.trim().parse().ok()?, lat.trim().parse().ok()?))
+                    })
+                    .ok_or_else(|| {
+                        invalid(
+                            "coordinate",
+                            raw.coordinate.clone(),
+                            "expect lon,lat".into(),
+                        )
+                    })?;
+
+                Ok(Fix {
+                    time: time + Duration::minutes((tau * 60.0) as i64),
+                    lat,
+                    lon,
+                    max_wind_speed: number_of("maxWindSpeed", raw.max_wind_speed.as_ref())?,
+                    max_gust_speed: number_of("maxGustSpeed", raw.max_gust_speed.as_ref())?,
+                    pressure: number_of("pressure", raw.pressure.as_ref())?,
+                    moving_speed: number_of("movingSpeed", raw.moving_speed.as_ref())?,
+                    moving_direction: raw
+                        .moving_direction
+                        .as_ref()
+                        .map(Scalar::text)
+                        .filter(|direction| !direction.trim().is_empty()),
+                    radius_15ms: number_of(
+                        "circleOf15Ms.radius",
+                        raw.circle_of_15ms
+                            .as_ref()
+                            .and_then(|circle| circle.radius.as_ref()),
+                    )?,
+                })
+            };
+
+            // 單一位置無法解析時只略過該位置
+            let mut invalid = Vec::new();
+            let mut fixes = |data: &Option<RawFixes>| {
+                let mut fixes = Vec::new();
+                for result in data.iter().flat_map(|data| &data.fix).map(fix) {
+                    match result {
+                        Ok(fix) => fixes.push(fix),
+                        Err(err) => invalid.push(err),
+                    }
+                }
+                fixes.sort_by_key(|fix| fix.time);
+                fixes
+            };
+
+            let analysis = fixes(&raw.analysis_data);
+            let forecast = fixes(&raw.forecast_data);
+
+            Cyclone {
+                analysis,
+                forecast,
+                invalid,
+                name,
+                name_zh,
+            }
+        }
+    }
+
+    /// 所有熱帶氣旋
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct TropicalCyclones {
+        /// 目前發布中的熱帶氣旋，沒有時為空
+        #[serde(default)]
+        pub tropical_cyclone: Vec<Cyclone>,
+    }
+
+    /// 熱帶氣旋資料
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Records {
+        /// 熱帶氣旋
+        pub tropical_cyclones: TropicalCyclones,
+    }
+
+    /// W-C0034-005 回應
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Data {
+        /// 颱風路徑資料
+        pub records: Records,
+    }
 }
 
+/// 地震報告，顯著有感 (E-A0015-001) 與 小區域有感 (E-A0016-001)
+pub mod earthquake {
+    use super::weather_data::{local_time, number, InvalidValue, Scalar};
+    use chrono::{DateTime, FixedOffset};
+    use serde::{Deserialize, Serialize};
+
+    /// 地震報告的種類
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ReportKind {
+        /// 顯著有感地震，有編號
+        Significant,
+        /// 小區域有感地震，無編號
+        Local,
+    }
+
+    impl ReportKind {
+        /// 所有種類
+        pub const ALL: [ReportKind; 2] = [ReportKind::Significant, ReportKind::Local];
+
+        /// 對應的 dataset
+        pub const fn dataset(&self) -> &'static str {
+            match self {
+                ReportKind::Significant => "E-A0015-001",
+                ReportKind::Local => "E-A0016-001",
+            }
+        }
+    }
+
+    impl std::fmt::Display for ReportKind {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.dataset())
+        }
+    }
+
+    /// 震度，依 CWB 震度分級，由小到大排序
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    pub enum Intensity {
+        /// 0 級
+        #[serde(rename = "0")]
+        Zero,
+        /// 1 級
+        #[serde(rename = "1")]
+        One,
+        /// 2 級
+        #[serde(rename = "2")]
+        Two,
+        /// 3 級
+        #[serde(rename = "3")]
+        Three,
+        /// 4 級
+        #[serde(rename = "4")]
+        Four,
+        /// 5 弱
+        #[serde(rename = "5-")]
+        FiveLower,
+        /// 5 強
+        #[serde(rename = "5+")]
+        FiveUpper,
+        /// 6 弱
+        #[serde(rename = "6-")]
+        SixLower,
+        /// 6 強
+        #[serde(rename = "6+")]
+        SixUpper,
+        /// 7 級
+        #[serde(rename = "7")]
+        Seven,
+    }
+
+    impl std::str::FromStr for Intensity {
+        type Err = String;
+
+        /// CWB 的震度文字，例如 `4級`、`5弱`、`6強`
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let intensity = match s.trim() {
+                "0級" => Intensity::Zero,
+                "1級" => Intensity::One,
+                "2級" => Intensity::Two,
+                "3級" => Intensity::Three,
+                "4級" => Intensity::Four,
+                "5弱" => Intensity::FiveLower,
+                "5強" => Intensity::FiveUpper,
+                "6弱" => Intensity::SixLower,
+                "6強" => Intensity::SixUpper,
+                "7級" => Intensity::Seven,
+                _ => return Err("expect one of 0級-4級, 5弱, 5強, 6弱, 6強, 7級".into()),
+            };
+
+            Ok(intensity)
+        }
+    }
+
+    /// 震央
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Epicenter {
+        /// 位置描述，例如 花蓮縣政府南南東方 20.3 公里 (位於花蓮縣壽豐鄉)
+        pub location: String,
+        pub lat: f64,
+        pub lon: f64,
+    }
+
+    /// 單一地震報告
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawEarthquake")]
+    pub struct Report {
+        /// 地震編號，小區域有感地震沒有編號
+        pub number: Option<u64>,
+        /// 報告內容
+        pub content: String,
+        /// 報告顏色，例如 綠色、黃色
+        pub color: Option<String>,
+        /// 報告網頁
+        pub web: Option<String>,
+        /// 發震時間
+        pub origin_time: DateTime<FixedOffset>,
+        /// 震源深度，單位 公里
+        pub depth: f32,
+        /// 芮氏規模
+        pub magnitude: f32,
+        /// 震央
+        pub epicenter: Epicenter,
+        /// 各縣市的最大震度，依震度由大到小排序
+        pub intensities: Vec<(String, Intensity)>,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct RawEpicenter {
+        location: String,
+        epicenter_latitude: Scalar,
+        epicenter_longitude: Scalar,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct RawMagnitude {
+        magnitude_value: Scalar,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct RawEarthquakeInfo {
+        origin_time: String,
+        focal_depth: Scalar,
+        epicenter: RawEpicenter,
+        earthquake_magnitude: RawMagnitude,
+    }
+
+    /// 震度分布，`CountyName` 可能以 、 列出多個縣市
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct RawShakingArea {
+        county_name: String,
+        area_intensity: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct RawIntensity {
+        #[serde(default)]
+        shaking_area: Vec<RawShakingArea>,
+    }
+
+    /// CWA 格式的單一地震報告
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct RawEarthquake {
+        earthquake_no: Option<Scalar>,
+        report_content: String,
+        report_color: Option<String>,
+        web: Option<String>,
+        earthquake_info: RawEarthquakeInfo,
+        intensity: Option<RawIntensity>,
+    }
+
+    impl TryFrom<RawEarthquake> for Report {
+        type Error = InvalidValue;
+
+        fn try_from(raw: RawEarthquake) -> Result<Self, Self::Error> {
+            let info = &raw.earthquake_info;
+            let invalid = |element: &'static str, value: String, reason: String| InvalidValue {
+                station: format!("{} {}", info.origin_time, info.epicenter.location),
+                element,
+                value,
+                reason,
+            };
+
+            let required = |element: &'static str, value: &Scalar| {
+                let value = value.text();
+                match number(&value) {
+                    Ok(Some(number)) => Ok(number),
+                    result => {
+                        let reason = result.err().unwrap_or_else(|| "missing".into());
+                        Err(invalid(element, value, reason))
+                    }
+                }
+            };
+            let degrees = |element: &'static str, value: &Scalar| {
+                let value = value.text();
+                value
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|err| invalid(element, value.clone(), err.to_string()))
+            };
+
+            let origin_time = match local_time(&info.origin_time) {
+                Ok(Some(time)) => time,
+                result => {
+                    let reason = result.err().unwrap_or_else(|| "missing".into());
+                    return Err(invalid("OriginTime", info.origin_time.clone(), reason));
+                }
+            };
+
+            // 小區域有感地震的編號結尾為 000
+            let number = raw
+                .earthquake_no
+                .as_ref()
+                .map(|no| {
+                    let no = no.text();
+                    no.trim()
+                        .parse::<u64>()
+                        .map_err(|err| invalid("EarthquakeNo", no.clone(), err.to_string()))
+                })
+                .transpose()?
+                .filter(|no| no % 1000 != 0);
+
+            // 同一縣市取最大震度
+            let mut intensities: Vec<(String, Intensity)> = Vec::new();
+            for area in raw
+                .intensity
+                .iter()
+                .flat_map(|intensity| &intensity.shaking_area)
+            {
+                let intensity: Intensity = area.area_intensity.parse().map_err(|reason| {
+                    invalid("AreaIntensity", area.area_intensity.clone(), reason)
+                })?;
+
+                for county in area.county_name.split(['、', ',']) {
+                    let county = county.trim();
+                    if county.is_empty() {
+                        continue;
+                    }
+
+                    match intensities.iter_mut().find(|(name, _)| name == county) {
+                        Some((_, max)) => *max = (*max).max(intensity),
+                        None => intensities.push((county.to_owned(), intensity)),
+                    }
+                }
+            }
+            intensities.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+            Ok(Report {
+                number,
+                color: raw.report_color.clone(),
+                web: raw.web.clone(),
+                origin_time,
+                depth: required("FocalDepth", &info.focal_depth)?,
+                magnitude: required("MagnitudeValue", &info.earthquake_magnitude.magnitude_value)?,
+                epicenter: Epicenter {
+                    location: info.epicenter.location.clone(),
+                    lat: degrees("EpicenterLatitude", &info.epicenter.epicenter_latitude)?,
+                    lon: degrees("EpicenterLongitude", &info.epicenter.epicenter_longitude)?,
+                },
+                intensities,
+                content: raw.report_content.clone(),
+            })
+        }
+    }
+
+    /// 地震報告列表
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Records {
+        /// 地震報告，依發震時間由新到舊
+        #[serde(rename = "Earthquake")]
+        pub earthquakes: Vec<Report>,
+    }
+
+    /// E-A0015-001、E-A0016-001 回應
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Data {
+        /// 地震報告
+        pub records: Records,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn takes_the_strongest_intensity_per_county() {
+            let report: Report = serde_json::from_str(
+                r#"{
+                    "EarthquakeNo": 115045,
+                    "ReportContent": "10/19-13:40花蓮縣壽豐鄉發生規模5.2有感地震，最大震度5弱。",
+                    "EarthquakeInfo": {
+                        "OriginTime": "2026-10-19 13:40:12",
+                        "FocalDepth": 10.0,
+                        "Epicenter": {
+                            "Location": "花蓮縣政府南南東方 20.3 公里 (位於花蓮縣壽豐鄉)",
+                            "EpicenterLatitude": 23.8, "EpicenterLongitude": 121.6
+                        },
+                        "EarthquakeMagnitude": {"MagnitudeType": "芮氏規模", "MagnitudeValue": 5.2}
+                    },
+                    "Intensity": {"ShakingArea": [
+                        {"AreaDesc": "最大震度5弱地區", "CountyName": "花蓮縣", "AreaIntensity": "5弱"},
+                        {"AreaDesc": "最大震度3級地區", "CountyName": "宜蘭縣、花蓮縣", "AreaIntensity": "3級"},
+                        {"AreaDesc": "臺北市地區", "CountyName": "臺北市", "AreaIntensity": "2級"}
+                    ]}
+                }"#,
+            )
+            .unwrap();
+
+            assert_eq!(report.number, Some(115045));
+            assert_eq!(report.origin_time.to_rfc3339(), "2026-10-19T13:40:12+08:00");
+            assert_eq!(
+                report.intensities,
+                [
+                    ("花蓮縣".to_owned(), Intensity::FiveLower),
+                    ("宜蘭縣".to_owned(), Intensity::Three),
+                    ("臺北市".to_owned(), Intensity::Two),
+                ]
+            );
+        }
+    }
+}
+
+/// 鄉鎮天氣預報 (F-D0047-001 ~ F-D0047-091)
 pub mod forecast {
     use serde::{Deserialize, Serialize};
 
+    /// 鄉鎮天氣預報的 dataset，`Display` 與 `FromStr` 使用 dataset 編號
     #[derive(Debug, PartialEq)]
     pub enum ForecastType {
         /// 鄉鎮天氣預報-宜蘭縣未來2天天氣預報 - F-D0047-001
@@ -241,8 +2129,10 @@ pub mod forecast {
         TaiwanInWeek,
     }
 
+    /// 無法解析的 dataset 編號
     #[derive(Debug)]
     pub enum CustomError {
+        /// 不是鄉鎮天氣預報的 dataset 編號
         ParseError,
     }
 
@@ -312,6 +2202,39 @@ pub mod forecast {
         }
     }
 
+    impl ForecastType {
+        /// 縣市的未來1週鄉鎮預報，縣市名稱 臺、台 皆可
+        pub fn in_week(city: &str) -> Option<ForecastType> {
+            let forecast_type = match city.trim().replace('台', "臺").as_str() {
+                "宜蘭縣" => ForecastType::YilanCountyInWeek,
+                "桃園市" => ForecastType::TaoyuanCityInWeek,
+                "新竹縣" => ForecastType::HsinchuCountyInWeek,
+                "苗栗縣" => ForecastType::MiaoliCountyInWeek,
+                "彰化縣" => ForecastType::ChanghuaCountyInWeek,
+                "南投縣" => ForecastType::NantouCountyInWeek,
+                "雲林縣" => ForecastType::YunlinCountyInWeek,
+                "嘉義縣" => ForecastType::ChiayiCountyInWeek,
+                "屏東縣" => ForecastType::PingtungCountyInWeek,
+                "臺東縣" => ForecastType::TaitungCountyInWeek,
+                "花蓮縣" => ForecastType::HualienCountyInWeek,
+                "澎湖縣" => ForecastType::PenghuCountyInWeek,
+                "基隆市" => ForecastType::KeelungCountyInWeek,
+                "新竹市" => ForecastType::HsinchuCityInWeek,
+                "嘉義市" => ForecastType::ChiayiCityInWeek,
+                "臺北市" => ForecastType::TaipeiCityInWeek,
+                "高雄市" => ForecastType::KaohsiungCityInWeek,
+                "新北市" => ForecastType::NewTaipeiCityInWeek,
+                "臺中市" => ForecastType::TaichungCityInWeek,
+                "臺南市" => ForecastType::TainanCityInWeek,
+                "連江縣" => ForecastType::LienchiangCountyInWeek,
+                "金門縣" => ForecastType::KinmenCountyInWeek,
+                _ => return None,
+            };
+
+            Some(forecast_type)
+        }
+    }
+
     impl std::fmt::Display for ForecastType {
         fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
             let msg = match self {
@@ -367,7 +2290,9 @@ pub mod forecast {
         }
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    /// 預報項目名稱
+    #[allow(clippy::upper_case_acronyms)]
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
     pub enum WeatherElementName {
         /// 12小時降雨機率
         #[serde(alias = "PoP12h")]
@@ -442,24 +2367,32 @@ pub mod forecast {
         UVI,
     }
 
+    /// 預報值與其單位
     #[derive(Serialize, Deserialize, Debug)]
     pub struct WeatherElementValue {
+        /// 預報值，原始文字
         pub value: String,
+        /// 單位，例如 攝氏度、百分比、NA
         pub measures: String,
     }
 
+    /// 單一時段的預報
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Time {
+        /// 預報值，部分項目有多個值
         #[serde(alias = "elementValue")]
         pub value: Vec<WeatherElementValue>,
 
+        /// 結束時間，yyyy-MM-dd hh:mm:ss
         #[serde(alias = "endTime")]
         pub end_time: String,
 
+        /// 開始時間，yyyy-MM-dd hh:mm:ss
         #[serde(alias = "startTime")]
         pub start_time: String,
     }
 
+    /// 單一預報項目的所有時段
     #[derive(Serialize, Deserialize, Debug)]
     pub struct WeatherElement {
         #[serde(alias = "elementName")]
@@ -469,27 +2402,42 @@ pub mod forecast {
         pub time: Vec<Time>,
     }
 
+    /// 單一鄉鎮的預報
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Location {
         #[serde(alias = "locationName")]
         pub name: String,
 
+        /// 緯度，原始文字，部分 dataset 沒有
+        #[serde(default)]
+        pub lat: Option<String>,
+
+        /// 經度，原始文字，部分 dataset 沒有
+        #[serde(default)]
+        pub lon: Option<String>,
+
         #[serde(alias = "weatherElement")]
         pub weather_elements: Vec<WeatherElement>,
     }
 
+    /// 單一縣市的所有鄉鎮
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Wrapper {
+        #[serde(alias = "locationsName", default)]
+        pub name: String,
+
         #[serde(alias = "location")]
         pub location: Vec<Location>,
     }
 
+    /// 預報資料
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Records {
         #[serde(alias = "locations")]
         pub locations: Vec<Wrapper>,
     }
 
+    /// F-D0047-* 回應
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Response {
         #[serde(alias = "records")]
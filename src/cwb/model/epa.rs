@@ -0,0 +1,276 @@
+//! 環境部 (MOENV) 開放資料原始格式：紫外線即時監測 (uv_s_01) 與空氣品質指標 (aqx_p_432)
+//!
+//! 所有欄位皆為文字，空字串、`-`、`ND` 表示缺值，發布時間為未標示時區的台灣時間。
+
+use super::cwb::weather_data::InvalidValue;
+use chrono::{DateTime, FixedOffset, NaiveDateTime};
+
+fn is_missing(value: &str) -> bool {
+    matches!(value.trim(), "" | "-" | "ND" | "NA")
+}
+
+/// 缺值回傳 `None`，其餘以 `parse` 解析
+fn optional<T, E: std::fmt::Display>(
+    value: &str,
+    parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<Option<T>, String> {
+    if is_missing(value) {
+        return Ok(None);
+    }
+
+    parse(value.trim()).map(Some).map_err(|err| err.to_string())
+}
+
+fn number(value: &str) -> Result<Option<f32>, String> {
+    optional(value, str::parse::<f32>)
+}
+
+fn text(value: &str) -> Option<String> {
+    (!is_missing(value)).then(|| value.trim().to_owned())
+}
+
+/// yyyy/MM/dd hh:mm:ss、yyyy-MM-dd hh:mm:ss 或 yyyy-MM-dd hh:mm
+fn publish_time(value: &str) -> Result<Option<DateTime<FixedOffset>>, String> {
+    let taiwan = FixedOffset::east_opt(8 * 3600).unwrap();
+
+    optional(value, |value| {
+        ["%Y/%m/%d %H:%M:%S", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+            .map(|time| time.and_local_timezone(taiwan).unwrap())
+            .ok_or("expect yyyy/MM/dd hh:mm:ss")
+    })
+}
+
+/// 十進位度數，例如 `121.5150`，或以逗號分隔的 度,分,秒，例如 `121,30,54`
+fn degrees(value: &str) -> Result<Option<f64>, String> {
+    optional(value, |value| {
+        let parts = value
+            .split(',')
+            .map(|part| part.trim().parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?;
+
+        match parts[..] {
+            [degrees] => Ok(degrees),
+            [degrees, minutes, seconds] => Ok(degrees + minutes / 60.0 + seconds / 3600.0),
+            _ => Err("expect decimal degrees or degrees,minutes,seconds".to_owned()),
+        }
+    })
+}
+
+/// 必要欄位，缺值或格式錯誤皆回傳 `InvalidValue`
+fn required<T>(
+    site: &str,
+    element: &'static str,
+    value: &str,
+    parse: impl FnOnce(&str) -> Result<Option<T>, String>,
+) -> Result<T, InvalidValue> {
+    let error = |reason: String| InvalidValue {
+        station: site.to_owned(),
+        element,
+        value: value.to_owned(),
+        reason,
+    };
+
+    parse(value)
+        .map_err(error)?
+        .ok_or_else(|| error("missing".into()))
+}
+
+/// 紫外線即時監測 (uv_s_01)，環境部與中央氣象署的測站每小時發布
+pub mod uv {
+    use super::{degrees, number, publish_time, required, InvalidValue};
+    use chrono::{DateTime, FixedOffset};
+    use serde::Deserialize;
+
+    /// 單一監測站的紫外線指數
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawSite")]
+    pub struct Site {
+        /// 監測站名稱
+        pub name: String,
+        pub county: String,
+        /// 紫外線指數，缺值時為 `None`
+        pub uvi: Option<f32>,
+        /// 發布機關，例如 環境部、中央氣象署
+        pub agency: String,
+        pub lat: f64,
+        pub lon: f64,
+        pub published_at: DateTime<FixedOffset>,
+    }
+
+    /// 環境部原始格式，座標可能為 度,分,秒
+    #[derive(Deserialize)]
+    struct RawSite {
+        sitename: String,
+        #[serde(default)]
+        county: String,
+        #[serde(default)]
+        uvi: String,
+        #[serde(default)]
+        publishagency: String,
+        #[serde(default)]
+        wgs84lat: String,
+        #[serde(default)]
+        wgs84lon: String,
+        #[serde(default)]
+        publishtime: String,
+    }
+
+    impl TryFrom<RawSite> for Site {
+        type Error = InvalidValue;
+
+        fn try_from(raw: RawSite) -> Result<Self, Self::Error> {
+            let site = raw.sitename.as_str();
+
+            Ok(Site {
+                uvi: number(&raw.uvi).map_err(|reason| InvalidValue {
+                    station: site.to_owned(),
+                    element: "uvi",
+                    value: raw.uvi.to_owned(),
+                    reason,
+                })?,
+                lat: required(site, "wgs84lat", &raw.wgs84lat, degrees)?,
+                lon: required(site, "wgs84lon", &raw.wgs84lon, degrees)?,
+                published_at: required(site, "publishtime", &raw.publishtime, publish_time)?,
+                name: raw.sitename.trim().to_owned(),
+                county: raw.county.trim().to_owned(),
+                agency: raw.publishagency.trim().to_owned(),
+            })
+        }
+    }
+
+    /// uv_s_01 回應
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Data {
+        /// 各監測站資料
+        pub records: Vec<Site>,
+    }
+}
+
+/// 空氣品質指標 (aqx_p_432)，環境部空氣品質監測站每小時發布
+pub mod air_quality {
+    use super::{degrees, number, optional, publish_time, required, text, InvalidValue};
+    use chrono::{DateTime, FixedOffset};
+    use serde::Deserialize;
+
+    /// 單一監測站的空氣品質
+    #[derive(Deserialize, Debug, Clone)]
+    #[serde(try_from = "RawSite")]
+    pub struct Site {
+        /// 監測站名稱
+        pub name: String,
+        pub county: String,
+        /// 空氣品質指標 (AQI)
+        pub aqi: Option<u16>,
+        /// 狀態，例如 良好、普通
+        pub status: Option<String>,
+        /// 指標污染物，例如 細懸浮微粒
+        pub pollutant: Option<String>,
+        /// 細懸浮微粒 (PM2.5) 濃度，單位 μg/m3
+        pub pm2_5: Option<f32>,
+        /// 懸浮微粒 (PM10) 濃度，單位 μg/m3
+        pub pm10: Option<f32>,
+        pub lat: f64,
+        pub lon: f64,
+        pub published_at: DateTime<FixedOffset>,
+    }
+
+    /// 環境部原始格式，只列出使用的欄位
+    #[derive(Deserialize)]
+    struct RawSite {
+        sitename: String,
+        #[serde(default)]
+        county: String,
+        #[serde(default)]
+        aqi: String,
+        #[serde(default)]
+        status: String,
+        #[serde(default)]
+        pollutant: String,
+        #[serde(default, rename = "pm2.5")]
+        pm2_5: String,
+        #[serde(default)]
+        pm10: String,
+        #[serde(default)]
+        latitude: String,
+        #[serde(default)]
+        longitude: String,
+        #[serde(default)]
+        publishtime: String,
+    }
+
+    impl TryFrom<RawSite> for Site {
+        type Error = InvalidValue;
+
+        fn try_from(raw: RawSite) -> Result<Self, Self::Error> {
+            let site = raw.sitename.as_str();
+            let invalid = |element: &'static str, value: &str| {
+                let value = value.to_owned();
+                move |reason| InvalidValue {
+                    station: site.to_owned(),
+                    element,
+                    value,
+                    reason,
+                }
+            };
+
+            Ok(Site {
+                aqi: optional(&raw.aqi, str::parse::<u16>).map_err(invalid("aqi", &raw.aqi))?,
+                pm2_5: number(&raw.pm2_5).map_err(invalid("pm2.5", &raw.pm2_5))?,
+                pm10: number(&raw.pm10).map_err(invalid("pm10", &raw.pm10))?,
+                lat: required(site, "latitude", &raw.latitude, degrees)?,
+                lon: required(site, "longitude", &raw.longitude, degrees)?,
+                published_at: required(site, "publishtime", &raw.publishtime, publish_time)?,
+                status: text(&raw.status),
+                pollutant: text(&raw.pollutant),
+                name: raw.sitename.trim().to_owned(),
+                county: raw.county.trim().to_owned(),
+            })
+        }
+    }
+
+    /// aqx_p_432 回應
+    #[derive(Deserialize, Debug, Clone)]
+    pub struct Data {
+        /// 各監測站資料
+        pub records: Vec<Site>,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_degrees_minutes_seconds() {
+        let site: uv::Site = serde_json::from_str(
+            r#"{"sitename": "臺北", "county": "臺北市", "uvi": "6.5",
+                "publishagency": "中央氣象署", "wgs84lon": "121,30,54", "wgs84lat": "25,2,22",
+                "publishtime": "2026-10-19 14:00"}"#,
+        )
+        .unwrap();
+
+        assert!((site.lon - 121.515).abs() < 1e-6);
+        assert!((site.lat - 25.039_444).abs() < 1e-6);
+        assert_eq!(site.published_at.to_rfc3339(), "2026-10-19T14:00:00+08:00");
+    }
+
+    #[test]
+    fn missing_values_are_none() {
+        let site: air_quality::Site = serde_json::from_str(
+            r#"{"sitename": "萬華", "county": "臺北市", "aqi": "", "status": "設備維護",
+                "pollutant": "", "pm2.5": "-", "pm10": "ND",
+                "longitude": "121.507972", "latitude": "25.046503",
+                "publishtime": "2026/10/19 14:00:00"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(site.aqi, None);
+        assert_eq!(site.pm2_5, None);
+        assert_eq!(site.pm10, None);
+        assert_eq!(site.pollutant, None);
+        assert_eq!(site.status.as_deref(), Some("設備維護"));
+    }
+}
@@ -0,0 +1,514 @@
+/// (中文, 英文)
+pub type Name = (&'static str, &'static str);
+
+/// (縣市中文, 縣市英文, 所轄鄉鎮市區)
+pub type County = (&'static str, &'static str, &'static [Name]);
+
+/// 縣市 與 所轄鄉鎮市區 的英文名稱 (依內政部公告之英譯)
+///
+/// 鄉鎮市區僅列出專名，通名 (區、市、鎮、鄉) 由 `suffix` 補上。
+pub const COUNTIES: &[County] = &[
+    (
+        "臺北市",
+        "Taipei City",
+        &[
+            ("中正區", "Zhongzheng"),
+            ("大同區", "Datong"),
+            ("中山區", "Zhongshan"),
+            ("松山區", "Songshan"),
+            ("大安區", "Da'an"),
+            ("萬華區", "Wanhua"),
+            ("信義區", "Xinyi"),
+            ("士林區", "Shilin"),
+            ("北投區", "Beitou"),
+            ("內湖區", "Neihu"),
+            ("南港區", "Nangang"),
+            ("文山區", "Wenshan"),
+        ],
+    ),
+    (
+        "新北市",
+        "New Taipei City",
+        &[
+            ("板橋區", "Banqiao"),
+            ("三重區", "Sanchong"),
+            ("中和區", "Zhonghe"),
+            ("永和區", "Yonghe"),
+            ("新莊區", "Xinzhuang"),
+            ("新店區", "Xindian"),
+            ("樹林區", "Shulin"),
+            ("鶯歌區", "Yingge"),
+            ("三峽區", "Sanxia"),
+            ("淡水區", "Tamsui"),
+            ("汐止區", "Xizhi"),
+            ("瑞芳區", "Ruifang"),
+            ("土城區", "Tucheng"),
+            ("蘆洲區", "Luzhou"),
+            ("五股區", "Wugu"),
+            ("泰山區", "Taishan"),
+            ("林口區", "Linkou"),
+            ("深坑區", "Shenkeng"),
+            ("石碇區", "Shiding"),
+            ("坪林區", "Pinglin"),
+            ("三芝區", "Sanzhi"),
+            ("石門區", "Shimen"),
+            ("八里區", "Bali"),
+            ("平溪區", "Pingxi"),
+            ("雙溪區", "Shuangxi"),
+            ("貢寮區", "Gongliao"),
+            ("金山區", "Jinshan"),
+            ("萬里區", "Wanli"),
+            ("烏來區", "Wulai"),
+        ],
+    ),
+    (
+        "基隆市",
+        "Keelung City",
+        &[
+            ("仁愛區", "Ren'ai"),
+            ("信義區", "Xinyi"),
+            ("中正區", "Zhongzheng"),
+            ("中山區", "Zhongshan"),
+            ("安樂區", "Anle"),
+            ("暖暖區", "Nuannuan"),
+            ("七堵區", "Qidu"),
+        ],
+    ),
+    (
+        "桃園市",
+        "Taoyuan City",
+        &[
+            ("桃園區", "Taoyuan"),
+            ("中壢區", "Zhongli"),
+            ("大溪區", "Daxi"),
+            ("楊梅區", "Yangmei"),
+            ("蘆竹區", "Luzhu"),
+            ("大園區", "Dayuan"),
+            ("龜山區", "Guishan"),
+            ("八德區", "Bade"),
+            ("龍潭區", "Longtan"),
+            ("平鎮區", "Pingzhen"),
+            ("新屋區", "Xinwu"),
+            ("觀音區", "Guanyin"),
+            ("復興區", "Fuxing"),
+        ],
+    ),
+    (
+        "新竹縣",
+        "Hsinchu County",
+        &[
+            ("竹北市", "Zhubei"),
+            ("竹東鎮", "Zhudong"),
+            ("新埔鎮", "Xinpu"),
+            ("關西鎮", "Guanxi"),
+            ("湖口鄉", "Hukou"),
+            ("新豐鄉", "Xinfeng"),
+            ("芎林鄉", "Qionglin"),
+            ("橫山鄉", "Hengshan"),
+            ("北埔鄉", "Beipu"),
+            ("寶山鄉", "Baoshan"),
+            ("峨眉鄉", "Emei"),
+            ("尖石鄉", "Jianshi"),
+            ("五峰鄉", "Wufeng"),
+        ],
+    ),
+    (
+        "新竹市",
+        "Hsinchu City",
+        &[("東區", "East"), ("北區", "North"), ("香山區", "Xiangshan")],
+    ),
+    (
+        "苗栗縣",
+        "Miaoli County",
+        &[
+            ("苗栗市", "Miaoli"),
+            ("頭份市", "Toufen"),
+            ("苑裡鎮", "Yuanli"),
+            ("通霄鎮", "Tongxiao"),
+            ("竹南鎮", "Zhunan"),
+            ("後龍鎮", "Houlong"),
+            ("卓蘭鎮", "Zhuolan"),
+            ("大湖鄉", "Dahu"),
+            ("公館鄉", "Gongguan"),
+            ("銅鑼鄉", "Tongluo"),
+            ("南庄鄉", "Nanzhuang"),
+            ("頭屋鄉", "Touwu"),
+            ("三義鄉", "Sanyi"),
+            ("西湖鄉", "Xihu"),
+            ("造橋鄉", "Zaoqiao"),
+            ("三灣鄉", "Sanwan"),
+            ("獅潭鄉", "Shitan"),
+            ("泰安鄉", "Tai'an"),
+        ],
+    ),
+    (
+        "臺中市",
+        "Taichung City",
+        &[
+            ("中區", "Central"),
+            ("東區", "East"),
+            ("南區", "South"),
+            ("西區", "West"),
+            ("北區", "North"),
+            ("北屯區", "Beitun"),
+            ("西屯區", "Xitun"),
+            ("南屯區", "Nantun"),
+            ("太平區", "Taiping"),
+            ("大里區", "Dali"),
+            ("霧峰區", "Wufeng"),
+            ("烏日區", "Wuri"),
+            ("豐原區", "Fengyuan"),
+            ("后里區", "Houli"),
+            ("石岡區", "Shigang"),
+            ("東勢區", "Dongshi"),
+            ("和平區", "Heping"),
+            ("新社區", "Xinshe"),
+            ("潭子區", "Tanzi"),
+            ("大雅區", "Daya"),
+            ("神岡區", "Shengang"),
+            ("大肚區", "Dadu"),
+            ("沙鹿區", "Shalu"),
+            ("龍井區", "Longjing"),
+            ("梧棲區", "Wuqi"),
+            ("清水區", "Qingshui"),
+            ("大甲區", "Dajia"),
+            ("外埔區", "Waipu"),
+            ("大安區", "Da'an"),
+        ],
+    ),
+    (
+        "彰化縣",
+        "Changhua County",
+        &[
+            ("彰化市", "Changhua"),
+            ("員林市", "Yuanlin"),
+            ("鹿港鎮", "Lukang"),
+            ("和美鎮", "Hemei"),
+            ("北斗鎮", "Beidou"),
+            ("溪湖鎮", "Xihu"),
+            ("田中鎮", "Tianzhong"),
+            ("二林鎮", "Erlin"),
+            ("線西鄉", "Xianxi"),
+            ("伸港鄉", "Shengang"),
+            ("福興鄉", "Fuxing"),
+            ("秀水鄉", "Xiushui"),
+            ("花壇鄉", "Huatan"),
+            ("芬園鄉", "Fenyuan"),
+            ("大村鄉", "Dacun"),
+            ("埔鹽鄉", "Puyan"),
+            ("埔心鄉", "Puxin"),
+            ("永靖鄉", "Yongjing"),
+            ("社頭鄉", "Shetou"),
+            ("二水鄉", "Ershui"),
+            ("田尾鄉", "Tianwei"),
+            ("埤頭鄉", "Pitou"),
+            ("芳苑鄉", "Fangyuan"),
+            ("大城鄉", "Dacheng"),
+            ("竹塘鄉", "Zhutang"),
+            ("溪州鄉", "Xizhou"),
+        ],
+    ),
+    (
+        "南投縣",
+        "Nantou County",
+        &[
+            ("南投市", "Nantou"),
+            ("埔里鎮", "Puli"),
+            ("草屯鎮", "Caotun"),
+            ("竹山鎮", "Zhushan"),
+            ("集集鎮", "Jiji"),
+            ("名間鄉", "Mingjian"),
+            ("鹿谷鄉", "Lugu"),
+            ("中寮鄉", "Zhongliao"),
+            ("魚池鄉", "Yuchi"),
+            ("國姓鄉", "Guoxing"),
+            ("水里鄉", "Shuili"),
+            ("信義鄉", "Xinyi"),
+            ("仁愛鄉", "Ren'ai"),
+        ],
+    ),
+    (
+        "雲林縣",
+        "Yunlin County",
+        &[
+            ("斗六市", "Douliu"),
+            ("斗南鎮", "Dounan"),
+            ("虎尾鎮", "Huwei"),
+            ("西螺鎮", "Xiluo"),
+            ("土庫鎮", "Tuku"),
+            ("北港鎮", "Beigang"),
+            ("古坑鄉", "Gukeng"),
+            ("大埤鄉", "Dapi"),
+            ("莿桐鄉", "Citong"),
+            ("林內鄉", "Linnei"),
+            ("二崙鄉", "Erlun"),
+            ("崙背鄉", "Lunbei"),
+            ("麥寮鄉", "Mailiao"),
+            ("東勢鄉", "Dongshi"),
+            ("褒忠鄉", "Baozhong"),
+            ("臺西鄉", "Taixi"),
+            ("元長鄉", "Yuanchang"),
+            ("四湖鄉", "Sihu"),
+            ("口湖鄉", "Kouhu"),
+            ("水林鄉", "Shuilin"),
+        ],
+    ),
+    (
+        "嘉義縣",
+        "Chiayi County",
+        &[
+            ("太保市", "Taibao"),
+            ("朴子市", "Puzi"),
+            ("布袋鎮", "Budai"),
+            ("大林鎮", "Dalin"),
+            ("民雄鄉", "Minxiong"),
+            ("溪口鄉", "Xikou"),
+            ("新港鄉", "Xingang"),
+            ("六腳鄉", "Liujiao"),
+            ("東石鄉", "Dongshi"),
+            ("義竹鄉", "Yizhu"),
+            ("鹿草鄉", "Lucao"),
+            ("水上鄉", "Shuishang"),
+            ("中埔鄉", "Zhongpu"),
+            ("竹崎鄉", "Zhuqi"),
+            ("梅山鄉", "Meishan"),
+            ("番路鄉", "Fanlu"),
+            ("大埔鄉", "Dapu"),
+            ("阿里山鄉", "Alishan"),
+        ],
+    ),
+    (
+        "嘉義市",
+        "Chiayi City",
+        &[("東區", "East"), ("西區", "West")],
+    ),
+    (
+        "臺南市",
+        "Tainan City",
+        &[
+            ("中西區", "West Central"),
+            ("東區", "East"),
+            ("南區", "South"),
+            ("北區", "North"),
+            ("安平區", "Anping"),
+            ("安南區", "Annan"),
+            ("永康區", "Yongkang"),
+            ("歸仁區", "Guiren"),
+            ("新化區", "Xinhua"),
+            ("左鎮區", "Zuozhen"),
+            ("玉井區", "Yujing"),
+            ("楠西區", "Nanxi"),
+            ("南化區", "Nanhua"),
+            ("仁德區", "Rende"),
+            ("關廟區", "Guanmiao"),
+            ("龍崎區", "Longqi"),
+            ("官田區", "Guantian"),
+            ("麻豆區", "Madou"),
+            ("佳里區", "Jiali"),
+            ("西港區", "Xigang"),
+            ("七股區", "Qigu"),
+            ("將軍區", "Jiangjun"),
+            ("學甲區", "Xuejia"),
+            ("北門區", "Beimen"),
+            ("新營區", "Xinying"),
+            ("後壁區", "Houbi"),
+            ("白河區", "Baihe"),
+            ("東山區", "Dongshan"),
+            ("六甲區", "Liujia"),
+            ("下營區", "Xiaying"),
+            ("柳營區", "Liuying"),
+            ("鹽水區", "Yanshui"),
+            ("善化區", "Shanhua"),
+            ("大內區", "Danei"),
+            ("山上區", "Shanshang"),
+            ("新市區", "Xinshi"),
+            ("安定區", "Anding"),
+        ],
+    ),
+    (
+        "高雄市",
+        "Kaohsiung City",
+        &[
+            ("新興區", "Xinxing"),
+            ("前金區", "Qianjin"),
+            ("苓雅區", "Lingya"),
+            ("鹽埕區", "Yancheng"),
+            ("鼓山區", "Gushan"),
+            ("旗津區", "Qijin"),
+            ("前鎮區", "Qianzhen"),
+            ("三民區", "Sanmin"),
+            ("楠梓區", "Nanzi"),
+            ("小港區", "Xiaogang"),
+            ("左營區", "Zuoying"),
+            ("仁武區", "Renwu"),
+            ("大社區", "Dashe"),
+            ("岡山區", "Gangshan"),
+            ("路竹區", "Luzhu"),
+            ("阿蓮區", "Alian"),
+            ("田寮區", "Tianliao"),
+            ("燕巢區", "Yanchao"),
+            ("橋頭區", "Qiaotou"),
+            ("梓官區", "Ziguan"),
+            ("彌陀區", "Mituo"),
+            ("永安區", "Yong'an"),
+            ("湖內區", "Hunei"),
+            ("鳳山區", "Fengshan"),
+            ("大寮區", "Daliao"),
+            ("林園區", "Linyuan"),
+            ("鳥松區", "Niaosong"),
+            ("大樹區", "Dashu"),
+            ("旗山區", "Qishan"),
+            ("美濃區", "Meinong"),
+            ("六龜區", "Liugui"),
+            ("內門區", "Neimen"),
+            ("杉林區", "Shanlin"),
+            ("甲仙區", "Jiaxian"),
+            ("桃源區", "Taoyuan"),
+            ("那瑪夏區", "Namaxia"),
+            ("茂林區", "Maolin"),
+            ("茄萣區", "Qieding"),
+        ],
+    ),
+    (
+        "屏東縣",
+        "Pingtung County",
+        &[
+            ("屏東市", "Pingtung"),
+            ("潮州鎮", "Chaozhou"),
+            ("東港鎮", "Donggang"),
+            ("恆春鎮", "Hengchun"),
+            ("萬丹鄉", "Wandan"),
+            ("長治鄉", "Changzhi"),
+            ("麟洛鄉", "Linluo"),
+            ("九如鄉", "Jiuru"),
+            ("里港鄉", "Ligang"),
+            ("鹽埔鄉", "Yanpu"),
+            ("高樹鄉", "Gaoshu"),
+            ("萬巒鄉", "Wanluan"),
+            ("內埔鄉", "Neipu"),
+            ("竹田鄉", "Zhutian"),
+            ("新埤鄉", "Xinpi"),
+            ("枋寮鄉", "Fangliao"),
+            ("新園鄉", "Xinyuan"),
+            ("崁頂鄉", "Kanding"),
+            ("林邊鄉", "Linbian"),
+            ("南州鄉", "Nanzhou"),
+            ("佳冬鄉", "Jiadong"),
+            ("琉球鄉", "Liuqiu"),
+            ("車城鄉", "Checheng"),
+            ("滿州鄉", "Manzhou"),
+            ("枋山鄉", "Fangshan"),
+            ("三地門鄉", "Sandimen"),
+            ("霧臺鄉", "Wutai"),
+            ("瑪家鄉", "Majia"),
+            ("泰武鄉", "Taiwu"),
+            ("來義鄉", "Laiyi"),
+            ("春日鄉", "Chunri"),
+            ("獅子鄉", "Shizi"),
+            ("牡丹鄉", "Mudan"),
+        ],
+    ),
+    (
+        "宜蘭縣",
+        "Yilan County",
+        &[
+            ("宜蘭市", "Yilan"),
+            ("羅東鎮", "Luodong"),
+            ("蘇澳鎮", "Su'ao"),
+            ("頭城鎮", "Toucheng"),
+            ("礁溪鄉", "Jiaoxi"),
+            ("壯圍鄉", "Zhuangwei"),
+            ("員山鄉", "Yuanshan"),
+            ("冬山鄉", "Dongshan"),
+            ("五結鄉", "Wujie"),
+            ("三星鄉", "Sanxing"),
+            ("大同鄉", "Datong"),
+            ("南澳鄉", "Nan'ao"),
+        ],
+    ),
+    (
+        "花蓮縣",
+        "Hualien County",
+        &[
+            ("花蓮市", "Hualien"),
+            ("鳳林鎮", "Fenglin"),
+            ("玉里鎮", "Yuli"),
+            ("新城鄉", "Xincheng"),
+            ("吉安鄉", "Ji'an"),
+            ("壽豐鄉", "Shoufeng"),
+            ("光復鄉", "Guangfu"),
+            ("豐濱鄉", "Fengbin"),
+            ("瑞穗鄉", "Ruisui"),
+            ("富里鄉", "Fuli"),
+            ("秀林鄉", "Xiulin"),
+            ("萬榮鄉", "Wanrong"),
+            ("卓溪鄉", "Zhuoxi"),
+        ],
+    ),
+    (
+        "臺東縣",
+        "Taitung County",
+        &[
+            ("臺東市", "Taitung"),
+            ("成功鎮", "Chenggong"),
+            ("關山鎮", "Guanshan"),
+            ("卑南鄉", "Beinan"),
+            ("大武鄉", "Dawu"),
+            ("太麻里鄉", "Taimali"),
+            ("東河鄉", "Donghe"),
+            ("長濱鄉", "Changbin"),
+            ("鹿野鄉", "Luye"),
+            ("池上鄉", "Chishang"),
+            ("綠島鄉", "Ludao"),
+            ("延平鄉", "Yanping"),
+            ("海端鄉", "Haiduan"),
+            ("達仁鄉", "Daren"),
+            ("金峰鄉", "Jinfeng"),
+            ("蘭嶼鄉", "Lanyu"),
+        ],
+    ),
+    (
+        "澎湖縣",
+        "Penghu County",
+        &[
+            ("馬公市", "Magong"),
+            ("湖西鄉", "Huxi"),
+            ("白沙鄉", "Baisha"),
+            ("西嶼鄉", "Xiyu"),
+            ("望安鄉", "Wang'an"),
+            ("七美鄉", "Qimei"),
+        ],
+    ),
+    (
+        "金門縣",
+        "Kinmen County",
+        &[
+            ("金城鎮", "Jincheng"),
+            ("金湖鎮", "Jinhu"),
+            ("金沙鎮", "Jinsha"),
+            ("金寧鄉", "Jinning"),
+            ("烈嶼鄉", "Lieyu"),
+            ("烏坵鄉", "Wuqiu"),
+        ],
+    ),
+    (
+        "連江縣",
+        "Lienchiang County",
+        &[
+            ("南竿鄉", "Nangan"),
+            ("北竿鄉", "Beigan"),
+            ("莒光鄉", "Juguang"),
+            ("東引鄉", "Dongyin"),
+        ],
+    ),
+];
+
+/// 鄉鎮市區 通名 的英譯
+pub fn suffix(town: &str) -> &'static str {
+    match town.chars().last() {
+        Some('區') => "District",
+        Some('市') => "City",
+        Some('鎮') | Some('鄉') => "Township",
+        _ => "",
+    }
+}
@@ -0,0 +1,182 @@
+//! 縣市、鄉鎮與預報項目名稱的翻譯
+
+mod county;
+mod weather;
+
+use super::resp::{MoonPhase, RainLevel, UvLevel};
+use county::{suffix, COUNTIES};
+pub use weather::element_name;
+
+/// 回傳文字使用的語系
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Language {
+    /// 正體中文，CWB 原始資料的語系
+    #[default]
+    ZhTw,
+
+    /// 英文
+    En,
+}
+
+/// 不支援的語系標籤
+#[derive(Debug)]
+pub struct ParseLanguageError(String);
+
+impl std::error::Error for ParseLanguageError {}
+
+impl std::fmt::Display for ParseLanguageError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "unsupported language: {}, expect one of zh-TW, en",
+            self.0
+        )
+    }
+}
+
+impl std::str::FromStr for Language {
+    type Err = ParseLanguageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tag = s.trim().to_lowercase();
+        let primary = tag.split('-').next().unwrap_or_default();
+
+        match primary {
+            "zh" => Ok(Language::ZhTw),
+            "en" => Ok(Language::En),
+            _ => Err(ParseLanguageError(s.to_owned())),
+        }
+    }
+}
+
+impl Language {
+    /// 用於 `Content-Language` header 的語系標籤
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Language::ZhTw => "zh-TW",
+            Language::En => "en",
+        }
+    }
+
+    /// 解析 `Accept-Language` header，取支援語系中權重 (q) 最高者
+    pub fn from_accept_language(header: &str) -> Option<Language> {
+        header
+            .split(',')
+            .filter_map(|item| {
+                let mut parts = item.split(';');
+                let language = parts.next()?.parse::<Language>().ok()?;
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+
+                Some((language, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .fold(
+                None,
+                |best: Option<(Language, f32)>, (language, quality)| match best {
+                    Some((_, best_quality)) if best_quality >= quality => best,
+                    _ => Some((language, quality)),
+                },
+            )
+            .map(|(language, _)| language)
+    }
+
+    /// 依語系選擇文字，英譯不存在時使用中文
+    pub fn pick(&self, zh: &str, en: Option<String>) -> String {
+        match (self, en) {
+            (Language::En, Some(en)) => en,
+            _ => zh.to_owned(),
+        }
+    }
+}
+
+/// 資料中 臺、台 混用，統一為 臺 以便查表
+pub fn normalize(name: &str) -> String {
+    name.trim().replace('台', "臺")
+}
+
+/// 縣市名稱
+pub fn city(name: &str, language: Language) -> String {
+    let normalized = normalize(name);
+    let en = COUNTIES
+        .iter()
+        .find(|(zh, _, _)| *zh == normalized)
+        .map(|(_, en, _)| en.to_string());
+
+    language.pick(name, en)
+}
+
+/// 鄉鎮市區名稱，同名鄉鎮 (如 東區) 以所屬縣市區分，縣市不明時取第一個符合者
+pub fn town(city: &str, name: &str, language: Language) -> String {
+    let city = normalize(city);
+    let normalized = normalize(name);
+
+    let en = COUNTIES
+        .iter()
+        .filter(|(zh, _, _)| city.is_empty() || *zh == city)
+        .flat_map(|(_, _, towns)| towns.iter())
+        .find(|(zh, _)| *zh == normalized)
+        .map(|(zh, en)| format!("{} {}", en, suffix(zh)));
+
+    language.pick(name, en)
+}
+
+/// 轄有此鄉鎮市區的縣市，同名鄉鎮 (如 東區) 可能屬於多個縣市
+pub fn cities_of_town(name: &str) -> Vec<&'static str> {
+    let normalized = normalize(name);
+
+    COUNTIES
+        .iter()
+        .filter(|(_, _, towns)| towns.iter().any(|(zh, _)| *zh == normalized))
+        .map(|(zh, _, _)| *zh)
+        .collect()
+}
+
+/// 地點所屬的縣市，縣市名稱即為本身，鄉鎮市區為轄有此鄉鎮的縣市，查無此地點時為空
+pub fn counties_of(name: &str) -> Vec<&'static str> {
+    let normalized = normalize(name);
+
+    match COUNTIES.iter().find(|(zh, _, _)| *zh == normalized) {
+        Some((zh, _, _)) => vec![*zh],
+        None => cities_of_town(name),
+    }
+}
+
+/// 天氣現象 (Wx)
+pub fn phenomenon(text: &str, language: Language) -> String {
+    language.pick(text, weather::phenomenon(text))
+}
+
+/// 特報的天氣現象與種類，例如 大雨、陸上強風、警報
+pub fn hazard(text: &str, language: Language) -> String {
+    language.pick(text, weather::hazard(text))
+}
+
+/// 豪雨分級名稱
+pub fn rain_level(level: RainLevel, language: Language) -> String {
+    let (zh, en) = weather::rain_level(level);
+    language.pick(zh, Some(en.to_owned()))
+}
+
+/// 紫外線曝曬級數名稱
+pub fn uv_level(level: UvLevel, language: Language) -> String {
+    let (zh, en) = weather::uv_level(level);
+    language.pick(zh, Some(en.to_owned()))
+}
+
+/// 月相名稱
+pub fn moon_phase(phase: MoonPhase, language: Language) -> String {
+    let (zh, en) = weather::moon_phase(phase);
+    language.pick(zh, Some(en.to_owned()))
+}
+
+/// 空氣品質狀態與指標污染物，例如 普通、細懸浮微粒
+pub fn air_quality(text: &str, language: Language) -> String {
+    language.pick(text, weather::air_quality(text))
+}
+
+/// 天氣預報綜合描述
+pub fn description(text: &str, language: Language) -> String {
+    language.pick(text, weather::description(text))
+}
@@ -0,0 +1,298 @@
+use super::super::cwb::forecast::WeatherElementName;
+use super::super::resp::{MoonPhase, RainLevel, UvLevel};
+
+/// 預報天氣因子名稱 (中文, 英文)
+pub fn element_name(name: &WeatherElementName) -> (&'static str, &'static str) {
+    match name {
+        WeatherElementName::ProbabilityOfPrecipitationIn12Hours => {
+            ("12小時降雨機率", "12-hour probability of precipitation")
+        }
+        WeatherElementName::WeatherPhenomenon => ("天氣現象", "Weather phenomenon"),
+        WeatherElementName::ApparentTemperature => ("體感溫度", "Apparent temperature"),
+        WeatherElementName::MaxApparentTemperature => {
+            ("最高體感溫度", "Maximum apparent temperature")
+        }
+        WeatherElementName::MinApparentTemperature => {
+            ("最低體感溫度", "Minimum apparent temperature")
+        }
+        WeatherElementName::Temperature => ("溫度", "Temperature"),
+        WeatherElementName::MinTemperature => ("最低溫度", "Minimum temperature"),
+        WeatherElementName::MaxTemperature => ("最高溫度", "Maximum temperature"),
+        WeatherElementName::RelativeHumidity => ("相對濕度", "Relative humidity"),
+        WeatherElementName::ComfortIndex => ("舒適度指數", "Comfort index"),
+        WeatherElementName::MinComfortIndex => ("最小舒適度指數", "Minimum comfort index"),
+        WeatherElementName::MaxComfortIndex => ("最大舒適度指數", "Maximum comfort index"),
+        WeatherElementName::WeatherDescription => ("天氣預報綜合描述", "Weather description"),
+        WeatherElementName::ProbabilityOfPrecipitationIn6Hours => {
+            ("6小時降雨機率", "6-hour probability of precipitation")
+        }
+        WeatherElementName::EstimateWindSpeed => ("風速", "Wind speed"),
+        WeatherElementName::EstimateWindDirection => ("風向", "Wind direction"),
+        WeatherElementName::DewPointTemperature => ("露點溫度", "Dew point temperature"),
+        WeatherElementName::UVI => ("紫外線指數", "UV index"),
+    }
+}
+
+/// 豪雨分級名稱 (中文, 英文)，英譯依 CWB 英文網站用語
+pub fn rain_level(level: RainLevel) -> (&'static str, &'static str) {
+    match level {
+        RainLevel::HeavyRain => ("大雨", "Heavy rain"),
+        RainLevel::ExtremelyHeavyRain => ("豪雨", "Extremely heavy rain"),
+        RainLevel::TorrentialRain => ("大豪雨", "Torrential rain"),
+        RainLevel::ExtremelyTorrentialRain => ("超大豪雨", "Extremely torrential rain"),
+    }
+}
+
+/// 紫外線曝曬級數名稱 (中文, 英文)，英譯依 WHO 用語
+pub fn uv_level(level: UvLevel) -> (&'static str, &'static str) {
+    match level {
+        UvLevel::Low => ("低量級", "Low"),
+        UvLevel::Moderate => ("中量級", "Moderate"),
+        UvLevel::High => ("高量級", "High"),
+        UvLevel::VeryHigh => ("過量級", "Very high"),
+        UvLevel::Extreme => ("危險級", "Extreme"),
+    }
+}
+
+/// 月相名稱 (中文, 英文)
+pub fn moon_phase(phase: MoonPhase) -> (&'static str, &'static str) {
+    match phase {
+        MoonPhase::NewMoon => ("新月", "New moon"),
+        MoonPhase::WaxingCrescent => ("眉月", "Waxing crescent"),
+        MoonPhase::FirstQuarter => ("上弦月", "First quarter"),
+        MoonPhase::WaxingGibbous => ("盈凸月", "Waxing gibbous"),
+        MoonPhase::FullMoon => ("滿月", "Full moon"),
+        MoonPhase::WaningGibbous => ("虧凸月", "Waning gibbous"),
+        MoonPhase::LastQuarter => ("下弦月", "Last quarter"),
+        MoonPhase::WaningCrescent => ("殘月", "Waning crescent"),
+    }
+}
+
+/// 空氣品質狀態與指標污染物，英譯依環境部英文網站用語
+const AIR_QUALITY: &[(&str, &str)] = &[
+    ("良好", "Good"),
+    ("普通", "Moderate"),
+    ("對敏感族群不健康", "Unhealthy for sensitive groups"),
+    ("對所有族群不健康", "Unhealthy"),
+    ("非常不健康", "Very unhealthy"),
+    ("危害", "Hazardous"),
+    ("設備維護", "Under maintenance"),
+    ("細懸浮微粒", "PM2.5"),
+    ("懸浮微粒", "PM10"),
+    ("臭氧八小時", "O3 (8-hour)"),
+    ("臭氧", "O3"),
+    ("二氧化氮", "NO2"),
+    ("一氧化碳", "CO"),
+    ("一氧化碳八小時", "CO (8-hour)"),
+    ("二氧化硫", "SO2"),
+];
+
+/// 天氣特報的現象與種類
+const HAZARDS: &[(&str, &str)] = &[
+    ("超大豪雨", "Extremely torrential rain"),
+    ("大豪雨", "Torrential rain"),
+    ("豪雨", "Extremely heavy rain"),
+    ("大雨", "Heavy rain"),
+    ("陸上強風", "Strong wind on land"),
+    ("海上強風", "Strong wind at sea"),
+    ("濃霧", "Dense fog"),
+    ("低溫", "Low temperature"),
+    ("高溫", "High temperature"),
+    ("颱風", "Typhoon"),
+    ("海上颱風", "Sea typhoon"),
+    ("海上陸上颱風", "Sea and land typhoon"),
+    ("特報", "Advisory"),
+    ("警報", "Warning"),
+];
+
+/// 天空狀態，出現在天氣現象的開頭
+const SKY: &[(&str, &str)] = &[
+    ("晴時多雲", "Mostly sunny"),
+    ("多雲時晴", "Partly cloudy"),
+    ("多雲時陰", "Mostly cloudy"),
+    ("陰時多雲", "Overcast with cloudy intervals"),
+    ("晴天", "Sunny"),
+    ("晴", "Sunny"),
+    ("多雲", "Cloudy"),
+    ("陰天", "Overcast"),
+    ("陰", "Overcast"),
+];
+
+/// 降水及其他天氣現象，依長度由長到短排列以便最長比對
+const PHENOMENA: &[(&str, &str)] = &[
+    ("午後短暫雷陣雨", "brief afternoon thunderstorms"),
+    ("短暫陣雨或雷雨", "brief showers or thunderstorms"),
+    ("午後雷陣雨", "afternoon thunderstorms"),
+    ("陣雨或雷雨", "showers or thunderstorms"),
+    ("短暫雨或雪", "brief rain or snow"),
+    ("短暫陣雨", "brief showers"),
+    ("短暫雷雨", "brief thunderstorms"),
+    ("雷陣雨", "thunderstorms"),
+    ("雨或雪", "rain or snow"),
+    ("短暫雨", "brief rain"),
+    ("陣雨", "showers"),
+    ("雷雨", "thunderstorms"),
+    ("大雨", "heavy rain"),
+    ("豪雨", "torrential rain"),
+    ("有霧", "fog"),
+    ("有靄", "mist"),
+    ("有霾", "haze"),
+    ("降雪", "snow"),
+    ("有雪", "snow"),
+    ("雨", "rain"),
+];
+
+const COMFORT: &[(&str, &str)] = &[
+    ("非常寒冷", "very cold"),
+    ("寒冷", "cold"),
+    ("稍有寒意", "chilly"),
+    ("舒適", "comfortable"),
+    ("悶熱", "muggy"),
+    ("易中暑", "risk of heat stroke"),
+];
+
+const WIND_DIRECTION: &[(&str, &str)] = &[
+    ("風向不定", "Variable wind"),
+    ("偏北風", "Northerly wind"),
+    ("東北風", "Northeasterly wind"),
+    ("偏東風", "Easterly wind"),
+    ("東南風", "Southeasterly wind"),
+    ("偏南風", "Southerly wind"),
+    ("西南風", "Southwesterly wind"),
+    ("偏西風", "Westerly wind"),
+    ("西北風", "Northwesterly wind"),
+    ("北風", "Northerly wind"),
+    ("東風", "Easterly wind"),
+    ("南風", "Southerly wind"),
+    ("西風", "Westerly wind"),
+];
+
+fn lookup(table: &[(&str, &'static str)], text: &str) -> Option<&'static str> {
+    table.iter().find(|(zh, _)| *zh == text).map(|(_, en)| *en)
+}
+
+/// 以最長比對取出開頭符合的詞彙
+fn longest_prefix<'a>(
+    table: &[(&str, &'static str)],
+    text: &'a str,
+) -> Option<(&'static str, &'a str)> {
+    table
+        .iter()
+        .filter_map(|(zh, en)| text.strip_prefix(zh).map(|rest| (*zh, *en, rest)))
+        .max_by_key(|(zh, _, _)| zh.len())
+        .map(|(_, en, rest)| (en, rest))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// 天氣現象 (Wx) 英譯，例如 `多雲時陰短暫陣雨` → `Mostly cloudy with brief showers`
+///
+/// 含有無法翻譯的字詞時回傳 `None`。
+pub fn phenomenon(text: &str) -> Option<String> {
+    let text = text.trim();
+
+    let (sky, mut rest) = match longest_prefix(SKY, text) {
+        Some((sky, rest)) => (Some(sky), rest),
+        None => (None, text),
+    };
+
+    let mut phenomena = Vec::new();
+    while !rest.is_empty() {
+        let (phenomenon, next) = longest_prefix(PHENOMENA, rest)?;
+        phenomena.push(phenomenon);
+        rest = next;
+    }
+
+    match (sky, phenomena.is_empty()) {
+        (Some(sky), true) => Some(sky.to_owned()),
+        (Some(sky), false) => Some(format!("{} with {}", sky, phenomena.join(" and "))),
+        (None, false) => Some(capitalize(&phenomena.join(" and "))),
+        (None, true) => None,
+    }
+}
+
+/// 特報的天氣現象與種類
+pub fn hazard(text: &str) -> Option<String> {
+    lookup(HAZARDS, text.trim()).map(str::to_owned)
+}
+
+/// 空氣品質狀態與指標污染物
+pub fn air_quality(text: &str) -> Option<String> {
+    lookup(AIR_QUALITY, text.trim()).map(str::to_owned)
+}
+
+/// 舒適度，例如 `稍有寒意至舒適` → `Chilly to comfortable`
+fn comfort(text: &str) -> Option<String> {
+    let levels = text
+        .split('至')
+        .map(|level| lookup(COMFORT, level))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(capitalize(&levels.join(" to ")))
+}
+
+/// 風向風速，例如 `偏北風 風速2-3級(每秒2-5公尺)` → `Northerly wind, Beaufort 2-3 (2-5 m/s)`
+fn wind(text: &str) -> Option<String> {
+    let (direction, speed) = text.split_once("風速")?;
+    let direction = lookup(WIND_DIRECTION, direction.trim())?;
+
+    let (scale, speed) = speed.split_once('級')?;
+    let speed = speed.trim().strip_prefix("(每秒")?.strip_suffix("公尺)")?;
+
+    Some(format!(
+        "{}, Beaufort {} ({} m/s)",
+        direction,
+        scale.trim(),
+        speed.trim()
+    ))
+}
+
+fn sentence(text: &str) -> Option<String> {
+    let text = text.trim();
+
+    if let Some(rest) = text.strip_prefix("降雨機率") {
+        return Some(format!("Chance of rain {}", rest.trim()));
+    }
+
+    if let Some(rest) = text.strip_prefix("溫度攝氏") {
+        let range = rest.strip_suffix('度')?.replace('至', " to ");
+        return Some(format!("Temperature {}°C", range.trim()));
+    }
+
+    if let Some(rest) = text.strip_prefix("相對濕度") {
+        return Some(format!(
+            "Relative humidity {}",
+            rest.replace('至', " to ").trim()
+        ));
+    }
+
+    if text.contains("風速") {
+        return wind(text);
+    }
+
+    comfort(text).or_else(|| phenomenon(text))
+}
+
+/// 天氣預報綜合描述 英譯，逐句翻譯，任何一句無法翻譯時回傳 `None`
+pub fn description(text: &str) -> Option<String> {
+    let sentences = text
+        .split('。')
+        .filter(|sentence| !sentence.trim().is_empty())
+        .map(sentence)
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(
+        sentences
+            .into_iter()
+            .map(|sentence| sentence + ".")
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
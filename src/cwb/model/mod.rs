@@ -1,4 +1,10 @@
+//! 資料模型：CWB 與環境部原始資料、服務回傳資料、單位制與語系
+
 pub mod cwb;
+pub mod epa;
+pub mod locale;
 pub mod resp;
+pub mod unit;
 
+/// 本 crate 共用的錯誤型別
 pub type Error = Box<dyn std::error::Error>;
@@ -1,8 +1,16 @@
+//! 服務回傳的資料結構，已由 CWB 原始資料整理過
+
+pub use super::cwb::earthquake::Intensity;
+use super::locale::{self, Language};
+use super::unit::UnitSystem;
+use chrono::{DateTime, FixedOffset, NaiveDate};
 use serde::{Deserialize, Serialize};
 
+/// 溫度，單位依 `UnitSystem` 而定，預設為 攝氏
 pub type Temperature = f32;
 
-#[derive(Serialize, Deserialize, Debug)]
+/// 座標，WGS84
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Position {
     #[serde(rename = "lat")]
     pub latitude: f32,
@@ -11,27 +19,765 @@ pub struct Position {
     pub longitude: f32,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+/// 單一測站的觀測資料，缺值的測站已排除
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Record {
     pub city: String,
     pub town: String,
     pub name: String,
+    /// 測站代碼，例如 `C07014`，來源資料沒有時不回傳
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
 
+    /// 日累積雨量，僅供排序與篩選，不回傳
     #[serde(skip)]
     pub precipitation_per_day: f32,
 
+    /// 高度，僅供分組，不回傳，不隨 `UnitSystem` 轉換，固定為 公尺
     #[serde(skip)]
     pub altitude: f32,
 
     #[serde(rename = "temp")]
     pub temperature: Temperature,
     pub location: Position,
+
+    /// 測站氣壓
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<f32>,
+
+    /// 風速
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_speed: Option<f32>,
+
+    /// 本小時最大陣風風速
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_gust: Option<f32>,
+
+    /// 露點溫度
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dew_point: Option<Temperature>,
+
+    /// 熱指數
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heat_index: Option<Temperature>,
+
+    /// 濕熱指數
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub humidex: Option<Temperature>,
+
+    /// 風寒指數
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wind_chill: Option<Temperature>,
+
+    /// 天氣現象，僅有人站
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weather: Option<String>,
+
+    /// 能見度，單位 公里，例如 `>30`，僅有人站
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+
+    /// 本日日照時數，單位 小時，僅有人站
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunshine_duration: Option<f32>,
+
+    /// 附近監測站的紫外線與空氣品質，僅於 `air=true` 時回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub air: Option<AirReading>,
+}
+
+/// 門檻規則的比較方式，`/weather/ws` 與 webhook 共用
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// 大於，`>`
+    #[serde(rename = ">")]
+    Above,
+    /// 大於等於，`>=`
+    #[serde(rename = ">=")]
+    AtLeast,
+    /// 小於，`<`
+    #[serde(rename = "<")]
+    Below,
+    /// 小於等於，`<=`
+    #[serde(rename = "<=")]
+    AtMost,
+}
+
+impl Op {
+    /// `value` 是否達到門檻
+    pub fn check(self, value: f32, threshold: f32) -> bool {
+        match self {
+            Op::Above => value > threshold,
+            Op::AtLeast => value >= threshold,
+            Op::Below => value < threshold,
+            Op::AtMost => value <= threshold,
+        }
+    }
+}
+
+/// `/weather/stream` 推送的觀測資料變動，測站以 `Record::key` 識別
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RecordDelta {
+    /// 新出現或數值有變動的測站
+    pub updated: Vec<Record>,
+    /// 不再出現的測站代碼，沒有代碼時為測站名稱
+    pub removed: Vec<String>,
+}
+
+/// 豪雨分級，依 24 小時、3 小時、1 小時累積雨量判定，由輕到重排序
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RainLevel {
+    /// 大雨
+    HeavyRain,
+    /// 豪雨
+    ExtremelyHeavyRain,
+    /// 大豪雨
+    TorrentialRain,
+    /// 超大豪雨
+    ExtremelyTorrentialRain,
+}
+
+/// 不支援的豪雨分級名稱
+#[derive(Debug)]
+pub struct ParseRainLevelError(String);
+
+impl std::error::Error for ParseRainLevelError {}
+
+impl std::fmt::Display for ParseRainLevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown rain level: {}, expect one of heavy_rain, extremely_heavy_rain, \
+             torrential_rain, extremely_torrential_rain",
+            self.0
+        )
+    }
+}
+
+impl std::str::FromStr for RainLevel {
+    type Err = ParseRainLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "heavy_rain" => Ok(RainLevel::HeavyRain),
+            "extremely_heavy_rain" => Ok(RainLevel::ExtremelyHeavyRain),
+            "torrential_rain" => Ok(RainLevel::TorrentialRain),
+            "extremely_torrential_rain" => Ok(RainLevel::ExtremelyTorrentialRain),
+            _ => Err(ParseRainLevelError(s.to_owned())),
+        }
+    }
+}
+
+/// 單一雨量站的累積雨量，縣市、鄉鎮缺值的測站已排除
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Rainfall {
+    pub city: String,
+    pub town: String,
+    pub name: String,
+
+    /// 高度，僅供分組，不回傳
+    #[serde(skip)]
+    pub altitude: Option<f32>,
+
+    /// 測站座標
+    pub location: Position,
+
+    /// 10 分鐘累積雨量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_10: Option<f32>,
+
+    /// 1 小時累積雨量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour_1: Option<f32>,
+
+    /// 3 小時累積雨量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour_3: Option<f32>,
+
+    /// 6 小時累積雨量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour_6: Option<f32>,
+
+    /// 12 小時累積雨量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour_12: Option<f32>,
+
+    /// 24 小時累積雨量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hour_24: Option<f32>,
+
+    /// 本日累積雨量
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub today: Option<f32>,
+
+    /// 豪雨分級，未達 大雨 標準時不回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<RainLevel>,
+
+    /// 豪雨分級名稱，依語系翻譯，例如 豪雨
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_name: Option<String>,
+}
+
+/// 單一特報
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Hazard {
+    /// 天氣現象，例如 大雨，依語系翻譯
+    pub phenomenon: String,
+    /// 特報種類，例如 特報、警報，依語系翻譯
+    pub significance: String,
+    /// 生效時間
+    pub start_time: DateTime<FixedOffset>,
+    pub end_time: DateTime<FixedOffset>,
+}
+
+/// 單一縣市發布中的特報
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Warning {
+    pub city: String,
+    /// 特報，依生效時間排序
+    pub hazards: Vec<Hazard>,
+}
+
+/// 颱風在單一時間點的中心位置與強度
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TyphoonFix {
+    /// 時間
+    pub time: DateTime<FixedOffset>,
+    /// 中心位置
+    pub location: Position,
+
+    /// 近中心最大風速
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_wind_speed: Option<f32>,
+
+    /// 近中心最大陣風
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_gust_speed: Option<f32>,
+
+    /// 中心氣壓
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pressure: Option<f32>,
+
+    /// 七級風暴風半徑，單位 公里
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius_15ms: Option<f32>,
+}
+
+/// 發布中的颱風 (熱帶氣旋)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Typhoon {
+    /// 國際命名，熱帶性低氣壓為編號
+    pub name: String,
+
+    /// 中文名稱
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_zh: Option<String>,
+
+    /// 目前位置，最新的分析點
+    pub current: TyphoonFix,
+
+    /// 移動方向，例如 WNW
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moving_direction: Option<String>,
+
+    /// 移動速度，單位 公里/小時
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moving_speed: Option<f32>,
+
+    /// 預報位置，依時間排序
+    pub forecast: Vec<TyphoonFix>,
+}
+
+/// 單一縣市的最大震度
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CountyIntensity {
+    /// 縣市
+    pub city: String,
+    /// 震度
+    pub intensity: Intensity,
+}
+
+/// 單一地震報告
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Earthquake {
+    /// 地震編號，小區域有感地震沒有編號
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<u64>,
+
+    /// 發震時間
+    pub origin_time: DateTime<FixedOffset>,
+
+    /// 芮氏規模
+    pub magnitude: f32,
+
+    /// 震源深度，單位 公里
+    pub depth: f32,
+
+    /// 震央位置描述
+    pub epicenter: String,
+
+    /// 震央座標
+    pub location: Position,
+
+    /// 最大震度，沒有測站測得震度時不回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_intensity: Option<Intensity>,
+
+    /// 各縣市的最大震度，依震度由大到小排序
+    pub intensities: Vec<CountyIntensity>,
+
+    /// 報告內容
+    pub content: String,
+
+    /// 報告網頁
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub web: Option<String>,
 }
 
+/// 紫外線曝曬級數，依紫外線指數判定，由輕到重排序
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum UvLevel {
+    /// 低量級，0 ~ 2
+    Low,
+    /// 中量級，3 ~ 5
+    Moderate,
+    /// 高量級，6 ~ 7
+    High,
+    /// 過量級，8 ~ 10
+    VeryHigh,
+    /// 危險級，11 以上
+    Extreme,
+}
+
+/// 紫外線監測站的觀測
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UvReading {
+    /// 監測站名稱
+    pub site: String,
+    /// 監測站所在縣市
+    pub city: String,
+    /// 監測站座標
+    pub location: Position,
+
+    /// 與氣象測站的距離，單位 公里，未對應測站時不回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f32>,
+
+    /// 紫外線指數
+    pub uvi: f32,
+    /// 曝曬級數
+    pub level: UvLevel,
+    /// 曝曬級數名稱，依語系翻譯，例如 過量級
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level_name: Option<String>,
+    /// 發布時間
+    pub published_at: DateTime<FixedOffset>,
+}
+
+/// 空氣品質監測站的觀測
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AqiReading {
+    /// 監測站名稱
+    pub site: String,
+    /// 監測站所在縣市
+    pub city: String,
+    /// 監測站座標
+    pub location: Position,
+
+    /// 與氣象測站的距離，單位 公里，未對應測站時不回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_km: Option<f32>,
+
+    /// 空氣品質指標
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aqi: Option<u16>,
+
+    /// 狀態，依語系翻譯，例如 普通
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// 指標污染物，依語系翻譯，例如 細懸浮微粒
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pollutant: Option<String>,
+
+    /// 細懸浮微粒 (PM2.5) 濃度，單位 μg/m3
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pm2_5: Option<f32>,
+
+    /// 懸浮微粒 (PM10) 濃度，單位 μg/m3
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pm10: Option<f32>,
+
+    /// 發布時間
+    pub published_at: DateTime<FixedOffset>,
+}
+
+/// 距離氣象測站最近的紫外線與空氣品質觀測，附近沒有監測站時不回傳該項
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AirReading {
+    /// 紫外線指數
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uv: Option<UvReading>,
+
+    /// 空氣品質
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub air_quality: Option<AqiReading>,
+}
+
+/// 單一氣象測站附近的紫外線與空氣品質
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Air {
+    /// 縣市
+    pub city: String,
+    /// 鄉鎮
+    pub town: String,
+    /// 氣象測站名稱
+    pub name: String,
+    /// 氣象測站座標
+    pub location: Position,
+
+    /// 紫外線與空氣品質
+    #[serde(flatten)]
+    pub reading: AirReading,
+}
+
+/// 月相，依朔望月順序
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum MoonPhase {
+    /// 新月 (朔)
+    NewMoon,
+    /// 眉月
+    WaxingCrescent,
+    /// 上弦月
+    FirstQuarter,
+    /// 盈凸月
+    WaxingGibbous,
+    /// 滿月 (望)
+    FullMoon,
+    /// 虧凸月
+    WaningGibbous,
+    /// 下弦月
+    LastQuarter,
+    /// 殘月
+    WaningCrescent,
+}
+
+/// 單一地點單日的天文資料，於本機計算，時間皆為台灣時間
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Astro {
+    /// 日期
+    pub date: NaiveDate,
+    /// 座標
+    pub location: Position,
+
+    /// 民用曙光開始，極晝、極夜時不回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub civil_dawn: Option<DateTime<FixedOffset>>,
+
+    /// 日出
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunrise: Option<DateTime<FixedOffset>>,
+
+    /// 太陽過中天
+    pub solar_noon: DateTime<FixedOffset>,
+
+    /// 日落
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<DateTime<FixedOffset>>,
+
+    /// 民用暮光結束
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub civil_dusk: Option<DateTime<FixedOffset>>,
+
+    /// 日出至日落的時間長度，單位 分鐘
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day_length_minutes: Option<i64>,
+
+    /// 當日正午的月相
+    pub moon_phase: MoonPhase,
+
+    /// 月相名稱，依語系翻譯，例如 上弦月
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moon_phase_name: Option<String>,
+
+    /// 月面被照亮的比例，0 ~ 1
+    pub moon_illumination: f32,
+
+    /// 月齡，單位 日
+    pub moon_age: f32,
+}
+
+/// 預報時段屬於白天或夜間，依當地日出、日落判定
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum DayPeriod {
+    /// 白天
+    Day,
+    /// 夜間
+    Night,
+}
+
+/// 文字型態的預報
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Description {
+    /// 預報項目，依語系翻譯
+    pub element: String,
+    pub start_time: String,
+    pub end_time: String,
+    /// 白天或夜間，鄉鎮座標不明時不回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<DayPeriod>,
+    pub value: String,
+}
+
+/// 單一鄉鎮的一週預報彙整
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Forecast {
+    pub city: String,
     pub name: String,
     pub max_temperature: Temperature,
     pub min_temperature: Temperature,
     pub temperature_difference_per_day: Temperature,
+    pub descriptions: Vec<Description>,
+}
+
+impl Record {
+    /// 將所有數值欄位轉換成指定單位制，高度僅供分組故維持 公尺
+    pub fn with_units(self, units: UnitSystem) -> Self {
+        let temperature = |value: Temperature| units.temperature(value);
+
+        Record {
+            precipitation_per_day: units.precipitation(self.precipitation_per_day),
+            temperature: temperature(self.temperature),
+            pressure: self.pressure.map(|value| units.pressure(value)),
+            wind_speed: self.wind_speed.map(|value| units.wind_speed(value)),
+            wind_gust: self.wind_gust.map(|value| units.wind_speed(value)),
+            dew_point: self.dew_point.map(temperature),
+            heat_index: self.heat_index.map(temperature),
+            humidex: self.humidex.map(temperature),
+            wind_chill: self.wind_chill.map(temperature),
+            ..self
+        }
+    }
+
+    /// 將縣市、鄉鎮名稱轉換成指定語系，測站名稱無英譯故維持原文
+    pub fn with_language(self, language: Language) -> Self {
+        Record {
+            town: locale::town(&self.city, &self.town, language),
+            city: locale::city(&self.city, language),
+            weather: self
+                .weather
+                .map(|weather| locale::phenomenon(&weather, language)),
+            air: self.air.map(|air| air.with_language(language)),
+            ..self
+        }
+    }
+
+    /// 識別測站用的鍵，以測站代碼為準，來源資料沒有代碼時退回測站名稱
+    pub fn key(&self) -> &str {
+        if self.id.is_empty() {
+            &self.name
+        } else {
+            &self.id
+        }
+    }
+}
+
+#[cfg(test)]
+impl Record {
+    /// 測試用的 臺北市 中正區 測站，溫度 30°C，其餘數值缺值，以 `..Record::sample(name)` 補齊欄位
+    pub fn sample(name: &str) -> Self {
+        Record {
+            city: "臺北市".into(),
+            town: "中正區".into(),
+            name: name.into(),
+            id: String::new(),
+            precipitation_per_day: 0.0,
+            altitude: 0.0,
+            temperature: 30.0,
+            location: Position {
+                latitude: 25.0,
+                longitude: 121.5,
+            },
+            pressure: None,
+            wind_speed: None,
+            wind_gust: None,
+            dew_point: None,
+            heat_index: None,
+            humidex: None,
+            wind_chill: None,
+            weather: None,
+            visibility: None,
+            sunshine_duration: None,
+            air: None,
+        }
+    }
+}
+
+impl Rainfall {
+    /// 將所有雨量欄位轉換成指定單位制，分級以毫米判定故不受影響，高度僅供分組故維持 公尺
+    pub fn with_units(self, units: UnitSystem) -> Self {
+        let precipitation = |value: Option<f32>| value.map(|value| units.precipitation(value));
+
+        Rainfall {
+            min_10: precipitation(self.min_10),
+            hour_1: precipitation(self.hour_1),
+            hour_3: precipitation(self.hour_3),
+            hour_6: precipitation(self.hour_6),
+            hour_12: precipitation(self.hour_12),
+            hour_24: precipitation(self.hour_24),
+            today: precipitation(self.today),
+            ..self
+        }
+    }
+
+    /// 將縣市、鄉鎮與分級名稱轉換成指定語系
+    pub fn with_language(self, language: Language) -> Self {
+        Rainfall {
+            town: locale::town(&self.city, &self.town, language),
+            city: locale::city(&self.city, language),
+            level_name: self.level.map(|level| locale::rain_level(level, language)),
+            ..self
+        }
+    }
+}
+
+impl Warning {
+    /// 將縣市、天氣現象與特報種類轉換成指定語系
+    pub fn with_language(self, language: Language) -> Self {
+        Warning {
+            city: locale::city(&self.city, language),
+            hazards: self
+                .hazards
+                .into_iter()
+                .map(|hazard| Hazard {
+                    phenomenon: locale::hazard(&hazard.phenomenon, language),
+                    significance: locale::hazard(&hazard.significance, language),
+                    ..hazard
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TyphoonFix {
+    /// 將風速、氣壓轉換成指定單位制
+    pub fn with_units(self, units: UnitSystem) -> Self {
+        TyphoonFix {
+            max_wind_speed: self.max_wind_speed.map(|value| units.wind_speed(value)),
+            max_gust_speed: self.max_gust_speed.map(|value| units.wind_speed(value)),
+            pressure: self.pressure.map(|value| units.pressure(value)),
+            ..self
+        }
+    }
+}
+
+impl Typhoon {
+    /// 將所有位置的風速、氣壓轉換成指定單位制
+    pub fn with_units(self, units: UnitSystem) -> Self {
+        Typhoon {
+            current: self.current.with_units(units),
+            forecast: self
+                .forecast
+                .into_iter()
+                .map(|fix| fix.with_units(units))
+                .collect(),
+            ..self
+        }
+    }
+}
+
+impl Earthquake {
+    /// 將縣市名稱轉換成指定語系，震央描述無英譯故維持原文
+    pub fn with_language(self, language: Language) -> Self {
+        Earthquake {
+            intensities: self
+                .intensities
+                .into_iter()
+                .map(|item| CountyIntensity {
+                    city: locale::city(&item.city, language),
+                    ..item
+                })
+                .collect(),
+            ..self
+        }
+    }
+}
+
+impl UvReading {
+    /// 將監測站縣市與曝曬級數名稱轉換成指定語系
+    pub fn with_language(self, language: Language) -> Self {
+        UvReading {
+            city: locale::city(&self.city, language),
+            level_name: Some(locale::uv_level(self.level, language)),
+            ..self
+        }
+    }
+}
+
+impl AqiReading {
+    /// 將監測站縣市、狀態與指標污染物轉換成指定語系
+    pub fn with_language(self, language: Language) -> Self {
+        AqiReading {
+            city: locale::city(&self.city, language),
+            status: self
+                .status
+                .map(|status| locale::air_quality(&status, language)),
+            pollutant: self
+                .pollutant
+                .map(|pollutant| locale::air_quality(&pollutant, language)),
+            ..self
+        }
+    }
+}
+
+impl AirReading {
+    /// 將紫外線與空氣品質的文字欄位轉換成指定語系
+    pub fn with_language(self, language: Language) -> Self {
+        AirReading {
+            uv: self.uv.map(|uv| uv.with_language(language)),
+            air_quality: self
+                .air_quality
+                .map(|air_quality| air_quality.with_language(language)),
+        }
+    }
+}
+
+impl Air {
+    /// 將縣市、鄉鎮與觀測的文字欄位轉換成指定語系
+    pub fn with_language(self, language: Language) -> Self {
+        Air {
+            town: locale::town(&self.city, &self.town, language),
+            city: locale::city(&self.city, language),
+            reading: self.reading.with_language(language),
+            ..self
+        }
+    }
+}
+
+impl Astro {
+    /// 將月相名稱轉換成指定語系
+    pub fn with_language(self, language: Language) -> Self {
+        Astro {
+            moon_phase_name: Some(locale::moon_phase(self.moon_phase, language)),
+            ..self
+        }
+    }
+}
+
+impl Forecast {
+    /// 將所有數值欄位轉換成指定單位制
+    pub fn with_units(self, units: UnitSystem) -> Self {
+        Forecast {
+            max_temperature: units.temperature(self.max_temperature),
+            min_temperature: units.temperature(self.min_temperature),
+            temperature_difference_per_day: units
+                .temperature_difference(self.temperature_difference_per_day),
+            ..self
+        }
+    }
 }
@@ -0,0 +1,140 @@
+//! 單位制與單位換算
+
+use serde::Serialize;
+
+/// 回傳數值所使用的單位制
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum UnitSystem {
+    /// 攝氏、百帕、毫米、公尺/秒、公尺 (與 CWB 原始資料相同)
+    #[default]
+    Metric,
+
+    /// 華氏、英寸汞柱、英寸、英里/時、英尺
+    Imperial,
+
+    /// 克氏溫標、帕、公尺、公尺/秒、公尺
+    SI,
+}
+
+/// 各物理量的單位標示
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Units {
+    pub system: &'static str,
+    pub temperature: &'static str,
+    pub pressure: &'static str,
+    pub precipitation: &'static str,
+    pub wind_speed: &'static str,
+    pub elevation: &'static str,
+}
+
+/// 無法辨識的單位制名稱
+#[derive(Debug)]
+pub struct ParseUnitSystemError(String);
+
+impl std::error::Error for ParseUnitSystemError {}
+
+impl std::fmt::Display for ParseUnitSystemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "unknown unit system: {}, expect one of metric, imperial, si",
+            self.0
+        )
+    }
+}
+
+impl std::str::FromStr for UnitSystem {
+    type Err = ParseUnitSystemError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "metric" => Ok(UnitSystem::Metric),
+            "imperial" => Ok(UnitSystem::Imperial),
+            "si" => Ok(UnitSystem::SI),
+            _ => Err(ParseUnitSystemError(s.to_owned())),
+        }
+    }
+}
+
+impl UnitSystem {
+    /// 此單位制下各物理量的單位標示
+    pub fn units(&self) -> Units {
+        match self {
+            UnitSystem::Metric => Units {
+                system: "metric",
+                temperature: "degC",
+                pressure: "hPa",
+                precipitation: "mm",
+                wind_speed: "m/s",
+                elevation: "m",
+            },
+            UnitSystem::Imperial => Units {
+                system: "imperial",
+                temperature: "degF",
+                pressure: "inHg",
+                precipitation: "in",
+                wind_speed: "mph",
+                elevation: "ft",
+            },
+            UnitSystem::SI => Units {
+                system: "si",
+                temperature: "K",
+                pressure: "Pa",
+                precipitation: "m",
+                wind_speed: "m/s",
+                elevation: "m",
+            },
+        }
+    }
+
+    /// 由 攝氏 轉換
+    pub fn temperature(&self, celsius: f32) -> f32 {
+        match self {
+            UnitSystem::Metric => celsius,
+            UnitSystem::Imperial => celsius * 9.0 / 5.0 + 32.0,
+            UnitSystem::SI => celsius + 273.15,
+        }
+    }
+
+    /// 由 攝氏 溫差 轉換，溫差不做零點平移
+    pub fn temperature_difference(&self, celsius: f32) -> f32 {
+        match self {
+            UnitSystem::Imperial => celsius * 9.0 / 5.0,
+            UnitSystem::Metric | UnitSystem::SI => celsius,
+        }
+    }
+
+    /// 由 百帕 轉換
+    pub fn pressure(&self, hpa: f32) -> f32 {
+        match self {
+            UnitSystem::Metric => hpa,
+            UnitSystem::Imperial => hpa * 0.029_53,
+            UnitSystem::SI => hpa * 100.0,
+        }
+    }
+
+    /// 由 毫米 轉換
+    pub fn precipitation(&self, mm: f32) -> f32 {
+        match self {
+            UnitSystem::Metric => mm,
+            UnitSystem::Imperial => mm / 25.4,
+            UnitSystem::SI => mm / 1000.0,
+        }
+    }
+
+    /// 由 公尺/秒 轉換
+    pub fn wind_speed(&self, meter_per_second: f32) -> f32 {
+        match self {
+            UnitSystem::Imperial => meter_per_second * 2.236_936,
+            UnitSystem::Metric | UnitSystem::SI => meter_per_second,
+        }
+    }
+
+    /// 由 公尺 轉換
+    pub fn elevation(&self, meter: f32) -> f32 {
+        match self {
+            UnitSystem::Imperial => meter / 0.3048,
+            UnitSystem::Metric | UnitSystem::SI => meter,
+        }
+    }
+}
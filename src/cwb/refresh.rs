@@ -0,0 +1,178 @@
+use super::cache::Cache;
+use super::feed::Feed;
+use super::logic::{self, Location};
+use super::model::{resp::Record, Error};
+use super::snapshot::Snapshots;
+use super::source::WeatherSource;
+use super::status::Status;
+use super::webhook::Webhooks;
+use crate::{config::Config, shutdown::Shutdown};
+
+use serde::Serialize;
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use tokio::{sync::Notify, task::JoinHandle};
+
+/// `/readyz` 發現資料過舊時請背景更新，探針本身不向上游請求
+#[derive(Default)]
+pub struct Warmup {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+impl Warmup {
+    /// 請背景更新過舊的觀測與預報，更新完成前重複的請求只算一次
+    pub fn request(&self) {
+        if !self.requested.swap(true, Ordering::SeqCst) {
+            self.notify.notify_one();
+        }
+    }
+}
+
+/// 背景更新使用的資料來源、快取與快照，以及更新後通知的對象
+pub struct Refresher {
+    pub config: Config,
+    pub source: Arc<dyn WeatherSource>,
+    pub status: Arc<Status>,
+    pub observations: Arc<Cache<Vec<Record>>>,
+    pub observation_snapshots: Arc<Snapshots<Vec<Record>>>,
+    pub forecasts: Arc<Cache<Vec<Location>>>,
+    pub forecast_snapshots: Arc<Snapshots<Vec<Location>>>,
+    pub feed: Arc<Feed>,
+    pub webhooks: Arc<Webhooks>,
+    pub warmup: Arc<Warmup>,
+}
+
+impl Refresher {
+    /// 向資料來源取得資料，成功時更新快取與快照，並記錄上游狀態
+    async fn load<T, F>(
+        &self,
+        dataset: &str,
+        load: F,
+        cache: &Cache<T>,
+        snapshots: &Snapshots<T>,
+    ) -> Option<Arc<T>>
+    where
+        T: Serialize + Send + Sync + 'static,
+        F: Future<Output = Result<T, Error>>,
+    {
+        let data = self.settle(dataset, load.await)?;
+        let data = cache.insert(dataset, data);
+        snapshots.save(dataset, data.clone());
+        Some(data)
+    }
+
+    /// 記錄上游狀態，失敗時記錄警告
+    fn settle<T>(&self, dataset: &str, result: Result<T, Error>) -> Option<T> {
+        match result {
+            Ok(data) => {
+                self.status.success(dataset);
+                Some(data)
+            }
+            Err(err) => {
+                let message = self.config.redact(&err.to_string());
+                tracing::warn!(dataset, error = %message, "background refresh failed");
+                self.status.failure(dataset, &message);
+                None
+            }
+        }
+    }
+
+    /// 取得預報規則涉及的各縣市鄉鎮預報；任一縣市失敗時不回傳，
+    /// 避免未取得的鄉鎮被當成回到門檻外而重複通知
+    async fn load_counties(&self) -> Option<Vec<Location>> {
+        let mut locations = Vec::new();
+
+        for forecast_type in self.webhooks.forecast_types() {
+            let load = logic::get_city_forecast(self.source.as_ref(), forecast_type).await;
+            locations.extend(self.settle(logic::WEATHER_FORECAST_DATASET, load)?);
+        }
+
+        Some(locations)
+    }
+
+    /// 就緒檢查要求更新，且 dataset 未在 `READY_MAX_AGE` 內成功取得過資料
+    fn needs_warmup(&self, warmup: bool, dataset: &str) -> bool {
+        let max_age =
+            chrono::Duration::from_std(self.config.ready_max_age).unwrap_or(chrono::Duration::MAX);
+
+        warmup && !self.status.get(dataset).is_fresh(max_age)
+    }
+
+    /// 有連線或觀測規則時更新觀測資料並推送變動，有預報規則時更新規則涉及縣市的鄉鎮預報，更新後比對 webhook 規則；
+    /// `warmup` 時另外更新就緒檢查所需的過舊資料
+    async fn refresh(&self, warmup: bool) {
+        if self.feed.subscribers() > 0
+            || self.webhooks.watches_observations()
+            || self.needs_warmup(warmup, &self.config.observation_dataset)
+        {
+            let dataset = self.config.observation_dataset.as_str();
+            let load = logic::get_weather_data(self.source.as_ref());
+
+            if let Some(records) = self
+                .load(
+                    dataset,
+                    load,
+                    &self.observations,
+                    &self.observation_snapshots,
+                )
+                .await
+            {
+                if let Some(change) = self.feed.publish(&records) {
+                    tracing::debug!(
+                        dataset,
+                        id = change.id,
+                        updated = change.updated.len(),
+                        removed = change.removed.len(),
+                        "observations changed"
+                    );
+                }
+
+                self.webhooks.check_observations(&records);
+            }
+        }
+
+        if self.needs_warmup(warmup, logic::WEATHER_FORECAST_DATASET) {
+            let dataset = logic::WEATHER_FORECAST_DATASET;
+            let load = logic::get_weather_forecast(self.source.as_ref());
+
+            self.load(dataset, load, &self.forecasts, &self.forecast_snapshots)
+                .await;
+        }
+
+        if self.webhooks.watches_forecasts() {
+            if let Some(locations) = self.load_counties().await {
+                self.webhooks.check_forecasts(&locations);
+            }
+        }
+    }
+
+    /// 每隔 `REFRESH_INTERVAL` 或收到就緒檢查要求時更新一次，沒有連線、規則與要求時不向上游請求，
+    /// 收到關閉訊號時停止；每次更新在獨立的 task 執行，單次更新失敗不會停止之後的更新
+    pub fn spawn(self, shutdown: Shutdown) -> JoinHandle<()> {
+        let refresher = Arc::new(self);
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(refresher.config.refresh_interval);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = refresher.warmup.notify.notified() => {},
+                    _ = shutdown.wait() => break,
+                }
+
+                let warmup = refresher.warmup.requested.swap(false, Ordering::SeqCst);
+                let cycle = refresher.clone();
+                if let Err(err) = tokio::spawn(async move { cycle.refresh(warmup).await }).await {
+                    tracing::error!(error = %err, "background refresh aborted");
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// 最近一次成功取得的資料
+pub struct Snapshot<T> {
+    pub data: Arc<T>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl<T> Clone for Snapshot<T> {
+    fn clone(&self) -> Self {
+        Snapshot {
+            data: self.data.clone(),
+            fetched_at: self.fetched_at,
+        }
+    }
+}
+
+/// 寫入磁碟的格式
+#[derive(Serialize, Deserialize)]
+struct Stored<T> {
+    fetched_at: DateTime<Utc>,
+    data: T,
+}
+
+/// 各 dataset 的 last-known-good 快照，上游失敗時作為備援
+///
+/// 與 `Cache` 不同，快照不會過期，是否仍可使用由呼叫端依 `MAX_STALENESS` 判斷。
+/// 設定 `SNAPSHOT_DIR` 時會同步寫入磁碟，重新啟動後仍可使用。
+pub struct Snapshots<T> {
+    dir: Option<PathBuf>,
+    entries: Mutex<HashMap<String, Snapshot<T>>>,
+}
+
+impl<T> Snapshots<T> {
+    pub fn get(&self, key: &str) -> Option<Snapshot<T>> {
+        self.entries.lock().unwrap().get(key).cloned()
+    }
+
+    fn path(dir: &Path, key: &str) -> PathBuf {
+        dir.join(format!("{}.json", key))
+    }
+}
+
+impl<T> Snapshots<T>
+where
+    T: Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// 建立快照儲存，並從 `dir` 載入先前保存的快照
+    pub fn new(dir: Option<PathBuf>, keys: &[&str]) -> Self {
+        let mut entries = HashMap::new();
+
+        if let Some(dir) = &dir {
+            if let Err(err) = fs::create_dir_all(dir) {
+                tracing::warn!(dir = %dir.display(), error = %err, "failed to create snapshot dir");
+            }
+
+            for key in keys {
+                let path = Self::path(dir, key);
+                if !path.exists() {
+                    continue;
+                }
+
+                match Self::read(&path) {
+                    Ok(stored) => {
+                        tracing::info!(dataset = key, fetched_at = %stored.fetched_at, "snapshot restored");
+                        entries.insert(
+                            key.to_string(),
+                            Snapshot {
+                                data: Arc::new(stored.data),
+                                fetched_at: stored.fetched_at,
+                            },
+                        );
+                    }
+                    Err(err) => {
+                        tracing::warn!(dataset = key, error = %err, "failed to restore snapshot")
+                    }
+                }
+            }
+        }
+
+        Snapshots {
+            dir,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    fn read(path: &Path) -> Result<Stored<T>, Box<dyn std::error::Error>> {
+        let content = fs::read(path)?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+}
+
+impl<T> Snapshots<T>
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    fn write(path: &Path, stored: &Stored<&T>) -> Result<(), Box<dyn std::error::Error>> {
+        let content = serde_json::to_vec(stored)?;
+
+        // 先寫入暫存檔再改名，避免中途中斷留下不完整的快照
+        let temp = path.with_extension("json.tmp");
+        fs::write(&temp, content)?;
+        fs::rename(temp, path)?;
+
+        Ok(())
+    }
+    /// 保存最新資料，有設定目錄時於背景寫入磁碟
+    pub fn save(&self, key: &str, data: Arc<T>) {
+        let fetched_at = Utc::now();
+
+        self.entries.lock().unwrap().insert(
+            key.to_owned(),
+            Snapshot {
+                data: data.clone(),
+                fetched_at,
+            },
+        );
+
+        if let Some(dir) = &self.dir {
+            let path = Self::path(dir, key);
+            let key = key.to_owned();
+
+            tokio::task::spawn_blocking(move || {
+                if let Err(err) = Self::write(
+                    &path,
+                    &Stored {
+                        fetched_at,
+                        data: data.as_ref(),
+                    },
+                ) {
+                    tracing::warn!(dataset = %key, error = %err, "failed to persist snapshot");
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use super::super::logic::{
+    AIR_QUALITY_DATASET, MANNED_STATIONS_DATASET, RAINFALL_DATASET, TYPHOON_DATASET, UV_DATASET,
+    WARNINGS_DATASET, WEATHER_DATA_DATASET, WEATHER_FORECAST_DATASET, WEATHER_FORECAST_TYPE,
+};
+use super::super::model::{
+    cwb::{earthquake, forecast, rainfall, typhoon, warning, weather_data},
+    epa::{air_quality, uv},
+    Error,
+};
+use super::{AirSource, WeatherSource};
+use crate::shutdown::Shutdown;
+
+use earthquake::ReportKind;
+use forecast::ForecastType;
+use futures::future::{BoxFuture, FutureExt};
+use serde::de::DeserializeOwned;
+use std::{
+    fs,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::task::JoinHandle;
+
+/// 檔名是否為 `<dataset>.json` 或加上時間戳記的 `<dataset>_<數字>.json`
+fn is_data_file(name: &str, dataset: &str) -> bool {
+    match name
+        .strip_prefix(dataset)
+        .and_then(|rest| rest.strip_suffix(".json"))
+    {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('_')
+            .is_some_and(|stamp| !stamp.is_empty() && stamp.bytes().all(|b| b.is_ascii_digit())),
+        None => false,
+    }
+}
+
+/// 由目錄讀取 CWB 開放資料下載的 JSON 檔，同一 dataset 有多個檔案時使用最新修改者
+///
+/// 檔名為 `<dataset>.json` 或 `<dataset>_<時間戳記>.json`，例如 `O-A0001-001.json`、`O-A0001-001_202610191400.json`；
+/// `O-A0001-001.v2.json` 等其他檔名不會讀取。
+/// 觀測資料優先使用 `O-A0001-001`，沒有時使用有人站 `O-A0003-001`；雨量為 `O-A0002-001`。
+/// 特報與颱風路徑分別為 `W-C0033-001`、`W-C0034-005`，地震報告為 `E-A0015-001`、`E-A0016-001`。
+/// 預報只讀取指定縣市的檔案 (例如新北市為 `F-D0047-071`)，不使用其他縣市的預報。
+/// 環境部的紫外線與空氣品質分別為 `uv_s_01`、`aqx_p_432`。
+pub struct FileSource {
+    dir: PathBuf,
+}
+
+impl FileSource {
+    /// 讀取 `dir` 下的檔案
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileSource { dir: dir.into() }
+    }
+
+    /// `dataset` 的檔案中，最新修改者
+    fn newest(&self, dataset: &str) -> Option<(PathBuf, SystemTime)> {
+        fs::read_dir(&self.dir)
+            .ok()?
+            .flatten()
+            .filter(|entry| is_data_file(&entry.file_name().to_string_lossy(), dataset))
+            .filter_map(|entry| {
+                let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+                Some((entry.path(), modified))
+            })
+            .max_by(|(a_path, a_time), (b_path, b_time)| {
+                a_time.cmp(b_time).then_with(|| a_path.cmp(b_path))
+            })
+    }
+
+    async fn read<T>(&self, datasets: &[&str]) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let (path, _) = datasets
+            .iter()
+            .find_map(|dataset| self.newest(dataset))
+            .ok_or_else(|| {
+                format!(
+                    "no {}.json in {}",
+                    datasets.join(".json or "),
+                    self.dir.display()
+                )
+            })?;
+
+        tracing::debug!(path = %path.display(), "reading data file");
+
+        let content = tokio::fs::read(&path).await?;
+        let data = serde_json::from_slice(&content)
+            .map_err(|err| format!("failed to parse {}: {}", path.display(), err))?;
+
+        Ok(data)
+    }
+
+    /// 背景定期檢查目錄，dataset 出現較新的檔案時呼叫 `on_change(dataset)`，收到關閉訊號時停止
+    pub fn spawn_watcher<F>(
+        self: &Arc<Self>,
+        interval: Duration,
+        shutdown: Shutdown,
+        on_change: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn(&str) + Send + 'static,
+    {
+        const SIGNIFICANT: &str = ReportKind::Significant.dataset();
+        const LOCAL: &str = ReportKind::Local.dataset();
+
+        let source = Arc::clone(self);
+        let forecast = WEATHER_FORECAST_TYPE.to_string();
+
+        tokio::spawn(async move {
+            // (快取使用的 dataset, 檔名使用的 dataset)
+            let datasets = [
+                (WEATHER_DATA_DATASET, WEATHER_DATA_DATASET),
+                (WEATHER_DATA_DATASET, MANNED_STATIONS_DATASET),
+                (RAINFALL_DATASET, RAINFALL_DATASET),
+                (WARNINGS_DATASET, WARNINGS_DATASET),
+                (TYPHOON_DATASET, TYPHOON_DATASET),
+                (SIGNIFICANT, SIGNIFICANT),
+                (LOCAL, LOCAL),
+                (WEATHER_FORECAST_DATASET, forecast.as_str()),
+                (UV_DATASET, UV_DATASET),
+                (AIR_QUALITY_DATASET, AIR_QUALITY_DATASET),
+            ];
+
+            let mut latest: Vec<_> = datasets
+                .iter()
+                .map(|(_, name)| source.newest(name))
+                .collect();
+
+            let mut interval = tokio::time::interval(interval);
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {},
+                    _ = shutdown.wait() => break,
+                }
+
+                for ((dataset, name), latest) in datasets.iter().zip(latest.iter_mut()) {
+                    let newest = source.newest(name);
+                    if newest != *latest {
+                        if let Some((path, _)) = &newest {
+                            tracing::info!(dataset, path = %path.display(), "data file changed");
+                        }
+
+                        *latest = newest;
+                        on_change(dataset);
+                    }
+                }
+            }
+        })
+    }
+}
+
+impl WeatherSource for FileSource {
+    fn observations(&self) -> BoxFuture<'_, Result<weather_data::Data, Error>> {
+        async move {
+            self.read(&[WEATHER_DATA_DATASET, MANNED_STATIONS_DATASET])
+                .await
+        }
+        .boxed()
+    }
+
+    fn rainfall(&self) -> BoxFuture<'_, Result<rainfall::Data, Error>> {
+        async move { self.read(&[RAINFALL_DATASET]).await }.boxed()
+    }
+
+    fn warnings(&self) -> BoxFuture<'_, Result<warning::Data, Error>> {
+        async move { self.read(&[WARNINGS_DATASET]).await }.boxed()
+    }
+
+    fn typhoons(&self) -> BoxFuture<'_, Result<typhoon::Data, Error>> {
+        async move { self.read(&[TYPHOON_DATASET]).await }.boxed()
+    }
+
+    fn earthquakes(&self, kind: ReportKind) -> BoxFuture<'_, Result<earthquake::Data, Error>> {
+        async move { self.read(&[kind.dataset()]).await }.boxed()
+    }
+
+    fn forecast(
+        &self,
+        forecast_type: ForecastType,
+    ) -> BoxFuture<'_, Result<forecast::Response, Error>> {
+        async move { self.read(&[&forecast_type.to_string()]).await }.boxed()
+    }
+}
+
+impl AirSource for FileSource {
+    fn uv(&self) -> BoxFuture<'_, Result<uv::Data, Error>> {
+        async move { self.read(&[UV_DATASET]).await }.boxed()
+    }
+
+    fn air_quality(&self) -> BoxFuture<'_, Result<air_quality::Data, Error>> {
+        async move { self.read(&[AIR_QUALITY_DATASET]).await }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_exact_dataset_names() {
+        assert!(is_data_file("O-A0001-001.json", "O-A0001-001"));
+        assert!(is_data_file("O-A0001-001_202610191400.json", "O-A0001-001"));
+
+        for name in [
+            "O-A0001-001.empty.json",
+            "O-A0001-001.v2.json",
+            "O-A0001-001_.json",
+            "O-A0001-001_latest.json",
+            "O-A0001-0010.json",
+            "O-A0001-001.json.bak",
+        ] {
+            assert!(!is_data_file(name, "O-A0001-001"), "{}", name);
+        }
+    }
+}
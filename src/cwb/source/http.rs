@@ -0,0 +1,89 @@
+use super::super::logic::{
+    AIR_QUALITY_DATASET, RAINFALL_DATASET, TYPHOON_DATASET, UV_DATASET, WARNINGS_DATASET,
+    WEATHER_FORECAST_DATASET,
+};
+use super::super::model::{
+    cwb::{earthquake, forecast, rainfall, typhoon, warning, weather_data},
+    epa::{air_quality, uv},
+    Error,
+};
+use super::super::upstream::Upstream;
+use super::{AirSource, WeatherSource};
+
+use earthquake::ReportKind;
+use forecast::ForecastType;
+use futures::future::{BoxFuture, FutureExt};
+
+impl WeatherSource for Upstream {
+    fn observations(&self) -> BoxFuture<'_, Result<weather_data::Data, Error>> {
+        async move {
+            let dataset = &self.config().observation_dataset;
+            let data: weather_data::Data = self.fetch(dataset, &[]).await?;
+
+            // 指定格式時，不接受另一種格式的資料
+            match self.config().schema {
+                Some(schema) if schema != data.records.schema => Err(format!(
+                    "{} uses the {} schema, expected {}",
+                    dataset, data.records.schema, schema
+                )
+                .into()),
+                _ => Ok(data),
+            }
+        }
+        .boxed()
+    }
+
+    fn rainfall(&self) -> BoxFuture<'_, Result<rainfall::Data, Error>> {
+        async move { self.fetch(RAINFALL_DATASET, &[]).await }.boxed()
+    }
+
+    fn warnings(&self) -> BoxFuture<'_, Result<warning::Data, Error>> {
+        async move { self.fetch(WARNINGS_DATASET, &[]).await }.boxed()
+    }
+
+    fn typhoons(&self) -> BoxFuture<'_, Result<typhoon::Data, Error>> {
+        async move { self.fetch(TYPHOON_DATASET, &[]).await }.boxed()
+    }
+
+    fn earthquakes(&self, kind: ReportKind) -> BoxFuture<'_, Result<earthquake::Data, Error>> {
+        async move { self.fetch(kind.dataset(), &[]).await }.boxed()
+    }
+
+    fn forecast(
+        &self,
+        forecast_type: ForecastType,
+    ) -> BoxFuture<'_, Result<forecast::Response, Error>> {
+        async move {
+            let query = [("locationId", forecast_type.to_string())];
+
+            self.fetch(WEATHER_FORECAST_DATASET, &query).await
+        }
+        .boxed()
+    }
+
+    /// 以 `limit=1` 請求，不下載整個縣市
+    fn first_town_forecast(
+        &self,
+        forecast_type: ForecastType,
+    ) -> BoxFuture<'_, Result<forecast::Response, Error>> {
+        async move {
+            let query = [
+                ("locationId", forecast_type.to_string()),
+                ("limit", "1".to_owned()),
+            ];
+
+            self.fetch(WEATHER_FORECAST_DATASET, &query).await
+        }
+        .boxed()
+    }
+}
+
+impl AirSource for Upstream {
+    fn uv(&self) -> BoxFuture<'_, Result<uv::Data, Error>> {
+        async move { self.fetch_air(UV_DATASET).await }.boxed()
+    }
+
+    fn air_quality(&self) -> BoxFuture<'_, Result<air_quality::Data, Error>> {
+        async move { self.fetch_air(AIR_QUALITY_DATASET).await }.boxed()
+    }
+}
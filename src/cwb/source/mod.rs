@@ -0,0 +1,63 @@
+//! 氣象資料來源：CWB 與環境部開放資料 API、本機目錄、錄製的回應
+
+mod file;
+pub use file::*;
+
+mod http;
+
+mod replay;
+pub use replay::*;
+
+use super::model::{
+    cwb::{earthquake, forecast, rainfall, typhoon, warning, weather_data},
+    epa::{air_quality, uv},
+    Error,
+};
+use earthquake::ReportKind;
+use forecast::ForecastType;
+use futures::future::BoxFuture;
+
+/// 天氣資料來源，只負責取得並解析 CWB 格式的原始資料，轉換由 `logic` 處理
+///
+/// 預設為 CWB 開放資料 API (`Upstream`)，離線或測試時可改用 `FileSource`、`ReplaySource`。
+pub trait WeatherSource: Send + Sync {
+    /// 全台測站即時資料 (O-A0001-001)
+    fn observations(&self) -> BoxFuture<'_, Result<weather_data::Data, Error>>;
+
+    /// 雨量站即時資料 (O-A0002-001)
+    fn rainfall(&self) -> BoxFuture<'_, Result<rainfall::Data, Error>>;
+
+    /// 各縣市天氣特報 (W-C0033-001)
+    fn warnings(&self) -> BoxFuture<'_, Result<warning::Data, Error>>;
+
+    /// 颱風路徑 (W-C0034-005)
+    fn typhoons(&self) -> BoxFuture<'_, Result<typhoon::Data, Error>>;
+
+    /// 地震報告，`kind` 指定 顯著有感 (E-A0015-001) 或 小區域有感 (E-A0016-001)
+    fn earthquakes(&self, kind: ReportKind) -> BoxFuture<'_, Result<earthquake::Data, Error>>;
+
+    /// 鄉鎮天氣預報 (F-D0047-093)，`forecast_type` 指定縣市與預報期間
+    fn forecast(
+        &self,
+        forecast_type: ForecastType,
+    ) -> BoxFuture<'_, Result<forecast::Response, Error>>;
+
+    /// 同 `forecast`，但只需要縣市的第一個鄉鎮，預設仍取得整個縣市
+    fn first_town_forecast(
+        &self,
+        forecast_type: ForecastType,
+    ) -> BoxFuture<'_, Result<forecast::Response, Error>> {
+        self.forecast(forecast_type)
+    }
+}
+
+/// 紫外線與空氣品質資料來源，只負責取得並解析環境部格式的原始資料
+///
+/// 與 `WeatherSource` 分開，未設定環境部 API 金鑰時服務仍可提供天氣資料。
+pub trait AirSource: Send + Sync {
+    /// 紫外線即時監測 (uv_s_01)
+    fn uv(&self) -> BoxFuture<'_, Result<uv::Data, Error>>;
+
+    /// 空氣品質指標 (aqx_p_432)
+    fn air_quality(&self) -> BoxFuture<'_, Result<air_quality::Data, Error>>;
+}
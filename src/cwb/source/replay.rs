@@ -0,0 +1,162 @@
+use super::super::logic::{
+    AIR_QUALITY_DATASET, RAINFALL_DATASET, TYPHOON_DATASET, UV_DATASET, WARNINGS_DATASET,
+    WEATHER_DATA_DATASET, WEATHER_FORECAST_DATASET,
+};
+use super::super::model::{
+    cwb::{earthquake, forecast, rainfall, typhoon, warning, weather_data},
+    epa::{air_quality, uv},
+    Error,
+};
+use super::{AirSource, WeatherSource};
+
+use earthquake::ReportKind;
+use forecast::ForecastType;
+use futures::future::{BoxFuture, FutureExt};
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, fs, path::Path, sync::Mutex};
+
+/// 依序重播事先錄製的回應，每次請求取下一筆，到最後一筆後固定回傳最後一筆
+///
+/// 可用於重現上游資料隨時間變化的情境，例如 資料缺漏、格式錯誤 之後恢復正常。
+#[derive(Default)]
+pub struct ReplaySource {
+    /// dataset => (錄製的回應, 下一筆的位置)
+    recordings: Mutex<HashMap<String, (Vec<String>, usize)>>,
+}
+
+impl ReplaySource {
+    /// 沒有任何錄製回應的來源
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 加入一筆 dataset 的回應，依加入順序重播
+    pub fn record(self, dataset: &str, payload: impl Into<String>) -> Self {
+        {
+            let mut recordings = self.recordings.lock().unwrap();
+            let (payloads, _) = recordings.entry(dataset.to_owned()).or_default();
+            payloads.push(payload.into());
+        }
+
+        self
+    }
+
+    /// 由目錄載入錄製的回應，`<dir>/<dataset>/` 下的 JSON 檔依檔名排序重播
+    pub fn from_dir(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        let mut source = Self::new();
+
+        for dataset in [
+            WEATHER_DATA_DATASET,
+            RAINFALL_DATASET,
+            WARNINGS_DATASET,
+            TYPHOON_DATASET,
+            ReportKind::Significant.dataset(),
+            ReportKind::Local.dataset(),
+            WEATHER_FORECAST_DATASET,
+            UV_DATASET,
+            AIR_QUALITY_DATASET,
+        ] {
+            let path = dir.as_ref().join(dataset);
+            if !path.is_dir() {
+                continue;
+            }
+
+            let mut files: Vec<_> = fs::read_dir(&path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<_, _>>()?;
+            files.retain(|file| file.extension().is_some_and(|ext| ext == "json"));
+            files.sort();
+
+            for file in files {
+                source = source.record(dataset, fs::read_to_string(file)?);
+            }
+        }
+
+        Ok(source)
+    }
+
+    fn next<T>(&self, dataset: &str) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let payload = {
+            let mut recordings = self.recordings.lock().unwrap();
+            let (payloads, cursor) = recordings
+                .get_mut(dataset)
+                .filter(|(payloads, _)| !payloads.is_empty())
+                .ok_or_else(|| format!("no recording of {}", dataset))?;
+
+            let payload = payloads[*cursor].clone();
+            *cursor = (*cursor + 1).min(payloads.len() - 1);
+            payload
+        };
+
+        Ok(serde_json::from_str(&payload)?)
+    }
+}
+
+impl WeatherSource for ReplaySource {
+    fn observations(&self) -> BoxFuture<'_, Result<weather_data::Data, Error>> {
+        async move { self.next(WEATHER_DATA_DATASET) }.boxed()
+    }
+
+    fn rainfall(&self) -> BoxFuture<'_, Result<rainfall::Data, Error>> {
+        async move { self.next(RAINFALL_DATASET) }.boxed()
+    }
+
+    fn warnings(&self) -> BoxFuture<'_, Result<warning::Data, Error>> {
+        async move { self.next(WARNINGS_DATASET) }.boxed()
+    }
+
+    fn typhoons(&self) -> BoxFuture<'_, Result<typhoon::Data, Error>> {
+        async move { self.next(TYPHOON_DATASET) }.boxed()
+    }
+
+    fn earthquakes(&self, kind: ReportKind) -> BoxFuture<'_, Result<earthquake::Data, Error>> {
+        async move { self.next(kind.dataset()) }.boxed()
+    }
+
+    fn forecast(&self, _: ForecastType) -> BoxFuture<'_, Result<forecast::Response, Error>> {
+        async move { self.next(WEATHER_FORECAST_DATASET) }.boxed()
+    }
+}
+
+impl AirSource for ReplaySource {
+    fn uv(&self) -> BoxFuture<'_, Result<uv::Data, Error>> {
+        async move { self.next(UV_DATASET) }.boxed()
+    }
+
+    fn air_quality(&self) -> BoxFuture<'_, Result<air_quality::Data, Error>> {
+        async move { self.next(AIR_QUALITY_DATASET) }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMPTY: &str = r#"{"records":{"location":[]}}"#;
+    const BROKEN: &str = r#"{"records":"#;
+
+    #[tokio::test]
+    async fn replays_in_order_and_repeats_the_last() {
+        let source = ReplaySource::new()
+            .record(WEATHER_DATA_DATASET, BROKEN)
+            .record(WEATHER_DATA_DATASET, EMPTY);
+
+        assert!(source.observations().await.is_err());
+        assert!(source.observations().await.is_ok());
+        assert!(source.observations().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn missing_recording_is_an_error() {
+        let source = ReplaySource::new().record(WEATHER_DATA_DATASET, EMPTY);
+
+        let err = source
+            .forecast(ForecastType::TaipeiCityInWeek)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains(WEATHER_FORECAST_DATASET));
+    }
+}
@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::{collections::HashMap, fmt::Display, sync::Mutex};
+
+/// 單一 dataset 最近一次向上游取得資料的結果
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct DatasetStatus {
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+}
+
+impl DatasetStatus {
+    /// 在 `max_age` 內成功取得過資料
+    pub fn is_fresh(&self, max_age: chrono::Duration) -> bool {
+        self.last_success
+            .is_some_and(|time| Utc::now() - time <= max_age)
+    }
+}
+
+/// 各 dataset 的上游狀態，供 readiness 判斷資料是否新鮮
+#[derive(Default)]
+pub struct Status {
+    datasets: Mutex<HashMap<String, DatasetStatus>>,
+}
+
+impl Status {
+    pub fn success(&self, dataset: &str) {
+        let mut datasets = self.datasets.lock().unwrap();
+        datasets.entry(dataset.to_owned()).or_default().last_success = Some(Utc::now());
+    }
+
+    pub fn failure(&self, dataset: &str, error: &dyn Display) {
+        let mut datasets = self.datasets.lock().unwrap();
+        let status = datasets.entry(dataset.to_owned()).or_default();
+
+        status.last_failure = Some(Utc::now());
+        status.last_error = Some(error.to_string());
+    }
+
+    pub fn get(&self, dataset: &str) -> DatasetStatus {
+        let datasets = self.datasets.lock().unwrap();
+        datasets.get(dataset).cloned().unwrap_or_default()
+    }
+}
@@ -0,0 +1,381 @@
+//! 向 CWB 與環境部開放資料 API 請求，含重試、逾時與斷路器
+
+use super::model::Error;
+use crate::{config::Config, metrics};
+
+use reqwest::{Client, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use std::{
+    fmt,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// 向上游請求失敗的原因
+#[derive(Debug)]
+pub enum UpstreamError {
+    /// 連續失敗次數過多，暫停向上游請求
+    CircuitOpen { retry_in: Duration },
+
+    /// 讀取回應時，超過 `READ_TIMEOUT` 未收到任何資料
+    ReadTimeout,
+
+    /// 連線、HTTP 狀態或解析錯誤
+    Request(reqwest::Error),
+}
+
+impl std::error::Error for UpstreamError {}
+
+impl fmt::Display for UpstreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UpstreamError::CircuitOpen { retry_in } => write!(
+                f,
+                "circuit breaker is open, upstream requests are paused for {:?}",
+                retry_in
+            ),
+            UpstreamError::ReadTimeout => write!(f, "timed out reading upstream response"),
+            UpstreamError::Request(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl UpstreamError {
+    /// 作為 metrics `result` label 的錯誤分類
+    pub fn class(&self) -> &'static str {
+        match self {
+            UpstreamError::CircuitOpen { .. } => "circuit_open",
+            UpstreamError::ReadTimeout => "read_timeout",
+            UpstreamError::Request(err) => metrics::error_class(err),
+        }
+    }
+
+    /// 逾時、連線失敗 與 5xx 視為暫時性錯誤，可以重試
+    fn is_retryable(&self) -> bool {
+        match self {
+            UpstreamError::CircuitOpen { .. } => false,
+            UpstreamError::ReadTimeout => true,
+            UpstreamError::Request(err) => {
+                err.is_timeout()
+                    || err.is_connect()
+                    || err.status().is_some_and(|status| {
+                        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+                    })
+            }
+        }
+    }
+}
+
+impl From<reqwest::Error> for UpstreamError {
+    fn from(err: reqwest::Error) -> Self {
+        // 網址中帶有授權碼，不放進錯誤訊息
+        UpstreamError::Request(err.without_url())
+    }
+}
+
+#[derive(Debug)]
+enum State {
+    Closed {
+        failures: u32,
+    },
+    Open {
+        until: Instant,
+    },
+    /// 冷卻結束後只放行一個試探請求，試探請求在 `until` 前沒有結果時再放行一個
+    HalfOpen {
+        until: Instant,
+    },
+}
+
+/// 斷路器，連續失敗達到門檻後在冷卻時間內直接失敗，不再打上游
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+/// 斷路器放行的請求，須以 `success` 或 `failure` 回報結果；
+/// 試探請求未回報就被 drop (例如 client 中斷連線) 時視為失敗，避免斷路器停在 `HalfOpen`
+struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    settled: bool,
+}
+
+impl Permit<'_> {
+    fn success(mut self) {
+        self.settled = true;
+        self.breaker.success();
+    }
+
+    fn failure(mut self) {
+        self.settled = true;
+        self.breaker.failure();
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.probe && !self.settled {
+            self.breaker.failure();
+        }
+    }
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreaker {
+            threshold,
+            cooldown,
+            state: Mutex::new(State::Closed { failures: 0 }),
+        }
+    }
+
+    fn check(&self) -> Result<Permit<'_>, UpstreamError> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+
+        let probe = match *state {
+            State::Closed { .. } => false,
+            State::Open { until } | State::HalfOpen { until } if now >= until => {
+                *state = State::HalfOpen {
+                    until: now + self.cooldown,
+                };
+                true
+            }
+            State::Open { until } | State::HalfOpen { until } => {
+                return Err(UpstreamError::CircuitOpen {
+                    retry_in: until - now,
+                })
+            }
+        };
+
+        Ok(Permit {
+            breaker: self,
+            probe,
+            settled: false,
+        })
+    }
+
+    fn success(&self) {
+        *self.state.lock().unwrap() = State::Closed { failures: 0 };
+    }
+
+    fn failure(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        let failures = match *state {
+            State::Closed { failures } => failures + 1,
+            State::Open { .. } | State::HalfOpen { .. } => self.threshold,
+        };
+
+        *state = if failures >= self.threshold {
+            tracing::warn!(failures, cooldown = ?self.cooldown, "circuit breaker opened");
+            State::Open {
+                until: Instant::now() + self.cooldown,
+            }
+        } else {
+            State::Closed { failures }
+        };
+    }
+}
+
+/// 共用的 CWB 與環境部開放資料 HTTP client，連線池於各請求間重複使用
+///
+/// 兩個 API 各自使用一個斷路器，其中一方中斷時不影響另一方。
+pub struct Upstream {
+    client: Client,
+    config: Config,
+    breaker: CircuitBreaker,
+    air_breaker: CircuitBreaker,
+}
+
+impl Upstream {
+    /// 依設定的逾時建立 client，連線池閒置 90 秒後關閉
+    pub fn new(config: &Config) -> Result<Self, reqwest::Error> {
+        let client = Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.request_timeout)
+            .pool_idle_timeout(Duration::from_secs(90))
+            .tcp_keepalive(Duration::from_secs(60))
+            .build()?;
+
+        Ok(Upstream {
+            client,
+            config: config.clone(),
+            breaker: CircuitBreaker::new(config.breaker_threshold, config.breaker_cooldown),
+            air_breaker: CircuitBreaker::new(config.breaker_threshold, config.breaker_cooldown),
+        })
+    }
+
+    /// 建立時使用的設定
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// 第 n 次重試前的等待時間，指數成長並加上 full jitter
+    fn backoff(&self, attempt: u32) -> Duration {
+        let delay = self
+            .config
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.retry_max_delay);
+
+        let millis = delay.as_millis() as u64;
+        Duration::from_millis(rand::random_range(0..=millis))
+    }
+
+    /// 讀取完整回應，兩次收到資料的間隔超過 `read_timeout` 即失敗
+    async fn request(&self, url: Url) -> Result<Vec<u8>, UpstreamError> {
+        let mut res = self.client.get(url).send().await?.error_for_status()?;
+        let mut body = Vec::new();
+
+        loop {
+            match tokio::time::timeout(self.config.read_timeout, res.chunk()).await {
+                Ok(Ok(Some(chunk))) => body.extend_from_slice(&chunk),
+                Ok(Ok(None)) => return Ok(body),
+                Ok(Err(err)) => return Err(err.into()),
+                Err(_) => return Err(UpstreamError::ReadTimeout),
+            }
+        }
+    }
+
+    /// 向 CWB 取得指定 dataset 並解析，暫時性錯誤依退避策略重試
+    #[tracing::instrument(name = "fetch", skip(self, query), err(Display))]
+    pub async fn fetch<T>(&self, dataset: &str, query: &[(&str, String)]) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let url = Url::parse_with_params(
+            &self.config.dataset_url(dataset),
+            [("Authorization", self.config.token.as_str())]
+                .into_iter()
+                .chain(query.iter().map(|(key, value)| (*key, value.as_str()))),
+        )?;
+
+        self.get(&self.breaker, dataset, url).await
+    }
+
+    /// 向環境部取得指定 dataset 並解析，未設定 `AIR_API_KEY` 時直接失敗
+    #[tracing::instrument(name = "fetch", skip(self), err(Display))]
+    pub async fn fetch_air<T>(&self, dataset: &str) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let api_key = self
+            .config
+            .air_api_key
+            .as_deref()
+            .ok_or("AIR_API_KEY is not set")?;
+
+        let url = Url::parse_with_params(
+            &self.config.air_dataset_url(dataset),
+            [("api_key", api_key), ("format", "json"), ("limit", "1000")],
+        )?;
+
+        self.get(&self.air_breaker, dataset, url).await
+    }
+
+    /// 經由 `breaker` 請求 `url`，暫時性錯誤依退避策略重試
+    async fn get<T>(&self, breaker: &CircuitBreaker, dataset: &str, url: Url) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let permit = match breaker.check() {
+            Ok(permit) => permit,
+            Err(err) => {
+                metrics::observe_upstream(dataset, err.class(), Duration::ZERO);
+                return Err(err.into());
+            }
+        };
+
+        let mut attempt = 0;
+        let (body, started) = loop {
+            let started = Instant::now();
+
+            // 打 API
+            match self.request(url.clone()).await {
+                Ok(body) => {
+                    permit.success();
+                    break (body, started);
+                }
+                Err(err) => {
+                    metrics::observe_upstream(dataset, err.class(), started.elapsed());
+
+                    // 4xx 等錯誤代表上游仍有回應，不算上游中斷
+                    if !err.is_retryable() {
+                        permit.success();
+                        return Err(err.into());
+                    }
+
+                    if attempt >= self.config.retry_max {
+                        permit.failure();
+                        return Err(err.into());
+                    }
+
+                    let delay = self.backoff(attempt);
+                    tracing::warn!(attempt, ?delay, error = %err, "retrying upstream request");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        };
+
+        metrics::UPSTREAM_PAYLOAD_SIZE
+            .with_label_values(&[dataset])
+            .observe(body.len() as f64);
+        tracing::debug!(bytes = body.len(), elapsed = ?started.elapsed(), attempt, "fetched");
+
+        // 解析 API 資料 變成 json
+        let data = tracing::info_span!("parse", bytes = body.len())
+            .in_scope(|| serde_json::from_slice(&body));
+
+        match data {
+            Ok(data) => {
+                metrics::observe_upstream(dataset, "ok", started.elapsed());
+                Ok(data)
+            }
+            Err(err) => {
+                metrics::observe_upstream(dataset, "decode", started.elapsed());
+                Err(err.into())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn abandoned_probe_reopens_the_breaker() {
+        let breaker = CircuitBreaker::new(1, Duration::ZERO);
+        breaker.failure();
+
+        let probe = breaker.check().unwrap();
+        assert!(probe.probe);
+        drop(probe);
+        assert!(matches!(*breaker.state.lock().unwrap(), State::Open { .. }));
+
+        // 冷卻結束後再放行下一個試探請求
+        breaker.check().unwrap().success();
+        assert!(matches!(
+            *breaker.state.lock().unwrap(),
+            State::Closed { failures: 0 }
+        ));
+    }
+
+    #[test]
+    fn unsettled_probe_expires() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.failure();
+        std::thread::sleep(Duration::from_millis(30));
+
+        let probe = breaker.check().unwrap();
+        assert!(breaker.check().is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.check().unwrap().probe);
+        drop(probe);
+    }
+}
@@ -0,0 +1,883 @@
+//! 門檻通知的 webhook：註冊、每次背景更新後比對規則、簽章後傳送並保存傳送紀錄
+//!
+//! 傳送內容為 JSON，附帶以下標頭，接收端以註冊時的 `secret` 驗證：
+//!
+//! - `X-Webhook-Id`：傳送編號，重試時不變，可用於去除重複
+//! - `X-Webhook-Timestamp`：本次嘗試的 Unix 時間 (秒)
+//! - `X-Webhook-Signature`：`sha256=<hex>`，為 `<timestamp>.<body>` 的 HMAC-SHA256
+
+use super::logic::Location;
+use super::model::{
+    cwb::forecast::ForecastType,
+    locale,
+    resp::{Op, Record},
+};
+use crate::{config::Config, metrics, shutdown::Shutdown};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, KeyInit, Mac};
+use reqwest::{header::CONTENT_TYPE, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// 可註冊的 webhook 數上限
+pub const MAX_WEBHOOKS: usize = 64;
+
+/// 每個 webhook 可註冊的規則數上限
+pub const MAX_RULES: usize = 32;
+
+/// 每個 webhook 保存的傳送紀錄筆數，超過時捨棄該 webhook 最舊的紀錄
+pub const DELIVERY_LOG_CAPACITY: usize = 100;
+
+pub const X_WEBHOOK_ID: &str = "x-webhook-id";
+pub const X_WEBHOOK_TIMESTAMP: &str = "x-webhook-timestamp";
+pub const X_WEBHOOK_SIGNATURE: &str = "x-webhook-signature";
+
+/// 規則比對的欄位，數值皆為公制
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    /// 測站溫度 (°C)
+    Temperature,
+    /// 測站日累積雨量 (mm)
+    PrecipitationPerDay,
+    /// 測站本小時最大陣風 (m/s)
+    WindGust,
+    /// 鄉鎮預報期間內最高的降雨機率 (%)
+    Pop,
+}
+
+impl Field {
+    /// 是否以鄉鎮預報比對
+    fn is_forecast(self) -> bool {
+        matches!(self, Field::Pop)
+    }
+}
+
+/// 門檻規則
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    /// 規則編號，同一 webhook 內不可重複
+    pub id: String,
+    pub field: Field,
+    pub op: Op,
+    pub value: f32,
+    /// 只比對這些測站 (代碼或名稱)、鄉鎮或縣市，未指定時比對全部測站；預報規則必須指定鄉鎮或縣市
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub locations: Vec<String>,
+    /// 通知後的冷卻時間 (秒)，註冊時未指定則為 `WEBHOOK_COOLDOWN`
+    #[serde(default)]
+    pub cooldown: Option<u64>,
+}
+
+impl Rule {
+    fn cooldown(&self) -> Duration {
+        Duration::from_secs(self.cooldown.unwrap_or_default())
+    }
+}
+
+/// `POST /admin/webhooks` 的內容
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Registration {
+    /// 接收通知的 http 或 https 網址
+    pub url: String,
+    /// 簽章用的密鑰，未提供時自動產生
+    #[serde(default)]
+    pub secret: Option<String>,
+    pub rules: Vec<Rule>,
+}
+
+/// 已註冊的 webhook，`secret` 只在註冊時回傳一次
+#[derive(Serialize, Debug, Clone)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    #[serde(skip)]
+    secret: String,
+    pub rules: Vec<Rule>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 註冊結果，含有簽章用的密鑰
+#[derive(Serialize, Debug)]
+pub struct Registered {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+/// 由未達門檻變為達到門檻的測站或鄉鎮
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Match {
+    /// 測站代碼，鄉鎮預報或來源資料沒有時不回傳
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub id: String,
+    /// 測站名稱或鄉鎮
+    pub name: String,
+    pub city: String,
+    pub town: String,
+    pub value: f32,
+}
+
+/// 傳送給 webhook 的內容
+#[derive(Serialize, Debug, Clone)]
+pub struct Alert {
+    /// 傳送編號，重試時不變
+    pub id: String,
+    pub webhook: String,
+    pub rule: Rule,
+    pub matches: Vec<Match>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 傳送結果
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// 傳送中或等待重試
+    Pending,
+    /// 接收端回應 2xx
+    Delivered,
+    /// 重試次數用盡、接收端回應不重試的狀態碼或服務關閉
+    Failed,
+}
+
+/// 單次傳送嘗試
+#[derive(Serialize, Debug, Clone)]
+pub struct Attempt {
+    pub at: DateTime<Utc>,
+    /// 接收端的 HTTP 狀態碼，連線失敗或逾時時不回傳
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// 傳送紀錄
+#[derive(Serialize, Debug, Clone)]
+pub struct Delivery {
+    #[serde(flatten)]
+    pub alert: Alert,
+    pub status: DeliveryStatus,
+    pub attempts: Vec<Attempt>,
+}
+
+/// 比對的對象：測站觀測或鄉鎮預報
+enum Target<'a> {
+    Station(&'a Record),
+    Town(&'a Location),
+}
+
+impl Target<'_> {
+    /// 去除重複用的識別，測站以 `Record::key`、鄉鎮以縣市加鄉鎮
+    fn key(&self) -> String {
+        match self {
+            Target::Station(record) => record.key().to_string(),
+            Target::Town(location) => format!("{}/{}", location.city, location.name),
+        }
+    }
+
+    fn value(&self, field: Field) -> Option<f32> {
+        match (self, field) {
+            (Target::Station(record), Field::Temperature) => Some(record.temperature),
+            (Target::Station(record), Field::PrecipitationPerDay) => {
+                Some(record.precipitation_per_day)
+            }
+            (Target::Station(record), Field::WindGust) => record.wind_gust,
+            (Target::Town(location), Field::Pop) => location
+                .precipitation_chances
+                .iter()
+                .map(|(_, chance)| *chance)
+                .reduce(f32::max),
+            _ => None,
+        }
+    }
+
+    /// 是否為 `locations` 指定的地點，名稱比對前先正規化
+    fn within(&self, locations: &[String]) -> bool {
+        if locations.is_empty() {
+            return true;
+        }
+
+        let names = match self {
+            Target::Station(record) => vec![
+                record.id.clone(),
+                locale::normalize(&record.name),
+                locale::normalize(&record.town),
+                locale::normalize(&record.city),
+            ],
+            Target::Town(location) => vec![
+                locale::normalize(&location.name),
+                locale::normalize(&location.city),
+            ],
+        };
+
+        locations
+            .iter()
+            .any(|location| names.contains(&locale::normalize(location)))
+    }
+
+    fn to_match(&self, value: f32) -> Match {
+        match self {
+            Target::Station(record) => Match {
+                id: record.id.clone(),
+                name: record.name.clone(),
+                city: record.city.clone(),
+                town: record.town.clone(),
+                value,
+            },
+            Target::Town(location) => Match {
+                id: String::new(),
+                name: location.name.clone(),
+                city: location.city.clone(),
+                town: location.name.clone(),
+                value,
+            },
+        }
+    }
+}
+
+struct Entry {
+    webhook: Webhook,
+    /// 已通知且仍達門檻的 (規則, 地點)，回到門檻外之前不重複通知
+    triggered: HashSet<(String, String)>,
+    /// 各規則上次通知的時間
+    notified: HashMap<String, Instant>,
+    /// 傳送紀錄，由舊到新，各 webhook 分開保存，不因其他 webhook 頻繁通知而被捨棄
+    deliveries: VecDeque<Delivery>,
+}
+
+#[derive(Default)]
+struct State {
+    /// 依註冊順序
+    entries: Vec<Entry>,
+}
+
+/// 已註冊的 webhook 與傳送紀錄，只保存在記憶體，服務重新啟動後需重新註冊
+pub struct Webhooks {
+    state: Mutex<State>,
+    client: reqwest::Client,
+    config: Config,
+    shutdown: Shutdown,
+}
+
+/// `<timestamp>.<body>` 的 HMAC-SHA256，格式為 `sha256=<hex>`
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+
+    format!("sha256={}", digest)
+}
+
+/// 32 bytes 的隨機密鑰，以 hex 表示
+fn generate_secret() -> String {
+    (0..32)
+        .map(|_| format!("{:02x}", rand::random::<u8>()))
+        .collect()
+}
+
+impl Webhooks {
+    pub fn new(config: &Config, shutdown: Shutdown) -> Result<Self, reqwest::Error> {
+        // 不跟隨重新導向，避免簽章內容被轉送到註冊以外的網址；3xx 視為傳送失敗
+        let client = reqwest::Client::builder()
+            .timeout(config.webhook_timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .build()?;
+
+        Ok(Webhooks {
+            state: Mutex::new(State::default()),
+            client,
+            config: config.clone(),
+            shutdown,
+        })
+    }
+
+    /// 驗證並註冊，未指定冷卻時間的規則套用 `WEBHOOK_COOLDOWN`
+    pub fn register(&self, registration: Registration) -> Result<Registered, String> {
+        let url: Url = registration
+            .url
+            .parse()
+            .map_err(|err| format!("invalid url: {}", err))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err("invalid url: expect an http or https url".into());
+        }
+
+        let secret = match registration.secret {
+            Some(secret) if secret.trim().is_empty() => {
+                return Err("secret must not be empty".into())
+            }
+            Some(secret) => secret,
+            None => generate_secret(),
+        };
+
+        let mut rules = registration.rules;
+        if rules.is_empty() || rules.len() > MAX_RULES {
+            return Err(format!("expect 1 to {} rules", MAX_RULES));
+        }
+
+        let mut ids = HashSet::new();
+        for rule in rules.iter_mut() {
+            if rule.id.trim().is_empty() {
+                return Err("rule id must not be empty".into());
+            }
+            if !ids.insert(rule.id.clone()) {
+                return Err(format!("duplicate rule id: {}", rule.id));
+            }
+            if !rule.value.is_finite() {
+                return Err(format!("invalid value of rule {}", rule.id));
+            }
+            // 預報規則只取得所列縣市的預報，未列出或查無所屬縣市的地點永遠不會通知
+            if rule.field.is_forecast() {
+                if rule.locations.is_empty() {
+                    return Err(format!("rule {} must list counties or towns", rule.id));
+                }
+                if let Some(location) = rule
+                    .locations
+                    .iter()
+                    .find(|location| locale::counties_of(location).is_empty())
+                {
+                    return Err(format!(
+                        "unknown county or town of rule {}: {}",
+                        rule.id, location
+                    ));
+                }
+            }
+
+            rule.cooldown
+                .get_or_insert(self.config.webhook_cooldown.as_secs());
+        }
+
+        let webhook = Webhook {
+            id: uuid::Uuid::new_v4().to_string(),
+            url: url.to_string(),
+            secret: secret.clone(),
+            rules,
+            created_at: Utc::now(),
+        };
+
+        let mut state = self.state.lock().unwrap();
+        if state.entries.len() >= MAX_WEBHOOKS {
+            return Err(format!(
+                "at most {} webhooks can be registered",
+                MAX_WEBHOOKS
+            ));
+        }
+
+        state.entries.push(Entry {
+            webhook: webhook.clone(),
+            triggered: HashSet::new(),
+            notified: HashMap::new(),
+            deliveries: VecDeque::new(),
+        });
+
+        Ok(Registered { webhook, secret })
+    }
+
+    pub fn list(&self) -> Vec<Webhook> {
+        let state = self.state.lock().unwrap();
+        state
+            .entries
+            .iter()
+            .map(|entry| entry.webhook.clone())
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Webhook> {
+        let state = self.state.lock().unwrap();
+        state
+            .entries
+            .iter()
+            .find(|entry| entry.webhook.id == id)
+            .map(|entry| entry.webhook.clone())
+    }
+
+    /// 移除 webhook 與其傳送紀錄，不存在時回傳 `false`
+    pub fn remove(&self, id: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let count = state.entries.len();
+        state.entries.retain(|entry| entry.webhook.id != id);
+
+        state.entries.len() != count
+    }
+
+    /// 指定 webhook 的傳送紀錄，由新到舊，webhook 不存在時為 `None`
+    pub fn deliveries(&self, id: &str) -> Option<Vec<Delivery>> {
+        let state = self.state.lock().unwrap();
+        let entry = state.entries.iter().find(|entry| entry.webhook.id == id)?;
+
+        Some(entry.deliveries.iter().rev().cloned().collect())
+    }
+
+    fn watches(&self, forecast: bool) -> bool {
+        let state = self.state.lock().unwrap();
+        state
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.webhook.rules)
+            .any(|rule| rule.field.is_forecast() == forecast)
+    }
+
+    /// 是否有比對測站觀測的規則
+    pub fn watches_observations(&self) -> bool {
+        self.watches(false)
+    }
+
+    /// 是否有比對鄉鎮預報的規則
+    pub fn watches_forecasts(&self) -> bool {
+        self.watches(true)
+    }
+
+    /// 預報規則所列地點所屬縣市的一週預報，每個縣市只列一次
+    pub fn forecast_types(&self) -> Vec<ForecastType> {
+        let state = self.state.lock().unwrap();
+        let mut forecast_types = Vec::new();
+
+        let counties = state
+            .entries
+            .iter()
+            .flat_map(|entry| &entry.webhook.rules)
+            .filter(|rule| rule.field.is_forecast())
+            .flat_map(|rule| &rule.locations)
+            .flat_map(|location| locale::counties_of(location));
+
+        for forecast_type in counties.filter_map(ForecastType::in_week) {
+            if !forecast_types.contains(&forecast_type) {
+                forecast_types.push(forecast_type);
+            }
+        }
+
+        forecast_types
+    }
+
+    /// 以最新的觀測資料比對規則，並傳送通知
+    pub fn check_observations(self: &Arc<Self>, records: &[Record]) {
+        let targets: Vec<_> = records.iter().map(Target::Station).collect();
+        self.check(false, &targets);
+    }
+
+    /// 以最新的鄉鎮預報比對規則，並傳送通知
+    pub fn check_forecasts(self: &Arc<Self>, locations: &[Location]) {
+        let targets: Vec<_> = locations.iter().map(Target::Town).collect();
+        self.check(true, &targets);
+    }
+
+    /// 比對各規則，地點由未達門檻變為達到門檻時通知；同一規則在冷卻期間不通知，
+    /// 期間達到門檻的地點於冷卻結束後的下一次更新通知
+    fn check(self: &Arc<Self>, forecast: bool, targets: &[Target]) {
+        let now = Instant::now();
+        let mut alerts = Vec::new();
+
+        let mut state = self.state.lock().unwrap();
+        for entry in state.entries.iter_mut() {
+            let Entry {
+                webhook,
+                triggered,
+                notified,
+                deliveries,
+            } = entry;
+
+            for rule in webhook
+                .rules
+                .iter()
+                .filter(|rule| rule.field.is_forecast() == forecast)
+            {
+                let mut matches = Vec::new();
+
+                for target in targets
+                    .iter()
+                    .filter(|target| target.within(&rule.locations))
+                {
+                    let key = (rule.id.clone(), target.key());
+
+                    match target.value(rule.field) {
+                        Some(value) if rule.op.check(value, rule.value) => {
+                            if !triggered.contains(&key) {
+                                matches.push((key, target.to_match(value)));
+                            }
+                        }
+                        _ => {
+                            triggered.remove(&key);
+                        }
+                    }
+                }
+
+                let cooling = notified
+                    .get(&rule.id)
+                    .is_some_and(|at| now.duration_since(*at) < rule.cooldown());
+                if matches.is_empty() || cooling {
+                    continue;
+                }
+
+                notified.insert(rule.id.clone(), now);
+                let matches = matches
+                    .into_iter()
+                    .map(|(key, item)| {
+                        triggered.insert(key);
+                        item
+                    })
+                    .collect();
+
+                let alert = Alert {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    webhook: webhook.id.clone(),
+                    rule: rule.clone(),
+                    matches,
+                    created_at: Utc::now(),
+                };
+
+                if deliveries.len() == DELIVERY_LOG_CAPACITY {
+                    deliveries.pop_front();
+                }
+                deliveries.push_back(Delivery {
+                    alert: alert.clone(),
+                    status: DeliveryStatus::Pending,
+                    attempts: Vec::new(),
+                });
+                alerts.push((webhook.url.clone(), webhook.secret.clone(), alert));
+            }
+        }
+        drop(state);
+
+        for (url, secret, alert) in alerts {
+            tokio::spawn(self.clone().deliver(url, secret, alert));
+        }
+    }
+
+    /// 第 n 次重試前的等待時間，指數成長至 `WEBHOOK_RETRY_MAX_DELAY_MS`
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.config
+            .webhook_retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.webhook_retry_max_delay)
+    }
+
+    /// 更新傳送紀錄，紀錄已被捨棄或 webhook 已移除時略過
+    fn record(&self, alert: &Alert, attempt: Option<Attempt>, status: DeliveryStatus) {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(delivery) = state
+            .entries
+            .iter_mut()
+            .find(|entry| entry.webhook.id == alert.webhook)
+            .and_then(|entry| {
+                entry
+                    .deliveries
+                    .iter_mut()
+                    .find(|delivery| delivery.alert.id == alert.id)
+            })
+        {
+            delivery.attempts.extend(attempt);
+            delivery.status = status;
+        }
+    }
+
+    /// 傳送通知，連線失敗、逾時、429 與 5xx 時重試，其餘非 2xx (含 3xx) 不重試，收到關閉訊號時停止
+    async fn deliver(self: Arc<Self>, url: String, secret: String, alert: Alert) {
+        let body =
+            serde_json::to_vec(&alert).expect("error occurred when serializing webhook payload");
+        let mut attempt = 0;
+
+        let status = loop {
+            let at = Utc::now();
+            let started = Instant::now();
+            let timestamp = at.timestamp();
+
+            let result = self
+                .client
+                .post(&url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(X_WEBHOOK_ID, &alert.id)
+                .header(X_WEBHOOK_TIMESTAMP, timestamp)
+                .header(X_WEBHOOK_SIGNATURE, sign(&secret, timestamp, &body))
+                .body(body.clone())
+                .send()
+                .await;
+
+            let (code, error, retry) = match result {
+                Ok(res) if res.status().is_success() => (Some(res.status()), None, false),
+                Ok(res) => {
+                    let code = res.status();
+                    let retry = code == StatusCode::TOO_MANY_REQUESTS || code.is_server_error();
+                    (
+                        Some(code),
+                        Some(format!("unexpected status {}", code)),
+                        retry,
+                    )
+                }
+                Err(err) => (None, Some(err.to_string()), true),
+            };
+
+            let delivered = error.is_none();
+            let record = Attempt {
+                at,
+                status: code.map(|code| code.as_u16()),
+                error,
+                duration_ms: started.elapsed().as_millis() as u64,
+            };
+
+            if delivered {
+                self.record(&alert, Some(record), DeliveryStatus::Delivered);
+                break DeliveryStatus::Delivered;
+            }
+
+            tracing::warn!(
+                webhook = %alert.webhook,
+                delivery = %alert.id,
+                attempt,
+                error = record.error.as_deref().unwrap_or_default(),
+                "webhook delivery failed"
+            );
+
+            if !retry || attempt >= self.config.webhook_retry_max {
+                self.record(&alert, Some(record), DeliveryStatus::Failed);
+                break DeliveryStatus::Failed;
+            }
+            self.record(&alert, Some(record), DeliveryStatus::Pending);
+
+            tokio::select! {
+                _ = tokio::time::sleep(self.backoff(attempt)) => {},
+                _ = self.shutdown.wait() => {
+                    self.record(&alert, None, DeliveryStatus::Failed);
+                    break DeliveryStatus::Failed;
+                }
+            }
+            attempt += 1;
+        };
+
+        metrics::observe_webhook(status == DeliveryStatus::Delivered);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, temperature: f32, wind_gust: Option<f32>) -> Record {
+        Record {
+            temperature,
+            wind_gust,
+            ..Record::sample(name)
+        }
+    }
+
+    fn rule(field: Field, op: Op, value: f32, cooldown: u64) -> Rule {
+        Rule {
+            id: "rule".into(),
+            field,
+            op,
+            value,
+            locations: Vec::new(),
+            cooldown: Some(cooldown),
+        }
+    }
+
+    /// 指向不存在的位址，傳送一律失敗且不重試
+    fn webhooks(rules: Vec<Rule>) -> Arc<Webhooks> {
+        let config = Config {
+            webhook_retry_max: 0,
+            ..Config::default()
+        };
+        let webhooks = Arc::new(Webhooks::new(&config, Shutdown::new()).unwrap());
+        webhooks
+            .register(Registration {
+                url: "http://127.0.0.1:9/hook".into(),
+                secret: Some("secret".into()),
+                rules,
+            })
+            .unwrap();
+        webhooks
+    }
+
+    /// 第一個 webhook 各次通知的地點名稱
+    fn notified(webhooks: &Webhooks) -> Vec<Vec<String>> {
+        let state = webhooks.state.lock().unwrap();
+        state.entries[0]
+            .deliveries
+            .iter()
+            .map(|delivery| {
+                delivery
+                    .alert
+                    .matches
+                    .iter()
+                    .map(|item| item.name.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        // 以 Python hmac.new(b"secret", b"1700000000.{}", "sha256") 計算
+        assert_eq!(
+            sign("secret", 1_700_000_000, b"{}"),
+            "sha256=b8569b78799ff9e3cbff0fc2d63a33a2b57f3282abd07c37ae5e8e7d79a5f163"
+        );
+    }
+
+    #[tokio::test]
+    async fn notifies_once_until_the_value_leaves_the_threshold() {
+        let webhooks = webhooks(vec![rule(Field::Temperature, Op::Above, 31.0, 0)]);
+
+        webhooks.check_observations(&[record("臺北", 30.5, None), record("板橋", 32.0, None)]);
+        webhooks.check_observations(&[record("臺北", 31.5, None), record("板橋", 32.5, None)]);
+        assert_eq!(notified(&webhooks), [vec!["板橋"], vec!["臺北"]]);
+
+        // 回到門檻外後再次達到門檻時重新通知
+        webhooks.check_observations(&[record("臺北", 30.0, None)]);
+        webhooks.check_observations(&[record("臺北", 32.0, None)]);
+        assert_eq!(notified(&webhooks).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn holds_notifications_during_cooldown() {
+        let webhooks = webhooks(vec![rule(Field::WindGust, Op::AtLeast, 17.2, 3600)]);
+
+        webhooks
+            .check_observations(&[record("臺北", 30.0, Some(18.0)), record("板橋", 30.0, None)]);
+        webhooks.check_observations(&[
+            record("臺北", 30.0, Some(18.0)),
+            record("板橋", 30.0, Some(20.0)),
+        ]);
+        assert_eq!(notified(&webhooks), [vec!["臺北"]]);
+
+        // 冷卻結束後通知期間達到門檻的地點
+        webhooks.state.lock().unwrap().entries[0]
+            .notified
+            .insert("rule".into(), Instant::now() - Duration::from_secs(3600));
+        webhooks.check_observations(&[
+            record("臺北", 30.0, Some(18.0)),
+            record("板橋", 30.0, Some(20.0)),
+        ]);
+        assert_eq!(notified(&webhooks), [vec!["臺北"], vec!["板橋"]]);
+    }
+
+    #[tokio::test]
+    async fn only_checks_the_given_locations() {
+        let mut rule = rule(Field::Temperature, Op::AtMost, 10.0, 0);
+        rule.locations = vec!["台北".into()];
+        let webhooks = webhooks(vec![rule]);
+
+        webhooks.check_observations(&[record("臺北", 5.0, None), record("玉山", 2.0, None)]);
+        assert_eq!(notified(&webhooks), [vec!["臺北"]]);
+
+        // 觀測規則不比對預報
+        assert!(webhooks.watches_observations());
+        assert!(!webhooks.watches_forecasts());
+    }
+
+    #[tokio::test]
+    async fn keeps_a_delivery_log_per_webhook() {
+        let webhooks = webhooks(vec![rule(Field::Temperature, Op::Above, 31.0, 0)]);
+        let quiet = webhooks
+            .register(Registration {
+                url: "http://127.0.0.1:9/hook".into(),
+                secret: None,
+                rules: vec![rule(Field::Temperature, Op::AtMost, 10.0, 0)],
+            })
+            .unwrap();
+
+        webhooks.check_observations(&[record("玉山", 2.0, None)]);
+
+        // 第一個 webhook 的通知超過保存筆數，不影響其他 webhook 的紀錄
+        for _ in 0..=DELIVERY_LOG_CAPACITY {
+            webhooks.check_observations(&[record("玉山", 2.0, None), record("臺北", 32.0, None)]);
+            webhooks.check_observations(&[record("玉山", 2.0, None), record("臺北", 30.0, None)]);
+        }
+
+        assert_eq!(notified(&webhooks).len(), DELIVERY_LOG_CAPACITY);
+        let deliveries = webhooks.deliveries(&quiet.webhook.id).unwrap();
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].alert.matches[0].name, "玉山");
+    }
+
+    #[test]
+    fn rejects_invalid_registrations() {
+        let config = Config::default();
+        let webhooks = Webhooks::new(&config, Shutdown::new()).unwrap();
+        let registration = |url: &str, rules: Vec<Rule>| Registration {
+            url: url.into(),
+            secret: None,
+            rules,
+        };
+        let pop_in = |locations: &[&str]| Rule {
+            locations: locations
+                .iter()
+                .map(|location| location.to_string())
+                .collect(),
+            ..rule(Field::Pop, Op::AtLeast, 70.0, 0)
+        };
+        let pop = || pop_in(&["板橋區"]);
+
+        for (url, rules) in [
+            ("ftp://example.com/hook", vec![pop()]),
+            ("not a url", vec![pop()]),
+            ("http://example.com/hook", vec![]),
+            ("http://example.com/hook", vec![pop(), pop()]),
+            ("http://example.com/hook", vec![pop_in(&[])]),
+            (
+                "http://example.com/hook",
+                vec![pop_in(&["板橋區", "不存在區"])],
+            ),
+        ] {
+            assert!(
+                webhooks.register(registration(url, rules)).is_err(),
+                "{}",
+                url
+            );
+        }
+
+        let registered = webhooks
+            .register(registration("http://example.com/hook", vec![pop()]))
+            .unwrap();
+        assert_eq!(registered.secret.len(), 64);
+        assert_eq!(webhooks.list().len(), 1);
+        assert!(webhooks.remove(&registered.webhook.id));
+        assert!(webhooks.get(&registered.webhook.id).is_none());
+    }
+
+    #[test]
+    fn lists_the_counties_of_forecast_rules() {
+        let webhooks = webhooks(vec![
+            Rule {
+                id: "taipei".into(),
+                locations: vec!["臺北市".into(), "板橋區".into()],
+                ..rule(Field::Pop, Op::AtLeast, 70.0, 0)
+            },
+            Rule {
+                id: "new-taipei".into(),
+                locations: vec!["台北市".into(), "新北市".into()],
+                ..rule(Field::Pop, Op::AtLeast, 70.0, 0)
+            },
+            Rule {
+                locations: vec!["花蓮縣".into()],
+                ..rule(Field::Temperature, Op::AtLeast, 30.0, 0)
+            },
+        ]);
+
+        assert_eq!(
+            webhooks.forecast_types(),
+            [
+                ForecastType::TaipeiCityInWeek,
+                ForecastType::NewTaipeiCityInWeek
+            ]
+        );
+    }
+}
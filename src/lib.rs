@@ -0,0 +1,69 @@
+//! 台灣天氣資料：CWB 開放資料的模型、取得與整理資料的函式，以及 kirby_weather HTTP 服務
+//!
+//! 其他服務可直接依賴本 crate 取得整理過的觀測與預報資料，不需自行定義 CWB 的資料結構。
+//! 資料來源以 [`WeatherSource`] 抽象，可使用 CWB 開放資料 API ([`Upstream`])、
+//! 本機下載的 JSON 檔 ([`FileSource`]) 或錄製的回應 ([`ReplaySource`])。
+//! 環境部的紫外線與空氣品質資料另以 [`AirSource`] 抽象，同樣由上述三者提供。
+//!
+//! ```
+//! use kirby_weather::{cwb::logic, get_weather_data, ReplaySource, UnitSystem};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> Result<(), kirby_weather::Error> {
+//! let source = ReplaySource::new().record(
+//!     logic::WEATHER_DATA_DATASET,
+//!     include_str!("../tests/fixtures/O-A0001-001.json"),
+//! );
+//!
+//! let mut records = get_weather_data(&source).await?;
+//! records.sort_by(logic::sort_by_temp);
+//!
+//! let coldest = records[0].clone().with_units(UnitSystem::Imperial);
+//! assert_eq!(coldest.name, "玉山");
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! 向 CWB 請求時以 [`Config`] 設定位址、授權碼與逾時：
+//!
+//! ```no_run
+//! use kirby_weather::{get_city_forecast, to_forecast, Config, ForecastType, Language, Upstream};
+//!
+//! # async fn example() -> Result<(), kirby_weather::Error> {
+//! let config = Config {
+//!     token: "CWB-XXXXXXXX".into(),
+//!     ..Config::default()
+//! };
+//! let upstream = Upstream::new(&config)?;
+//!
+//! let locations = get_city_forecast(&upstream, ForecastType::TaipeiCityInWeek).await?;
+//! let forecast = locations.iter().find_map(|item| to_forecast(item, Language::En));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod config;
+pub mod cwb;
+pub mod logging;
+mod metrics;
+pub mod shutdown;
+
+pub use config::Config;
+pub use cwb::{
+    logic::{
+        get_air_quality, get_city_forecast, get_earthquakes, get_rainfall, get_typhoons, get_uv,
+        get_warnings, get_weather_data, get_weather_forecast, to_forecast,
+    },
+    model::{
+        cwb::forecast::ForecastType,
+        locale::Language,
+        resp::{
+            Air, AirReading, AqiReading, Astro, DayPeriod, Earthquake, Forecast, MoonPhase, Op,
+            RainLevel, Rainfall, Record, RecordDelta, Typhoon, UvReading, Warning,
+        },
+        unit::UnitSystem,
+        Error,
+    },
+    source::{AirSource, FileSource, ReplaySource, WeatherSource},
+    upstream::Upstream,
+};
@@ -0,0 +1,112 @@
+//! 結構化日誌與請求追蹤
+
+use crate::{config::LogLevel, metrics};
+
+use hyper::{header::HeaderValue, Body, Request, Response};
+use routerify::{ext::RequestExt, RequestInfo};
+use std::{net::IpAddr, time::Instant};
+use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
+
+/// 請求編號的 header，未提供時由服務產生並於回應帶回
+pub const X_REQUEST_ID: &str = "x-request-id";
+
+/// 初始化 JSON 格式日誌，`RUST_LOG` 優先，未設定時使用 `LOG_LEVEL`
+pub fn init(level: LogLevel) {
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(format!("{}={}", env!("CARGO_CRATE_NAME"), level)));
+
+    tracing_subscriber::fmt()
+        .json()
+        .with_env_filter(filter)
+        .with_current_span(true)
+        .with_span_list(false)
+        .init();
+}
+
+/// 存取日誌所需的請求資訊，由 `start` 寫入 request context
+#[derive(Clone)]
+struct Access {
+    id: String,
+    client_ip: IpAddr,
+    started: Instant,
+}
+
+/// `X-Forwarded-For` 的最後一個位址，由最近的反向代理 (如 Heroku router) 附加，用戶端無法偽造
+fn last_forwarded(value: &str) -> Option<IpAddr> {
+    value.rsplit(',').next()?.trim().parse().ok()
+}
+
+/// 取得用戶端 IP，經由反向代理時以 `X-Forwarded-For` 的最後一個位址為準
+fn client_ip(req: &Request<Body>) -> IpAddr {
+    req.headers()
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok())
+        .and_then(last_forwarded)
+        .unwrap_or_else(|| req.remote_addr().ip())
+}
+
+/// pre middleware，沿用呼叫端帶入的 `X-Request-Id`，否則產生新的 id
+pub async fn start(req: Request<Body>) -> Result<Request<Body>, hyper::http::Error> {
+    let id = req
+        .headers()
+        .get(X_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.is_empty() && value.len() <= 128)
+        .map(str::to_owned)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    req.set_context(Access {
+        id,
+        client_ip: client_ip(&req),
+        started: Instant::now(),
+    });
+
+    Ok(req)
+}
+
+/// post middleware，輸出存取日誌並於回應附上 `X-Request-Id`
+pub async fn finish(
+    mut res: Response<Body>,
+    info: RequestInfo,
+) -> Result<Response<Body>, hyper::http::Error> {
+    let access = match info.context::<Access>() {
+        Some(access) => access,
+        None => return Ok(res),
+    };
+
+    tracing::info!(
+        request_id = %access.id,
+        method = %info.method(),
+        route = metrics::route(&info),
+        query = info.uri().query().unwrap_or_default(),
+        status = res.status().as_u16(),
+        latency_ms = access.started.elapsed().as_secs_f64() * 1000.0,
+        client_ip = %access.client_ip,
+        "request completed"
+    );
+
+    if let Ok(id) = HeaderValue::from_str(&access.id) {
+        res.headers_mut().insert(X_REQUEST_ID, id);
+    }
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trusts_only_the_last_forwarded_address() {
+        assert_eq!(
+            last_forwarded("1.2.3.4, 10.0.0.1"),
+            Some("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(
+            last_forwarded("10.0.0.1"),
+            Some("10.0.0.1".parse().unwrap())
+        );
+        assert_eq!(last_forwarded("10.0.0.1, spoofed"), None);
+    }
+}
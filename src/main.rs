@@ -1,21 +1,50 @@
-mod cwb;
-mod env;
-
-use hyper::Server;
-use routerify::RouterService;
-use std::net::SocketAddr;
+use kirby_weather::{
+    config::Config,
+    cwb, logging,
+    shutdown::{self, Shutdown},
+};
+use std::net::TcpListener;
 
 #[tokio::main]
 async fn main() {
-    env::init();
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("config error: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    logging::init(config.log_level);
+
+    let addr = config.addr;
+    let drain_timeout = config.drain_timeout;
+
+    let listener = match TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(err) => {
+            tracing::error!(%addr, error = %err, "failed to bind");
+            std::process::exit(1);
+        }
+    };
+
+    let shutdown = Shutdown::new();
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown::signal().await;
+            tracing::info!("shutting down, draining in-flight requests");
+            shutdown.trigger();
+        }
+    });
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], env::get_port()));
+    let router = cwb::service(config, shutdown.clone());
 
-    let service = RouterService::new(cwb::service()).unwrap();
-    let server = Server::bind(&addr).serve(service);
+    // `PORT=0` 時由系統指定，記錄實際綁定的 port
+    let addr = listener.local_addr().unwrap_or(addr);
+    tracing::info!(%addr, "service runs on {}", addr);
 
-    println!("Service runs on: {:?}", addr);
-    if let Err(e) = server.await {
-        eprint!("server error: {}", e);
+    if let Err(e) = shutdown::serve(listener, router, shutdown, drain_timeout).await {
+        tracing::error!(error = %e, "server error");
     }
 }
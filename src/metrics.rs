@@ -0,0 +1,235 @@
+//! Prometheus 指標
+
+use hyper::{body::HttpBody, Body, Request, Response};
+use prometheus::{
+    exponential_buckets, Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, Opts,
+    Registry, TextEncoder,
+};
+use routerify::{ext::RequestExt, RequestInfo};
+use std::{
+    sync::LazyLock,
+    time::{Duration, Instant},
+};
+
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+fn counter(name: &str, help: &str, labels: &[&str]) -> IntCounterVec {
+    let counter = IntCounterVec::new(Opts::new(name, help), labels).unwrap();
+    REGISTRY.register(Box::new(counter.clone())).unwrap();
+    counter
+}
+
+fn histogram(name: &str, help: &str, labels: &[&str], buckets: Vec<f64>) -> HistogramVec {
+    let histogram =
+        HistogramVec::new(HistogramOpts::new(name, help).buckets(buckets), labels).unwrap();
+    REGISTRY.register(Box::new(histogram.clone())).unwrap();
+    histogram
+}
+
+/// 1KB ~ 16MB
+fn size_buckets() -> Vec<f64> {
+    exponential_buckets(1024.0, 4.0, 8).unwrap()
+}
+
+pub static HTTP_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter(
+        "http_requests_total",
+        "HTTP requests handled, by route, method and status",
+        &["route", "method", "status"],
+    )
+});
+
+pub static HTTP_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    histogram(
+        "http_request_duration_seconds",
+        "HTTP request latency, by route",
+        &["route"],
+        prometheus::DEFAULT_BUCKETS.to_vec(),
+    )
+});
+
+pub static HTTP_RESPONSE_SIZE: LazyLock<HistogramVec> = LazyLock::new(|| {
+    histogram(
+        "http_response_size_bytes",
+        "HTTP response payload size, by route",
+        &["route"],
+        size_buckets(),
+    )
+});
+
+pub static UPSTREAM_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter(
+        "cwb_upstream_requests_total",
+        "CWB open data requests, by dataset and result (ok or error class)",
+        &["dataset", "result"],
+    )
+});
+
+pub static UPSTREAM_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    histogram(
+        "cwb_upstream_request_duration_seconds",
+        "CWB open data request latency, by dataset",
+        &["dataset"],
+        prometheus::DEFAULT_BUCKETS.to_vec(),
+    )
+});
+
+pub static UPSTREAM_PAYLOAD_SIZE: LazyLock<HistogramVec> = LazyLock::new(|| {
+    histogram(
+        "cwb_upstream_payload_size_bytes",
+        "CWB open data payload size, by dataset",
+        &["dataset"],
+        size_buckets(),
+    )
+});
+
+pub static CACHE_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter(
+        "cache_requests_total",
+        "Upstream cache lookups, by dataset and result (hit or miss)",
+        &["dataset", "result"],
+    )
+});
+
+pub static STALE_RESPONSES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter(
+        "stale_responses_total",
+        "Responses served from the last-known-good snapshot because the upstream failed, by dataset",
+        &["dataset"],
+    )
+});
+
+pub static STATIONS_DROPPED: LazyLock<IntCounter> = LazyLock::new(|| {
+    let counter = IntCounter::new(
+        "cwb_stations_dropped_total",
+        "Stations and forecast slots dropped because of missing (-99) or unparsable values",
+    )
+    .unwrap();
+    REGISTRY.register(Box::new(counter.clone())).unwrap();
+    counter
+});
+
+pub static WEBHOOK_DELIVERIES: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    counter(
+        "webhook_deliveries_total",
+        "Webhook deliveries after all retries, by result (delivered or failed)",
+        &["result"],
+    )
+});
+
+/// 上游請求結果分類，作為 `result` label
+pub fn error_class(err: &reqwest::Error) -> &'static str {
+    if err.is_timeout() {
+        "timeout"
+    } else if err.is_connect() {
+        "connect"
+    } else if err.is_status() {
+        match err.status() {
+            Some(status) if status.is_server_error() => "status_5xx",
+            _ => "status_4xx",
+        }
+    } else if err.is_decode() || err.is_body() {
+        "body"
+    } else {
+        "other"
+    }
+}
+
+pub fn observe_upstream(dataset: &str, result: &str, elapsed: Duration) {
+    UPSTREAM_REQUESTS
+        .with_label_values(&[dataset, result])
+        .inc();
+    UPSTREAM_DURATION
+        .with_label_values(&[dataset])
+        .observe(elapsed.as_secs_f64());
+}
+
+pub fn observe_webhook(delivered: bool) {
+    let result = if delivered { "delivered" } else { "failed" };
+    WEBHOOK_DELIVERIES.with_label_values(&[result]).inc();
+}
+
+pub fn observe_cache(dataset: &str, hit: bool) {
+    let result = if hit { "hit" } else { "miss" };
+    CACHE_REQUESTS.with_label_values(&[dataset, result]).inc();
+}
+
+/// 請求開始時間，由 `start` 寫入 request context
+#[derive(Clone)]
+struct Started(Instant);
+
+/// pre middleware，記錄請求開始時間
+pub async fn start(req: Request<Body>) -> Result<Request<Body>, hyper::http::Error> {
+    req.set_context(Started(Instant::now()));
+    Ok(req)
+}
+
+/// 請求對應的路由樣式，例如 `/admin/webhooks/:id`，由 `matched` 寫入 request context
+#[derive(Clone)]
+struct Route(&'static str);
+
+/// 包裝路由的 handler，處理前記錄對應的路由樣式作為指標與存取日誌的 `route`
+pub fn matched<H, R>(pattern: &'static str, handler: H) -> impl Fn(Request<Body>) -> R
+where
+    H: Fn(Request<Body>) -> R,
+{
+    move |req| {
+        req.set_context(Route(pattern));
+        handler(req)
+    }
+}
+
+/// 請求對應的路由樣式，未對應到路由的請求統一為 `unmatched`；
+/// 以路由樣式而非實際路徑作為 label，避免 label 數量無限制成長
+pub fn route(info: &RequestInfo) -> &'static str {
+    info.context::<Route>()
+        .map_or("unmatched", |Route(pattern)| pattern)
+}
+
+/// post middleware，記錄請求次數、延遲與回傳大小
+pub async fn finish(
+    res: Response<Body>,
+    info: RequestInfo,
+) -> Result<Response<Body>, hyper::http::Error> {
+    let route = route(&info);
+
+    HTTP_REQUESTS
+        .with_label_values(&[route, info.method().as_str(), res.status().as_str()])
+        .inc();
+
+    if let Some(Started(started)) = info.context::<Started>() {
+        HTTP_DURATION
+            .with_label_values(&[route])
+            .observe(started.elapsed().as_secs_f64());
+    }
+
+    if let Some(size) = res.body().size_hint().exact() {
+        HTTP_RESPONSE_SIZE
+            .with_label_values(&[route])
+            .observe(size as f64);
+    }
+
+    Ok(res)
+}
+
+/// Prometheus 文字格式
+pub fn encode() -> String {
+    // 確保尚未有資料的指標也會輸出
+    LazyLock::force(&HTTP_REQUESTS);
+    LazyLock::force(&HTTP_DURATION);
+    LazyLock::force(&HTTP_RESPONSE_SIZE);
+    LazyLock::force(&UPSTREAM_REQUESTS);
+    LazyLock::force(&UPSTREAM_DURATION);
+    LazyLock::force(&UPSTREAM_PAYLOAD_SIZE);
+    LazyLock::force(&CACHE_REQUESTS);
+    LazyLock::force(&STALE_RESPONSES);
+    LazyLock::force(&STATIONS_DROPPED);
+    LazyLock::force(&WEBHOOK_DELIVERIES);
+
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buffer)
+        .expect("error occurred when encoding metrics");
+
+    String::from_utf8(buffer).expect("metrics are not valid utf-8")
+}
@@ -0,0 +1,180 @@
+//! 優雅關閉：通知背景工作並等待處理中的請求完成
+
+use hyper::{http::Error, Body, Server};
+use routerify::{Router, RouterService};
+use std::{net::TcpListener, sync::Arc, time::Duration};
+use tokio::sync::watch;
+
+/// 關閉訊號，背景工作 (快取清理、輪詢等) 透過 `wait` 得知服務即將關閉
+#[derive(Clone)]
+pub struct Shutdown {
+    sender: Arc<watch::Sender<bool>>,
+    receiver: watch::Receiver<bool>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    /// 尚未觸發的關閉訊號
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(false);
+
+        Shutdown {
+            sender: Arc::new(sender),
+            receiver,
+        }
+    }
+
+    /// 觸發關閉，所有 `wait` 皆會返回
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// 等待關閉訊號
+    pub async fn wait(&self) {
+        let mut receiver = self.receiver.clone();
+
+        // sender 與 Shutdown 同生命週期，不會提早被 drop
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+}
+
+/// 等待 SIGINT (Ctrl-C) 或 SIGTERM (Heroku 等平台重啟時送出)
+pub async fn signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("error occurred when installing SIGINT handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("error occurred when installing SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// 啟動服務直到收到關閉訊號，
+/// 之後不再接受新連線，並在 `drain_timeout` 內等待處理中的請求完成
+pub async fn serve(
+    listener: TcpListener,
+    router: Router<Body, Error>,
+    shutdown: Shutdown,
+    drain_timeout: Duration,
+) -> Result<(), hyper::Error> {
+    let service = RouterService::new(router).unwrap();
+    let server = Server::from_tcp(listener)?
+        .serve(service)
+        .with_graceful_shutdown({
+            let shutdown = shutdown.clone();
+            async move { shutdown.wait().await }
+        });
+
+    let drain = async {
+        shutdown.wait().await;
+        tokio::time::sleep(drain_timeout).await;
+    };
+
+    tokio::select! {
+        result = server => result,
+        _ = drain => {
+            tracing::warn!(
+                ?drain_timeout,
+                "drain timeout exceeded, dropping remaining connections"
+            );
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::{Request, Response};
+    use routerify::ext::RequestExt;
+    use tokio::sync::Notify;
+
+    /// 處理中的慢速請求，開始處理時通知測試
+    async fn slow(req: Request<Body>) -> Result<Response<Body>, Error> {
+        req.data::<Arc<Notify>>().unwrap().notify_one();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        Response::builder().body(Body::from("done"))
+    }
+
+    fn router(started: Arc<Notify>) -> Router<Body, Error> {
+        Router::builder()
+            .data(started)
+            .get("/slow", slow)
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn in_flight_request_completes_during_shutdown() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/slow", listener.local_addr().unwrap());
+        let started = Arc::new(Notify::new());
+        let shutdown = Shutdown::new();
+
+        let server = tokio::spawn(serve(
+            listener,
+            router(started.clone()),
+            shutdown.clone(),
+            Duration::from_secs(5),
+        ));
+
+        let client = reqwest::Client::new();
+        let request = tokio::spawn(client.get(&url).send());
+
+        // 請求進行中時觸發關閉
+        started.notified().await;
+        shutdown.trigger();
+
+        let res = request.await.unwrap().unwrap();
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.text().await.unwrap(), "done");
+
+        server.await.unwrap().unwrap();
+
+        // 關閉後不再接受新連線
+        assert!(client.get(&url).send().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn drain_timeout_stops_waiting_for_slow_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/slow", listener.local_addr().unwrap());
+        let started = Arc::new(Notify::new());
+        let shutdown = Shutdown::new();
+
+        let server = tokio::spawn(serve(
+            listener,
+            router(started.clone()),
+            shutdown.clone(),
+            Duration::from_millis(50),
+        ));
+
+        let request = tokio::spawn(reqwest::Client::new().get(&url).send());
+
+        started.notified().await;
+        shutdown.trigger();
+
+        // 不等待仍在處理中的請求，於 drain timeout 後即返回
+        server.await.unwrap().unwrap();
+        assert!(!request.is_finished());
+    }
+}
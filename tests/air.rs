@@ -0,0 +1,114 @@
+mod common;
+
+use common::{names, MockCwb, Reply, Service, AIR_QUALITY, TOKEN, UV, WEATHER_DATA};
+use reqwest::StatusCode;
+
+async fn start() -> (MockCwb, Service) {
+    let mock = MockCwb::start().await;
+    let air_api = format!("{}/api/v2", mock.url());
+    let service = Service::start(
+        &mock.url(),
+        &[("AIR_API", &air_api), ("AIR_API_KEY", TOKEN)],
+    )
+    .await;
+    (mock, service)
+}
+
+#[tokio::test]
+async fn pairs_each_station_with_the_nearest_sites() {
+    let (mock, service) = start().await;
+
+    // 玉山、阿里山 25 公里內沒有監測站
+    let data = service.json("/air").await;
+    assert_eq!(names(&data), ["臺北", "板橋"]);
+
+    let taipei = &data[0];
+    assert_eq!(taipei["uv"]["site"], "臺北");
+    assert_eq!(taipei["uv"]["uvi"], 7.4);
+    assert_eq!(taipei["uv"]["level"], "high");
+    assert_eq!(taipei["uv"]["level_name"], "高量級");
+    assert_eq!(taipei["air_quality"]["site"], "中山");
+    assert_eq!(taipei["air_quality"]["aqi"], 62);
+    assert_eq!(taipei["air_quality"]["pm2_5"], 21.0);
+    assert_eq!(taipei["air_quality"]["status"], "普通");
+    assert!(taipei["air_quality"]["distance_km"].as_f64().unwrap() < 5.0);
+
+    assert_eq!(data[1]["uv"]["site"], "板橋");
+    assert_eq!(data[1]["air_quality"]["site"], "板橋");
+
+    assert_eq!(mock.hits(UV), 1);
+    assert_eq!(mock.hits(AIR_QUALITY), 1);
+}
+
+#[tokio::test]
+async fn filters_orders_and_translates() {
+    let (_mock, service) = start().await;
+
+    let data = service.json("/air?max_AQI=50").await;
+    assert_eq!(names(&data), ["板橋"]);
+
+    let data = service.json("/air?order_by=PM2_5&limit=1&lang=en").await;
+    assert_eq!(names(&data), ["臺北"]);
+    assert_eq!(data[0]["city"], "Taipei City");
+    assert_eq!(data[0]["air_quality"]["status"], "Moderate");
+    assert_eq!(data[0]["air_quality"]["pollutant"], "PM2.5");
+    assert_eq!(data[0]["uv"]["level_name"], "High");
+
+    let res = service.get("/air?min_UVI=abc").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn missing_dataset_serves_the_other_as_stale() {
+    let (mock, service) = start().await;
+    mock.reply(UV, Reply::status(StatusCode::BAD_GATEWAY));
+
+    let res = service.get("/air").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-data-stale"], "true");
+
+    let data: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(names(&data), ["臺北", "板橋"]);
+    assert!(data[0]["uv"].is_null());
+    assert_eq!(data[0]["air_quality"]["site"], "中山");
+
+    // 觀測資料仍是必要的
+    let (mock, service) = start().await;
+    mock.reply(WEATHER_DATA, Reply::status(StatusCode::BAD_GATEWAY));
+    let res = service.get("/air").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn weather_records_embed_readings_on_request() {
+    let (_mock, service) = start().await;
+
+    let data = service.json("/weather").await;
+    assert!(data[0].get("air").is_none());
+
+    let data = service.json("/weather?air=true").await;
+    assert_eq!(data[0]["name"], "臺北");
+    assert_eq!(data[0]["air"]["uv"]["site"], "臺北");
+    assert_eq!(data[0]["air"]["air_quality"]["site"], "中山");
+
+    let res = service.get("/weather?air=maybe").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn disabled_without_an_api_key() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    // 設定問題，重試也不會成功
+    for path in ["/air", "/weather?air=true"] {
+        let res = service.get(path).await;
+        assert_eq!(res.status(), StatusCode::NOT_IMPLEMENTED, "{}", path);
+        assert!(res.text().await.unwrap().contains("AIR_API_KEY is not set"));
+    }
+
+    // 其餘資料不受影響
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(mock.hits(UV), 0);
+}
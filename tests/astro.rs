@@ -0,0 +1,61 @@
+mod common;
+
+use common::start;
+use reqwest::StatusCode;
+
+#[tokio::test]
+async fn computes_sun_and_moon_locally() {
+    let (mock, service) = start(&[]).await;
+
+    let data = service
+        .json("/astro?lat=25.0377&lon=121.5149&date=2026-10-19")
+        .await;
+    assert_eq!(data["date"], "2026-10-19");
+    assert_eq!(data["location"]["lat"], 25.0377);
+
+    let sunrise = data["sunrise"].as_str().unwrap();
+    assert!(sunrise.starts_with("2026-10-19T05:"));
+    assert!(sunrise.ends_with("+08:00"));
+    assert!(data["sunset"]
+        .as_str()
+        .unwrap()
+        .starts_with("2026-10-19T17:"));
+    assert!(data["civil_dawn"].as_str().unwrap() < sunrise);
+
+    let day_length = data["day_length_minutes"].as_i64().unwrap();
+    assert!((11 * 60..12 * 60).contains(&day_length));
+    assert!(data["moon_phase"].is_string());
+    assert!(data["moon_phase_name"].is_string());
+
+    // 不需向上游請求
+    assert_eq!(mock.requests(), 0);
+}
+
+#[tokio::test]
+async fn translates_moon_phase() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service
+        .json("/astro?lat=25.0377&lon=121.5149&date=2024-01-26&lang=en")
+        .await;
+    assert_eq!(data["moon_phase"], "full_moon");
+    assert_eq!(data["moon_phase_name"], "Full moon");
+}
+
+#[tokio::test]
+async fn rejects_invalid_queries() {
+    let (_mock, service) = start(&[]).await;
+
+    for query in [
+        "/astro",
+        "/astro?lat=25.0",
+        "/astro?lat=91&lon=121.5",
+        "/astro?lat=25.0&lon=abc",
+        "/astro?lat=25.0&lon=121.5&date=2026-13-01",
+        "/astro?lat=25&lon=121&date=-262143-01-01",
+        "/astro?lat=25&lon=121&date=2101-01-01",
+    ] {
+        let res = service.get(query).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", query);
+    }
+}
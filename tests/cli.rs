@@ -0,0 +1,163 @@
+mod common;
+
+use common::{names, MockCwb, Service, NEW_TAIPEI_FORECAST, WEATHER_DATA, WEATHER_FORECAST};
+use serde_json::Value;
+use std::{path::PathBuf, process::Command};
+
+/// 放入錄製資料的暫存目錄，供 `--data-dir` 使用
+fn data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("kirby-cli-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, dataset) in [
+        (WEATHER_DATA, WEATHER_DATA),
+        (NEW_TAIPEI_FORECAST, WEATHER_FORECAST),
+    ] {
+        let path = dir.join(format!("{}.json", name));
+        std::fs::write(path, common::fixture(dataset, None)).unwrap();
+    }
+
+    dir
+}
+
+/// 執行 `kirby`，回傳 (是否成功, stdout, stderr)
+fn kirby(args: &[&str]) -> (bool, String, String) {
+    kirby_with(&[], args)
+}
+
+/// 同 `kirby`，另外設定環境變數
+fn kirby_with(envs: &[(&str, &str)], args: &[&str]) -> (bool, String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_kirby"))
+        .args(args)
+        .current_dir(std::env::temp_dir())
+        .env_clear()
+        .envs(envs.iter().copied())
+        .output()
+        .expect("failed to run kirby");
+
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn reads_local_data() {
+    let dir = data_dir("local");
+    let dir = dir.to_str().unwrap();
+
+    let (ok, out, _) = kirby(&[
+        "--data-dir",
+        dir,
+        "now",
+        "--sort",
+        "temp",
+        "--format",
+        "json",
+    ]);
+    assert!(ok);
+    assert_eq!(
+        names(&serde_json::from_str(&out).unwrap()),
+        ["玉山", "阿里山", "板橋", "臺北"]
+    );
+
+    let (ok, out, _) = kirby(&[
+        "--data-dir",
+        dir,
+        "now",
+        "--city",
+        "台北市",
+        "--format",
+        "csv",
+    ]);
+    assert!(ok);
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("city,town,station,temp (degC)"));
+    assert!(lines[1].starts_with("臺北市,中正區,臺北,30.5"));
+
+    let (ok, out, _) = kirby(&[
+        "--data-dir",
+        dir,
+        "stations",
+        "--near",
+        "23.5,120.8",
+        "--limit",
+        "2",
+        "--format",
+        "json",
+    ]);
+    assert!(ok);
+    let data: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(names(&data), ["阿里山", "玉山"]);
+    assert!(data[0]["distance_km"].is_number());
+
+    let (ok, out, _) = kirby(&["--data-dir", dir, "forecast", "--town", "板橋區"]);
+    assert!(ok);
+    assert!(out.starts_with("新北市 板橋區\n"));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reports_errors() {
+    let dir = data_dir("errors");
+    let dir = dir.to_str().unwrap();
+
+    // 多個縣市皆有 東區
+    let (ok, _, err) = kirby(&["--data-dir", dir, "forecast", "--town", "東區"]);
+    assert!(!ok);
+    assert!(err.starts_with("error: 東區 is in"));
+
+    let (ok, _, _) = kirby(&["--data-dir", dir, "stations", "--near", "north"]);
+    assert!(!ok);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+// `kirby` 同步執行，模擬的 CWB 需在其他執行緒回應
+#[tokio::test(flavor = "multi_thread")]
+async fn queries_cwb_for_the_whole_county() {
+    let mock = MockCwb::start().await;
+    let url = mock.url();
+
+    let (ok, out, _) = kirby_with(
+        &[("CWB_API", &url), ("TOKEN", common::TOKEN)],
+        &["forecast", "--town", "板橋區"],
+    );
+    assert!(ok);
+    assert!(out.starts_with("新北市 板橋區\n"));
+
+    // 任一鄉鎮都可能被查詢，不可只取第一個鄉鎮
+    let query = mock.query(WEATHER_FORECAST).unwrap();
+    assert!(query.contains("locationId=F-D0047-071"), "{}", query);
+    assert!(!query.contains("limit="), "{}", query);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn queries_the_service() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+    let server = service.url("");
+
+    let (ok, out, _) = kirby(&[
+        "--server", &server, "now", "--sort", "rain", "--limit", "1", "--format", "json",
+    ]);
+    assert!(ok);
+    assert_eq!(names(&serde_json::from_str(&out).unwrap()), ["阿里山"]);
+
+    let (ok, out, _) = kirby(&[
+        "--server",
+        &server,
+        "--lang",
+        "en",
+        "forecast",
+        "--town",
+        "板橋區",
+        "--format",
+        "json",
+    ]);
+    assert!(ok);
+    let data: Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(data["name"], "Banqiao District");
+}
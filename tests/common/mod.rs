@@ -0,0 +1,339 @@
+//! 整合測試共用工具：模擬 CWB 開放資料 API 的本機伺服器，以及啟動服務執行檔
+
+#![allow(dead_code)]
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    fs,
+    io::{BufRead, BufReader},
+    net::{SocketAddr, TcpListener},
+    path::PathBuf,
+    process::{Child, ChildStdout, Command, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+pub const TOKEN: &str = "CWB-TEST-TOKEN";
+
+pub const WEATHER_DATA: &str = "O-A0001-001";
+pub const WEATHER_FORECAST: &str = "F-D0047-093";
+/// 新北市鄉鎮預報，檔案來源的預報檔以此命名
+pub const NEW_TAIPEI_FORECAST: &str = "F-D0047-071";
+pub const MANNED_STATIONS: &str = "O-A0003-001";
+pub const RAINFALL: &str = "O-A0002-001";
+pub const WARNINGS: &str = "W-C0033-001";
+pub const TYPHOONS: &str = "W-C0034-005";
+pub const SIGNIFICANT_EARTHQUAKES: &str = "E-A0015-001";
+pub const LOCAL_EARTHQUAKES: &str = "E-A0016-001";
+pub const UV: &str = "uv_s_01";
+pub const AIR_QUALITY: &str = "aqx_p_432";
+
+/// 讀取 `tests/fixtures` 下的錄製資料，`variant` 為 `empty`、`malformed` 等變化版本
+pub fn fixture(dataset: &str, variant: Option<&str>) -> String {
+    let name = match variant {
+        Some(variant) => format!("{}.{}.json", dataset, variant),
+        None => format!("{}.json", dataset),
+    };
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+
+    fs::read_to_string(&path).unwrap_or_else(|err| panic!("{}: {}", path.display(), err))
+}
+
+/// 模擬 CWB 對單一 dataset 的回應
+#[derive(Clone)]
+pub struct Reply {
+    pub status: StatusCode,
+    pub body: String,
+}
+
+impl Reply {
+    pub fn fixture(dataset: &str, variant: Option<&str>) -> Self {
+        Reply {
+            status: StatusCode::OK,
+            body: fixture(dataset, variant),
+        }
+    }
+
+    pub fn status(status: StatusCode) -> Self {
+        Reply {
+            status,
+            body: String::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    replies: Mutex<HashMap<String, Reply>>,
+    hits: Mutex<HashMap<String, usize>>,
+    queries: Mutex<HashMap<String, String>>,
+    requests: AtomicUsize,
+}
+
+/// 本機模擬的 CWB 開放資料 API，`/v1/rest/datastore/{dataset}` 依設定回傳錄製資料
+///
+/// 同時模擬環境部開放資料 API 的 `/api/v2/{dataset}`，授權碼改以 `api_key` 帶入。
+pub struct MockCwb {
+    pub addr: SocketAddr,
+    state: Arc<State>,
+}
+
+impl MockCwb {
+    /// 預設回傳正常的錄製資料
+    pub async fn start() -> Self {
+        let mock = Self::empty().await;
+        mock.reply(WEATHER_DATA, Reply::fixture(WEATHER_DATA, None));
+        mock.reply(WEATHER_FORECAST, Reply::fixture(WEATHER_FORECAST, None));
+        mock.reply(RAINFALL, Reply::fixture(RAINFALL, None));
+        mock.reply(WARNINGS, Reply::fixture(WARNINGS, None));
+        mock.reply(TYPHOONS, Reply::fixture(TYPHOONS, None));
+        for dataset in [SIGNIFICANT_EARTHQUAKES, LOCAL_EARTHQUAKES, UV, AIR_QUALITY] {
+            mock.reply(dataset, Reply::fixture(dataset, None));
+        }
+        mock
+    }
+
+    /// 所有 dataset 皆回傳 404
+    pub async fn empty() -> Self {
+        let state = Arc::new(State::default());
+
+        let service = make_service_fn({
+            let state = state.clone();
+            move |_| {
+                let state = state.clone();
+                async move {
+                    Ok::<_, Infallible>(service_fn(move |req| {
+                        let state = state.clone();
+                        async move { Ok::<_, Infallible>(handle(&state, req)) }
+                    }))
+                }
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = Server::from_tcp(listener).unwrap().serve(service);
+        tokio::spawn(server);
+
+        MockCwb { addr, state }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn reply(&self, dataset: &str, reply: Reply) {
+        let mut replies = self.state.replies.lock().unwrap();
+        replies.insert(dataset.to_owned(), reply);
+    }
+
+    /// 指定 dataset 被請求的次數
+    pub fn hits(&self, dataset: &str) -> usize {
+        let hits = self.state.hits.lock().unwrap();
+        hits.get(dataset).copied().unwrap_or(0)
+    }
+
+    /// 指定 dataset 最近一次請求的 query string
+    pub fn query(&self, dataset: &str) -> Option<String> {
+        let queries = self.state.queries.lock().unwrap();
+        queries.get(dataset).cloned()
+    }
+
+    /// 所有請求次數，包含授權碼錯誤者
+    pub fn requests(&self) -> usize {
+        self.state.requests.load(Ordering::SeqCst)
+    }
+}
+
+fn handle(state: &State, req: Request<Body>) -> Response<Body> {
+    state.requests.fetch_add(1, Ordering::SeqCst);
+
+    let authorized = req
+        .uri()
+        .query()
+        .map(|query| {
+            querystring::querify(query)
+                .into_iter()
+                .any(|(key, value)| matches!(key, "Authorization" | "api_key") && value == TOKEN)
+        })
+        .unwrap_or(false);
+
+    if !authorized {
+        return Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::from(r#"{"message":"Unauthorized"}"#))
+            .unwrap();
+    }
+
+    let path = req.uri().path();
+    let dataset = match path
+        .strip_prefix("/v1/rest/datastore/")
+        .or_else(|| path.strip_prefix("/api/v2/"))
+    {
+        Some(dataset) => dataset.to_owned(),
+        None => {
+            return Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+                .unwrap()
+        }
+    };
+
+    *state
+        .hits
+        .lock()
+        .unwrap()
+        .entry(dataset.clone())
+        .or_default() += 1;
+    state.queries.lock().unwrap().insert(
+        dataset.clone(),
+        req.uri().query().unwrap_or_default().to_owned(),
+    );
+
+    let reply = state.replies.lock().unwrap().get(&dataset).cloned();
+    let reply = reply.unwrap_or_else(|| Reply::status(StatusCode::NOT_FOUND));
+
+    Response::builder()
+        .status(reply.status)
+        .header("content-type", "application/json")
+        .body(Body::from(reply.body))
+        .unwrap()
+}
+
+/// 由服務的 JSON 日誌讀出 `service runs on` 的位址，之後的日誌在背景讀完丟棄
+fn listening_addr(stdout: ChildStdout) -> SocketAddr {
+    let mut lines = BufReader::new(stdout).lines();
+
+    let addr = lines
+        .by_ref()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(&line).ok())
+        .filter(|log| {
+            log["fields"]["message"]
+                .as_str()
+                .is_some_and(|message| message.starts_with("service runs on"))
+        })
+        .find_map(|log| log["fields"]["addr"].as_str()?.parse().ok())
+        .expect("service exited before listening");
+
+    std::thread::spawn(move || lines.for_each(drop));
+    addr
+}
+
+/// 以子程序啟動的服務，結束時一併終止
+pub struct Service {
+    pub addr: SocketAddr,
+    child: Child,
+}
+
+impl Service {
+    /// 指向 `cwb_api` 啟動服務，`envs` 可覆寫預設設定
+    pub async fn start(cwb_api: &str, envs: &[(&str, &str)]) -> Self {
+        let mut defaults = vec![("CWB_API", cwb_api), ("TOKEN", TOKEN)];
+        defaults.extend_from_slice(envs);
+
+        Self::spawn(&[], &defaults).await
+    }
+
+    /// 以 `--data-dir` 離線模式啟動，不設定 `CWB_API` 與 `TOKEN`
+    pub async fn offline(data_dir: &str, envs: &[(&str, &str)]) -> Self {
+        Self::spawn(&["--data-dir", data_dir], envs).await
+    }
+
+    async fn spawn(args: &[&str], envs: &[(&str, &str)]) -> Self {
+        // 以 port 0 綁定，由服務啟動日誌取得實際位址，避免先取 port 再釋放時被其他程序搶走
+        let mut child = Command::new(env!("CARGO_BIN_EXE_kirby_weather"))
+            .args(args)
+            // 避免讀到開發用的 .env 或 kirby.toml
+            .current_dir(std::env::temp_dir())
+            .env_clear()
+            .env("HOST", "127.0.0.1")
+            .env("PORT", "0")
+            .env("RETRY_MAX", "0")
+            .env("LOG_LEVEL", "info")
+            .envs(envs.iter().copied())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start service");
+
+        let stdout = child.stdout.take().unwrap();
+        let addr = tokio::task::spawn_blocking(move || listening_addr(stdout))
+            .await
+            .unwrap();
+
+        let service = Service { addr, child };
+        service.wait_until_listening().await;
+        service
+    }
+
+    async fn wait_until_listening(&self) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+
+        while Instant::now() < deadline {
+            if tokio::net::TcpStream::connect(self.addr).await.is_ok() {
+                return;
+            }
+
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        panic!("service did not start listening on {}", self.addr);
+    }
+
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    pub async fn get(&self, path: &str) -> reqwest::Response {
+        reqwest::get(self.url(path)).await.unwrap()
+    }
+
+    pub async fn json(&self, path: &str) -> serde_json::Value {
+        let res = self.get(path).await;
+        assert_eq!(res.status(), StatusCode::OK, "GET {}", path);
+        res.json().await.unwrap()
+    }
+}
+
+impl Drop for Service {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// 啟動模擬 CWB 與指向它的服務，`envs` 可覆寫預設設定
+pub async fn start(envs: &[(&str, &str)]) -> (MockCwb, Service) {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), envs).await;
+    (mock, service)
+}
+
+/// 回傳陣列中各項目的 `name`
+pub fn names(data: &serde_json::Value) -> Vec<&str> {
+    data.as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect()
+}
+
+/// 臺北 測站的溫度由 30.5 改為 31.5
+pub fn warmer_taipei() -> Reply {
+    Reply {
+        status: StatusCode::OK,
+        body: fixture(WEATHER_DATA, None).replacen("\"30.5\"", "\"31.5\"", 1),
+    }
+}
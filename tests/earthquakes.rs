@@ -0,0 +1,100 @@
+mod common;
+
+use common::{start, MockCwb, Reply, Service, LOCAL_EARTHQUAKES, SIGNIFICANT_EARTHQUAKES};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+fn origin_times(data: &Value) -> Vec<&str> {
+    data.as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["origin_time"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn merges_both_reports_newest_first() {
+    let (mock, service) = start(&[]).await;
+
+    let data = service.json("/earthquakes").await;
+    assert_eq!(
+        origin_times(&data),
+        [
+            "2026-10-19T13:40:12+08:00",
+            "2026-10-18T21:05:33+08:00",
+            "2026-10-15T02:10:00+08:00"
+        ]
+    );
+
+    let hualien = &data[0];
+    assert_eq!(hualien["number"], 115045);
+    assert_eq!(hualien["magnitude"], 5.8);
+    assert_eq!(hualien["depth"], 10.0);
+    assert_eq!(hualien["location"]["lat"], 23.8);
+    assert_eq!(hualien["max_intensity"], "5-");
+
+    // 各縣市取最大震度，依震度排序
+    let intensities: Vec<_> = hualien["intensities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| {
+            (
+                item["city"].as_str().unwrap(),
+                item["intensity"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        intensities,
+        [
+            ("花蓮縣", "5-"),
+            ("宜蘭縣", "3"),
+            ("南投縣", "3"),
+            ("臺北市", "2")
+        ]
+    );
+
+    // 小區域有感地震沒有編號
+    assert!(data[1].get("number").is_none());
+
+    // 第二次請求使用快取
+    service.json("/earthquakes").await;
+    assert_eq!(mock.hits(SIGNIFICANT_EARTHQUAKES), 1);
+    assert_eq!(mock.hits(LOCAL_EARTHQUAKES), 1);
+}
+
+#[tokio::test]
+async fn filters_by_time_and_magnitude() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/earthquakes?since=2026-10-18").await;
+    assert_eq!(origin_times(&data).len(), 2);
+
+    let data = service
+        .json("/earthquakes?since=2026-10-18T22:00:00%2B08:00")
+        .await;
+    assert_eq!(origin_times(&data), ["2026-10-19T13:40:12+08:00"]);
+
+    let data = service.json("/earthquakes?min_magnitude=4.5").await;
+    assert_eq!(
+        origin_times(&data),
+        ["2026-10-19T13:40:12+08:00", "2026-10-15T02:10:00+08:00"]
+    );
+
+    let data = service.json("/earthquakes?lang=en&limit=1").await;
+    assert_eq!(data[0]["intensities"][0]["city"], "Hualien County");
+
+    let res = service.get("/earthquakes?since=yesterday").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn unavailable_when_a_report_fails() {
+    let mock = MockCwb::start().await;
+    mock.reply(LOCAL_EARTHQUAKES, Reply::status(StatusCode::BAD_GATEWAY));
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/earthquakes").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
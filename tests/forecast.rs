@@ -0,0 +1,90 @@
+mod common;
+
+use common::{MockCwb, Service, WEATHER_FORECAST};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+fn approx(value: &Value, expected: f64) -> bool {
+    (value.as_f64().unwrap() - expected).abs() < 0.01
+}
+
+#[tokio::test]
+async fn summarises_week_forecast() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/forecast").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-language"], "zh-TW");
+
+    let data: Value = res.json().await.unwrap();
+    assert_eq!(data["city"], "新北市");
+    assert_eq!(data["name"], "板橋區");
+    assert_eq!(data["max_temperature"], 30.0);
+    assert_eq!(data["min_temperature"], 19.0);
+    assert_eq!(data["temperature_difference_per_day"], 11.0);
+
+    let descriptions = data["descriptions"].as_array().unwrap();
+    assert_eq!(descriptions.len(), 8);
+    assert_eq!(descriptions[0]["element"], "天氣現象");
+    assert_eq!(descriptions[0]["value"], "多雲");
+    assert_eq!(descriptions[0]["start_time"], "2026-10-19 18:00:00");
+    assert_eq!(descriptions[0]["end_time"], "2026-10-20 06:00:00");
+    assert_eq!(descriptions[0]["period"], "night");
+    assert_eq!(descriptions[1]["period"], "day");
+    assert_eq!(descriptions[4]["element"], "天氣預報綜合描述");
+
+    // 只取新北市一週預報的第一個鄉鎮
+    assert_eq!(mock.hits(WEATHER_FORECAST), 1);
+    let query = mock.query(WEATHER_FORECAST).unwrap();
+    assert!(query.contains("locationId=F-D0047-071"), "{}", query);
+    assert!(query.contains("limit=1"), "{}", query);
+}
+
+#[tokio::test]
+async fn converts_units() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/forecast?units=imperial").await;
+    assert!(res.headers()["x-units"]
+        .to_str()
+        .unwrap()
+        .starts_with("system=imperial"));
+
+    let data: Value = res.json().await.unwrap();
+    assert!(approx(&data["max_temperature"], 86.0));
+    assert!(approx(&data["min_temperature"], 66.2));
+    // 溫差不做零點平移
+    assert!(approx(&data["temperature_difference_per_day"], 19.8));
+
+    let data = service.json("/forecast?units=si").await;
+    assert!(approx(&data["max_temperature"], 303.15));
+    assert!(approx(&data["temperature_difference_per_day"], 11.0));
+
+    let res = service.get("/forecast?units=rankine").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn translates_descriptions() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/forecast?lang=en").await;
+    assert_eq!(res.headers()["content-language"], "en");
+
+    let data: Value = res.json().await.unwrap();
+    assert_eq!(data["city"], "New Taipei City");
+    assert_eq!(data["name"], "Banqiao District");
+
+    let descriptions = data["descriptions"].as_array().unwrap();
+    assert_eq!(descriptions[0]["element"], "Weather phenomenon");
+    assert_eq!(descriptions[0]["value"], "Cloudy");
+    assert_eq!(descriptions[1]["value"], "Mostly sunny");
+    assert_eq!(descriptions[4]["element"], "Weather description");
+    assert_ne!(descriptions[4]["value"], "");
+
+    let res = service.get("/forecast?lang=ja").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
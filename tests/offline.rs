@@ -0,0 +1,100 @@
+mod common;
+
+use common::{fixture, Service, NEW_TAIPEI_FORECAST, WEATHER_DATA, WEATHER_FORECAST};
+use std::{fs, path::PathBuf, time::Duration};
+
+/// 每個測試使用獨立的暫存目錄
+fn data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("kirby-offline-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn serves_files_without_cwb_settings() {
+    let dir = data_dir("serve");
+    fs::write(dir.join("O-A0001-001.json"), fixture(WEATHER_DATA, None)).unwrap();
+    // 入口網站下載的檔案，以縣市預報 dataset 命名
+    fs::write(
+        dir.join(format!("{}.json", NEW_TAIPEI_FORECAST)),
+        fixture(WEATHER_FORECAST, None),
+    )
+    .unwrap();
+
+    let service = Service::offline(dir.to_str().unwrap(), &[]).await;
+
+    let data = service.json("/weather?order_by=TEMP&limit=1").await;
+    assert_eq!(data[0]["name"], "玉山");
+
+    let data = service.json("/forecast?lang=en").await;
+    assert_eq!(data["name"], "Banqiao District");
+
+    let data = service.json("/readyz").await;
+    assert_eq!(data["ready"], true);
+
+    drop(service);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn picks_up_newer_files() {
+    let dir = data_dir("watch");
+    fs::write(
+        dir.join("O-A0001-001_0001.json"),
+        fixture(WEATHER_DATA, None),
+    )
+    .unwrap();
+
+    let service = Service::offline(
+        dir.to_str().unwrap(),
+        &[("CACHE_TTL", "3600"), ("WATCH_INTERVAL", "1")],
+    )
+    .await;
+
+    let data = service.json("/weather").await;
+    assert_eq!(data.as_array().unwrap().len(), 4);
+
+    // 確保修改時間晚於前一個檔案
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    fs::write(
+        dir.join("O-A0001-001_0002.json"),
+        fixture(WEATHER_DATA, Some("empty")),
+    )
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+
+    // 快取尚未過期，但偵測到新檔案後重新讀取
+    let data = service.json("/weather").await;
+    assert!(data.as_array().unwrap().is_empty());
+
+    drop(service);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn missing_files_are_unavailable() {
+    let dir = data_dir("missing");
+    // 非 dataset 檔名的變化版本與其他縣市的預報都不使用
+    fs::write(
+        dir.join("O-A0001-001.empty.json"),
+        fixture(WEATHER_DATA, Some("empty")),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("F-D0047-061.json"),
+        fixture(WEATHER_FORECAST, None),
+    )
+    .unwrap();
+
+    let service = Service::offline(dir.to_str().unwrap(), &[]).await;
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    let res = service.get("/forecast").await;
+    assert_eq!(res.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+
+    drop(service);
+    fs::remove_dir_all(dir).unwrap();
+}
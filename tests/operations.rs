@@ -0,0 +1,144 @@
+mod common;
+
+use common::{MockCwb, Reply, Service, MANNED_STATIONS, WEATHER_DATA, WEATHER_FORECAST};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+#[tokio::test]
+async fn healthz_and_version() {
+    let mock = MockCwb::empty().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let data = service.json("/healthz").await;
+    assert_eq!(data["status"], "ok");
+
+    let data = service.json("/version").await;
+    assert_eq!(data["version"], env!("CARGO_PKG_VERSION"));
+    assert!(data["git_sha"].is_string());
+    assert!(data["build_time"].is_string());
+
+    // 存活檢查不依賴上游
+    assert_eq!(mock.requests(), 0);
+}
+
+/// 重複檢查 `/readyz`，直到 `done` 成立
+async fn readyz_until(service: &Service, done: impl Fn(&Value) -> bool) -> Value {
+    for _ in 0..100 {
+        let data: Value = service.get("/readyz").await.json().await.unwrap();
+        if done(&data) {
+            return data;
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+
+    panic!("/readyz did not reach the expected state");
+}
+
+#[tokio::test]
+async fn readyz_refreshes_stale_datasets_in_background() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    // 探針不等待上游，尚未取得資料時直接回應未就緒
+    let res = service.get("/readyz").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let data = readyz_until(&service, |data| data["ready"] == true).await;
+    assert_eq!(data["datasets"][WEATHER_DATA]["fresh"], true);
+    assert_eq!(data["datasets"][WEATHER_FORECAST]["fresh"], true);
+    assert_eq!(mock.hits(WEATHER_DATA), 1);
+    assert_eq!(mock.hits(WEATHER_FORECAST), 1);
+
+    // 資料仍新鮮時不再請求上游
+    let res = service.get("/readyz").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(mock.hits(WEATHER_DATA), 1);
+}
+
+#[tokio::test]
+async fn readyz_reports_upstream_failures() {
+    let mock = MockCwb::start().await;
+    mock.reply(WEATHER_FORECAST, Reply::status(StatusCode::BAD_GATEWAY));
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let data = readyz_until(&service, |data| {
+        data["datasets"][WEATHER_FORECAST]["last_error"].is_string()
+    })
+    .await;
+    assert_eq!(data["ready"], false);
+    assert_eq!(data["datasets"][WEATHER_DATA]["fresh"], true);
+
+    let forecast = &data["datasets"][WEATHER_FORECAST];
+    assert_eq!(forecast["fresh"], false);
+    assert!(forecast["last_success"].is_null());
+    assert!(forecast["last_error"].as_str().unwrap().contains("502"));
+    // 錯誤訊息不可帶出授權碼
+    assert!(!data.to_string().contains(common::TOKEN));
+
+    let res = service.get("/readyz").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn readyz_and_metrics_follow_the_observation_dataset() {
+    let mock = MockCwb::start().await;
+    mock.reply(MANNED_STATIONS, Reply::fixture(MANNED_STATIONS, None));
+    let service = Service::start(&mock.url(), &[("OBSERVATION_DATASET", MANNED_STATIONS)]).await;
+
+    let data = readyz_until(&service, |data| data["ready"] == true).await;
+    assert_eq!(data["datasets"][MANNED_STATIONS]["fresh"], true);
+    assert!(data["datasets"].get(WEATHER_DATA).is_none());
+    assert_eq!(mock.hits(WEATHER_DATA), 0);
+
+    // 背景更新寫入的快取供之後的請求使用
+    service.json("/weather").await;
+    assert_eq!(mock.hits(MANNED_STATIONS), 1);
+
+    let text = service.get("/metrics").await.text().await.unwrap();
+    assert!(text.contains(r#"cache_requests_total{dataset="O-A0003-001",result="hit"} 1"#));
+    assert!(!text.contains(r#"dataset="O-A0001-001""#));
+}
+
+#[tokio::test]
+async fn metrics_counts_requests() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    service.json("/weather").await;
+    service.json("/weather").await;
+
+    let res = service.get("/metrics").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(res.headers()["content-type"]
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+
+    let text = res.text().await.unwrap();
+    assert!(text.contains(r#"http_requests_total{method="GET",route="/weather",status="200"} 2"#));
+    assert!(text.contains(r#"cache_requests_total{dataset="O-A0001-001",result="hit"} 1"#));
+    assert!(text.contains(r#"upstream_requests_total{dataset="O-A0001-001",result="ok"} 1"#));
+    assert!(text.contains("cwb_stations_dropped_total 2"));
+}
+
+#[tokio::test]
+async fn unknown_route_and_request_id() {
+    let mock = MockCwb::empty().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/nowhere").await;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    assert!(res.headers().contains_key("x-request-id"));
+
+    let text = service.get("/metrics").await.text().await.unwrap();
+    assert!(text.contains(r#"http_requests_total{method="GET",route="unmatched",status="404"} 1"#));
+
+    let res = reqwest::Client::new()
+        .get(service.url("/healthz"))
+        .header("x-request-id", "trace-me")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["x-request-id"], "trace-me");
+}
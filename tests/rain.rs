@@ -0,0 +1,113 @@
+mod common;
+
+use common::{names, start, MockCwb, Reply, Service, RAINFALL};
+use reqwest::StatusCode;
+
+#[tokio::test]
+async fn returns_accumulations_and_levels() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/rain").await;
+    // 故障站 沒有縣市、鄉鎮，不回傳
+    assert_eq!(names(&data), ["臺北", "宜蘭", "太平山", "阿里山", "板橋"]);
+
+    let taipei = &data[0];
+    assert_eq!(taipei["city"], "臺北市");
+    assert_eq!(taipei["min_10"], 0.5);
+    assert_eq!(taipei["hour_1"], 12.0);
+    assert_eq!(taipei["hour_24"], 85.0);
+    assert_eq!(taipei["today"], 70.5);
+    assert_eq!(taipei["level"], "heavy_rain");
+    assert_eq!(taipei["level_name"], "大雨");
+
+    // 宜蘭 24 小時 360.5 毫米
+    assert_eq!(data[1]["level"], "torrential_rain");
+    assert_eq!(data[1]["level_name"], "大豪雨");
+    assert_eq!(data[2]["level_name"], "超大豪雨");
+
+    // 阿里山 -998 表示無降雨
+    let alishan = &data[3];
+    assert_eq!(alishan["hour_24"], 0.0);
+    assert!(alishan.get("level").is_none());
+
+    // 板橋 僅 3 小時、24 小時 有資料，3 小時 110 毫米
+    let banqiao = &data[4];
+    assert!(banqiao.get("hour_1").is_none());
+    assert_eq!(banqiao["level_name"], "豪雨");
+}
+
+#[tokio::test]
+async fn orders_filters_and_groups() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/rain?order_by=RAIN").await;
+    assert_eq!(names(&data), ["太平山", "宜蘭", "臺北", "阿里山", "板橋"]);
+
+    let data = service.json("/rain?min_HOUR_3=100&order_by=HOUR_3").await;
+    assert_eq!(names(&data), ["太平山", "宜蘭", "板橋"]);
+
+    let data = service.json("/rain?level=extremely_heavy_rain").await;
+    assert_eq!(names(&data), ["宜蘭", "太平山", "板橋"]);
+
+    let data = service.json("/rain?order_by=HOUR_24&limit=2").await;
+    assert_eq!(names(&data), ["太平山", "宜蘭"]);
+
+    // 每個縣市取 24 小時雨量最高者
+    let data = service.json("/rain?group_by=CITY").await;
+    let groups: Vec<_> = data
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|group| {
+            (
+                group[0].as_str().unwrap(),
+                group[1]["name"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("嘉義縣", "阿里山"),
+            ("宜蘭縣", "太平山"),
+            ("新北市", "板橋"),
+            ("臺北市", "臺北")
+        ]
+    );
+}
+
+#[tokio::test]
+async fn converts_units_and_translates() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/rain?units=imperial&lang=en&limit=1").await;
+    let taipei = &data[0];
+    assert_eq!(taipei["city"], "Taipei City");
+    assert_eq!(taipei["level_name"], "Heavy rain");
+    assert!((taipei["hour_24"].as_f64().unwrap() - 85.0 / 25.4).abs() < 1e-3);
+
+    // 門檻同樣以英制比較，100 毫米約 3.94 英吋
+    let data = service
+        .json("/rain?units=imperial&min_HOUR_3=3.9&order_by=HOUR_3")
+        .await;
+    assert_eq!(names(&data), ["太平山", "宜蘭", "板橋"]);
+
+    let res = service.get("/rain?level=drizzle").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let res = service.get("/rain?min_RAIN=a%20lot").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn unavailable_without_rain_data() {
+    let mock = MockCwb::start().await;
+    mock.reply(RAINFALL, Reply::status(StatusCode::INTERNAL_SERVER_ERROR));
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/rain").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // 觀測資料不受影響
+    assert_eq!(service.get("/weather").await.status(), StatusCode::OK);
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::{MockCwb, Reply, Service, MANNED_STATIONS, WEATHER_DATA};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+#[tokio::test]
+async fn both_layouts_produce_the_same_records() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+    let v1 = service.json("/weather").await;
+
+    let mock = MockCwb::start().await;
+    mock.reply(WEATHER_DATA, Reply::fixture(WEATHER_DATA, Some("v2")));
+    let service = Service::start(&mock.url(), &[]).await;
+    let v2 = service.json("/weather").await;
+
+    assert_eq!(v2, v1);
+}
+
+#[tokio::test]
+async fn configured_schema_rejects_the_other_layout() {
+    let mock = MockCwb::start().await;
+    mock.reply(WEATHER_DATA, Reply::fixture(WEATHER_DATA, Some("v2")));
+
+    let service = Service::start(&mock.url(), &[("CWB_SCHEMA", "v1")]).await;
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let service = Service::start(&mock.url(), &[("CWB_SCHEMA", "v2")]).await;
+    let data = service.json("/weather").await;
+    assert_eq!(data.as_array().unwrap().len(), 4);
+}
+
+#[tokio::test]
+async fn manned_stations_include_extra_elements() {
+    let mock = MockCwb::start().await;
+    mock.reply(MANNED_STATIONS, Reply::fixture(MANNED_STATIONS, None));
+    let service = Service::start(&mock.url(), &[("OBSERVATION_DATASET", MANNED_STATIONS)]).await;
+
+    let data = service.json("/weather").await;
+    assert_eq!(mock.hits(WEATHER_DATA), 0);
+
+    let taipei = &data[0];
+    assert_eq!(taipei["name"], "臺北");
+    assert_eq!(taipei["weather"], "多雲");
+    assert_eq!(taipei["visibility"], ">30");
+    assert!((taipei["sunshine_duration"].as_f64().unwrap() - 5.2).abs() < 0.01);
+
+    // 缺值 (-99) 的項目不回傳
+    let tamsui = &data[1];
+    assert_eq!(tamsui["visibility"], "10-15");
+    assert!(tamsui.get("weather").is_none());
+    assert!(tamsui.get("sunshine_duration").is_none());
+
+    let data: Value = service.json("/weather?lang=en&limit=1").await;
+    assert_eq!(data[0]["weather"], "Cloudy");
+}
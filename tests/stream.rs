@@ -0,0 +1,179 @@
+mod common;
+
+use common::{names, warmer_taipei, MockCwb, Service, WEATHER_DATA};
+use reqwest::StatusCode;
+use serde_json::Value;
+use std::time::Duration;
+
+/// 臺北市的 URL 編碼
+const TAIPEI_CITY: &str = "%E8%87%BA%E5%8C%97%E5%B8%82";
+
+/// 解析後的 SSE 事件，heartbeat 註解行以 `name` 為 `heartbeat` 表示
+#[derive(Debug)]
+struct Event {
+    id: Option<u64>,
+    name: String,
+    data: Value,
+}
+
+/// 從串流回應中逐一讀取事件
+struct Events {
+    res: reqwest::Response,
+    buffer: String,
+}
+
+impl Events {
+    async fn connect(service: &Service, path: &str, last_event_id: Option<&str>) -> Events {
+        let mut req = reqwest::Client::new().get(service.url(path));
+        if let Some(id) = last_event_id {
+            req = req.header("Last-Event-ID", id);
+        }
+
+        let res = req.send().await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "GET {}", path);
+        assert_eq!(
+            res.headers()["content-type"],
+            "text/event-stream;charset=utf-8"
+        );
+
+        Events {
+            res,
+            buffer: String::new(),
+        }
+    }
+
+    async fn next(&mut self) -> Event {
+        let read = async {
+            loop {
+                if let Some(end) = self.buffer.find("\n\n") {
+                    let block: String = self.buffer.drain(..end + 2).collect();
+                    return parse(&block);
+                }
+
+                let chunk = self.res.chunk().await.unwrap().expect("stream ended");
+                self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+            }
+        };
+
+        tokio::time::timeout(Duration::from_secs(10), read)
+            .await
+            .expect("no event within 10 seconds")
+    }
+
+    /// 略過 heartbeat 的下一個事件
+    async fn next_data(&mut self) -> Event {
+        loop {
+            let event = self.next().await;
+            if event.name != "heartbeat" {
+                return event;
+            }
+        }
+    }
+}
+
+fn parse(block: &str) -> Event {
+    let mut event = Event {
+        id: None,
+        name: String::new(),
+        data: Value::Null,
+    };
+
+    for line in block.lines().filter(|line| !line.is_empty()) {
+        match line.split_once(": ") {
+            Some(("", _)) => event.name = "heartbeat".into(),
+            Some(("id", id)) => event.id = Some(id.parse().unwrap()),
+            Some(("event", name)) => event.name = name.into(),
+            Some(("data", data)) => event.data = serde_json::from_str(data).unwrap(),
+            _ => panic!("unexpected line: {}", line),
+        }
+    }
+
+    event
+}
+
+async fn start(envs: &[(&str, &str)]) -> (MockCwb, Service) {
+    let mut defaults = vec![("REFRESH_INTERVAL", "1")];
+    defaults.extend_from_slice(envs);
+    common::start(&defaults).await
+}
+
+#[tokio::test]
+async fn pushes_changed_readings_after_the_snapshot() {
+    let (mock, service) = start(&[]).await;
+
+    let path = format!("/weather/stream?city={}", TAIPEI_CITY);
+    let mut events = Events::connect(&service, &path, None).await;
+
+    let snapshot = events.next_data().await;
+    assert_eq!(snapshot.name, "snapshot");
+    assert_eq!(snapshot.id, Some(0));
+    assert_eq!(names(&snapshot.data), ["臺北"]);
+    assert_eq!(snapshot.data[0]["temp"], 30.5);
+
+    mock.reply(WEATHER_DATA, warmer_taipei());
+
+    let update = events.next_data().await;
+    assert_eq!(update.name, "update");
+    assert_eq!(update.id, Some(1));
+    assert_eq!(names(&update.data["updated"]), ["臺北"]);
+    assert_eq!(update.data["updated"][0]["temp"], 31.5);
+    assert_eq!(update.data["removed"], Value::Array(vec![]));
+
+    // 背景更新同時更新快取
+    let data = service.json("/weather?limit=1").await;
+    assert_eq!(data[0]["temp"], 31.5);
+}
+
+#[tokio::test]
+async fn resumes_from_last_event_id() {
+    let (mock, service) = start(&[]).await;
+
+    let mut events = Events::connect(&service, "/weather/stream", None).await;
+    assert_eq!(events.next_data().await.name, "snapshot");
+
+    mock.reply(WEATHER_DATA, warmer_taipei());
+    assert_eq!(events.next_data().await.id, Some(1));
+
+    // 重播緩衝內有編號 0 之後的變動，不再送出完整資料
+    let mut resumed = Events::connect(&service, "/weather/stream", Some("0")).await;
+    let update = resumed.next_data().await;
+    assert_eq!(update.name, "update");
+    assert_eq!(update.id, Some(1));
+
+    // 編號不明時改送完整資料
+    let mut unknown = Events::connect(&service, "/weather/stream", Some("42")).await;
+    let snapshot = unknown.next_data().await;
+    assert_eq!(snapshot.name, "snapshot");
+    assert_eq!(snapshot.id, Some(1));
+}
+
+#[tokio::test]
+async fn filters_by_bbox_and_station() {
+    let (_mock, service) = start(&[("HEARTBEAT_INTERVAL", "1")]).await;
+
+    let mut events =
+        Events::connect(&service, "/weather/stream?bbox=121.3,24.9,121.6,25.1", None).await;
+    assert_eq!(names(&events.next_data().await.data), ["臺北", "板橋"]);
+
+    // 沒有變動時送出 heartbeat
+    assert_eq!(events.next().await.name, "heartbeat");
+
+    let path = "/weather/stream?station=%E6%9D%BF%E6%A9%8B&units=imperial&lang=en";
+    let mut events = Events::connect(&service, path, None).await;
+    let snapshot = events.next_data().await;
+    assert_eq!(names(&snapshot.data), ["板橋"]);
+    assert_eq!(snapshot.data[0]["city"], "New Taipei City");
+
+    for query in ["bbox=121.6,24.9,121.3,25.1", "bbox=1,2,3", "units=kelvin"] {
+        let res = service.get(&format!("/weather/stream?{}", query)).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", query);
+    }
+}
+
+#[tokio::test]
+async fn does_not_poll_without_connections() {
+    let (mock, _service) = start(&[]).await;
+
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert_eq!(mock.hits(WEATHER_DATA), 0);
+}
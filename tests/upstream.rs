@@ -0,0 +1,298 @@
+mod common;
+
+use common::{
+    fixture, MockCwb, Reply, Service, NEW_TAIPEI_FORECAST, WEATHER_DATA, WEATHER_FORECAST,
+};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+#[tokio::test]
+async fn empty_datasets() {
+    let mock = MockCwb::start().await;
+    mock.reply(WEATHER_DATA, Reply::fixture(WEATHER_DATA, Some("empty")));
+    mock.reply(
+        WEATHER_FORECAST,
+        Reply::fixture(WEATHER_FORECAST, Some("empty")),
+    );
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let data = service.json("/weather").await;
+    assert_eq!(data, Value::Array(vec![]));
+
+    let data = service.json("/weather?group_by=ELEV").await;
+    assert_eq!(data, Value::Array(vec![]));
+
+    // 沒有任何鄉鎮可以彙整
+    let res = service.get("/forecast").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn stations_without_data_are_dropped() {
+    let mock = MockCwb::start().await;
+    mock.reply(WEATHER_DATA, Reply::fixture(WEATHER_DATA, Some("missing")));
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let data = service.json("/weather").await;
+    assert_eq!(data, Value::Array(vec![]));
+}
+
+#[tokio::test]
+async fn malformed_payloads() {
+    let mock = MockCwb::start().await;
+    mock.reply(
+        WEATHER_DATA,
+        Reply::fixture(WEATHER_DATA, Some("malformed")),
+    );
+    mock.reply(
+        WEATHER_FORECAST,
+        Reply::fixture(WEATHER_FORECAST, Some("malformed")),
+    );
+    let service = Service::start(&mock.url(), &[]).await;
+
+    for path in ["/weather", "/forecast"] {
+        let res = service.get(path).await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE, "{}", path);
+    }
+
+    let text = service.get("/metrics").await.text().await.unwrap();
+    assert!(text.contains(r#"upstream_requests_total{dataset="O-A0001-001",result="decode"} 1"#));
+}
+
+#[tokio::test]
+async fn drops_only_stations_with_invalid_values() {
+    let mock = MockCwb::start().await;
+    mock.reply(
+        WEATHER_DATA,
+        Reply {
+            status: StatusCode::OK,
+            body: fixture(WEATHER_DATA, None).replacen("\"30.5\"", "\"hot\"", 1),
+        },
+    );
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let data = service.json("/weather").await;
+    let mut names: Vec<_> = data
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect();
+    names.sort();
+    assert_eq!(names, ["板橋", "玉山", "阿里山"]);
+
+    // 缺值的 2 個測站，加上 臺北
+    let text = service.get("/metrics").await.text().await.unwrap();
+    assert!(text.contains("cwb_stations_dropped_total 3"));
+}
+
+#[tokio::test]
+async fn rejected_token() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[("TOKEN", "WRONG")]).await;
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // 4xx 不重試
+    assert_eq!(mock.requests(), 1);
+}
+
+#[tokio::test]
+async fn caches_upstream_data() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[("CACHE_TTL", "60")]).await;
+
+    for _ in 0..3 {
+        service.json("/weather").await;
+        service.json("/forecast").await;
+    }
+
+    assert_eq!(mock.hits(WEATHER_DATA), 1);
+    assert_eq!(mock.hits(WEATHER_FORECAST), 1);
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let mock = MockCwb::start().await;
+    mock.reply(WEATHER_DATA, Reply::status(StatusCode::SERVICE_UNAVAILABLE));
+    let service = Service::start(
+        &mock.url(),
+        &[("RETRY_MAX", "2"), ("RETRY_BASE_DELAY_MS", "1")],
+    )
+    .await;
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(mock.hits(WEATHER_DATA), 3);
+}
+
+#[tokio::test]
+async fn circuit_breaker_stops_calling_upstream() {
+    let mock = MockCwb::start().await;
+    mock.reply(
+        WEATHER_DATA,
+        Reply::status(StatusCode::INTERNAL_SERVER_ERROR),
+    );
+    let service = Service::start(
+        &mock.url(),
+        &[("BREAKER_THRESHOLD", "2"), ("BREAKER_COOLDOWN", "60")],
+    )
+    .await;
+
+    for _ in 0..4 {
+        let res = service.get("/weather").await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    assert_eq!(mock.hits(WEATHER_DATA), 2);
+}
+
+#[tokio::test]
+async fn circuit_breaker_closes_after_a_client_error_probe() {
+    let mock = MockCwb::start().await;
+    mock.reply(
+        WEATHER_DATA,
+        Reply::status(StatusCode::INTERNAL_SERVER_ERROR),
+    );
+    let service = Service::start(
+        &mock.url(),
+        &[("BREAKER_THRESHOLD", "1"), ("BREAKER_COOLDOWN", "1")],
+    )
+    .await;
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // 冷卻結束後的試探請求收到 4xx，斷路器不可停在 half-open
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    mock.reply(WEATHER_DATA, Reply::status(StatusCode::UNAUTHORIZED));
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(mock.hits(WEATHER_DATA), 2);
+
+    mock.reply(WEATHER_DATA, Reply::fixture(WEATHER_DATA, None));
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(mock.hits(WEATHER_DATA), 3);
+}
+
+#[tokio::test]
+async fn serves_stale_snapshot_when_upstream_fails() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[("CACHE_TTL", "0")]).await;
+
+    let res = service.get("/weather").await;
+    assert!(!res.headers().contains_key("x-data-stale"));
+    let fresh: Value = res.json().await.unwrap();
+
+    mock.reply(WEATHER_DATA, Reply::status(StatusCode::TOO_MANY_REQUESTS));
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-data-stale"], "true");
+    assert!(res.headers()["warning"]
+        .to_str()
+        .unwrap()
+        .starts_with("110"));
+    assert!(res.headers().contains_key("age"));
+
+    let stale: Value = res.json().await.unwrap();
+    assert_eq!(stale, fresh);
+}
+
+#[tokio::test]
+async fn malformed_forecast_value_serves_stale_snapshot() {
+    let mock = MockCwb::start().await;
+    let malformed = Reply {
+        status: StatusCode::OK,
+        body: fixture(WEATHER_FORECAST, None).replacen("\"22\"", "\" \"", 1),
+    };
+    mock.reply(WEATHER_FORECAST, malformed.clone());
+    let service = Service::start(&mock.url(), &[("CACHE_TTL", "0")]).await;
+
+    // 沒有快照時回傳 503，而非中斷連線
+    let res = service.get("/forecast").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    mock.reply(WEATHER_FORECAST, Reply::fixture(WEATHER_FORECAST, None));
+    let fresh = service.json("/forecast").await;
+
+    mock.reply(WEATHER_FORECAST, malformed);
+    let res = service.get("/forecast").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-data-stale"], "true");
+    assert_eq!(res.json::<Value>().await.unwrap(), fresh);
+}
+
+#[tokio::test]
+async fn snapshot_older_than_max_staleness_is_not_served() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[("CACHE_TTL", "0"), ("MAX_STALENESS", "0")]).await;
+
+    service.json("/weather").await;
+    mock.reply(WEATHER_DATA, Reply::status(StatusCode::BAD_GATEWAY));
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn snapshot_survives_restart() {
+    let mock = MockCwb::start().await;
+    let dir = std::env::temp_dir().join(format!("kirby-snapshots-{}", mock.addr.port()));
+    let envs = [("CACHE_TTL", "0"), ("SNAPSHOT_DIR", dir.to_str().unwrap())];
+
+    let service = Service::start(&mock.url(), &envs).await;
+    let fresh = service.json("/weather").await;
+
+    // 快照於背景寫入磁碟
+    let path = dir.join(format!("{}.json", WEATHER_DATA));
+    for _ in 0..100 {
+        if path.exists() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    assert!(path.exists());
+    drop(service);
+
+    let mock = MockCwb::empty().await;
+    let service = Service::start(&mock.url(), &envs).await;
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["x-data-stale"], "true");
+    assert_eq!(res.json::<Value>().await.unwrap(), fresh);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn file_source_does_not_call_cwb() {
+    let dir = std::env::temp_dir().join(format!("kirby-source-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for (name, dataset) in [
+        (WEATHER_DATA, WEATHER_DATA),
+        (NEW_TAIPEI_FORECAST, WEATHER_FORECAST),
+    ] {
+        let path = dir.join(format!("{}.json", name));
+        std::fs::write(path, common::fixture(dataset, None)).unwrap();
+    }
+
+    let mock = MockCwb::start().await;
+    let source = format!("file:{}", dir.display());
+    let service = Service::start(&mock.url(), &[("SOURCE", &source)]).await;
+
+    let data = service.json("/weather").await;
+    assert_eq!(data.as_array().unwrap().len(), 4);
+
+    let data = service.json("/forecast").await;
+    assert_eq!(data["name"], "板橋區");
+
+    assert_eq!(mock.requests(), 0);
+
+    drop(service);
+    std::fs::remove_dir_all(dir).unwrap();
+}
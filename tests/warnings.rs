@@ -0,0 +1,103 @@
+mod common;
+
+use common::{names, start, MockCwb, Reply, Service, TYPHOONS, WARNINGS};
+use reqwest::StatusCode;
+
+#[tokio::test]
+async fn lists_active_hazards_and_typhoons() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/warnings").await;
+
+    // 臺北市 的特報已結束、宜蘭縣 尚未生效、嘉義縣 沒有特報
+    let warnings = data["warnings"].as_array().unwrap();
+    let cities: Vec<_> = warnings
+        .iter()
+        .map(|item| item["city"].as_str().unwrap())
+        .collect();
+    assert_eq!(cities, ["新北市", "南投縣"]);
+
+    let hazard = &warnings[0]["hazards"][0];
+    assert_eq!(hazard["phenomenon"], "大雨");
+    assert_eq!(hazard["significance"], "特報");
+    assert_eq!(hazard["start_time"], "2026-10-19T08:00:00+08:00");
+    assert_eq!(warnings[1]["hazards"].as_array().unwrap().len(), 1);
+
+    let typhoons = &data["typhoons"];
+    assert_eq!(names(typhoons), ["KONG-REY", "TD23"]);
+
+    let kong_rey = &typhoons[0];
+    assert_eq!(kong_rey["name_zh"], "康芮");
+    assert_eq!(kong_rey["current"]["time"], "2026-10-19T14:00:00+08:00");
+    assert_eq!(kong_rey["current"]["location"]["lat"], 21.8);
+    assert_eq!(kong_rey["current"]["max_wind_speed"], 48.0);
+    assert_eq!(kong_rey["moving_direction"], "NW");
+
+    // 預報位置 依 發布時間 + 時距 排序
+    let forecast = kong_rey["forecast"].as_array().unwrap();
+    assert_eq!(forecast[0]["time"], "2026-10-20T02:00:00+08:00");
+    assert_eq!(forecast[1]["time"], "2026-10-20T14:00:00+08:00");
+
+    // 未提供的暴風半徑不回傳
+    assert!(typhoons[1]["current"].get("radius_15ms").is_none());
+}
+
+#[tokio::test]
+async fn filters_by_city_and_translates() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service
+        .json("/warnings?city=%E5%8D%97%E6%8A%95%E7%B8%A3&lang=en&units=imperial")
+        .await;
+    let warnings = data["warnings"].as_array().unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["city"], "Nantou County");
+    assert_eq!(
+        warnings[0]["hazards"][0]["phenomenon"],
+        "Strong wind on land"
+    );
+    assert_eq!(warnings[0]["hazards"][0]["significance"], "Advisory");
+
+    let speed = data["typhoons"][0]["current"]["max_wind_speed"]
+        .as_f64()
+        .unwrap();
+    assert!(speed > 100.0);
+}
+
+#[tokio::test]
+async fn filters_observations_and_forecast_by_warning() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/weather?warned=true").await;
+    assert_eq!(names(&data), ["玉山", "板橋"]);
+
+    let data = service.json("/weather?warned=false").await;
+    assert_eq!(names(&data), ["臺北", "阿里山"]);
+
+    // 預報的 新北市 有大雨特報
+    let res = service.get("/forecast?warned=true").await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = service.get("/forecast?warned=false").await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    let res = service.get("/weather?warned=maybe").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn unavailable_without_warning_data() {
+    let mock = MockCwb::start().await;
+    mock.reply(WARNINGS, Reply::status(StatusCode::INTERNAL_SERVER_ERROR));
+    mock.reply(TYPHOONS, Reply::fixture(TYPHOONS, None));
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/warnings").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let res = service.get("/weather?warned=true").await;
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // 不篩選特報時不受影響
+    assert_eq!(service.get("/weather").await.status(), StatusCode::OK);
+}
@@ -0,0 +1,187 @@
+mod common;
+
+use common::{names, start};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+#[tokio::test]
+async fn returns_valid_stations_and_drops_missing_values() {
+    let (_mock, service) = start(&[]).await;
+
+    let res = service.get("/weather").await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers()["content-language"], "zh-TW");
+    assert!(res.headers()["x-units"]
+        .to_str()
+        .unwrap()
+        .starts_with("system=metric; temperature=degC"));
+
+    let data: Value = res.json().await.unwrap();
+    // 故障站 溫度為 -99、缺高度 沒有 ELEV，皆不回傳
+    assert_eq!(names(&data), ["臺北", "玉山", "板橋", "阿里山"]);
+
+    let taipei = &data[0];
+    assert_eq!(taipei["city"], "臺北市");
+    assert_eq!(taipei["town"], "中正區");
+    assert_eq!(taipei["temp"], 30.5);
+    assert_eq!(taipei["location"]["lat"], 25.0377);
+    assert_eq!(taipei["pressure"], 1008.0);
+    assert!(taipei["dew_point"].is_number());
+    assert!(taipei["heat_index"].is_number());
+    assert!(taipei["humidex"].is_number());
+    assert!(taipei.get("wind_chill").is_none());
+
+    // 板橋 濕度、風速為 -99，不計算衍生氣象量
+    let banqiao = &data[2];
+    assert!(banqiao.get("wind_speed").is_none());
+    assert!(banqiao.get("dew_point").is_none());
+    assert!(banqiao.get("heat_index").is_none());
+
+    // 阿里山 沒有氣壓
+    assert!(data[3].get("pressure").is_none());
+}
+
+#[tokio::test]
+async fn order_by_each_field() {
+    let (_mock, service) = start(&[]).await;
+
+    let cases = [
+        ("TEMP", vec!["玉山", "阿里山", "板橋", "臺北"]),
+        ("H_24R", vec!["阿里山", "臺北", "板橋", "玉山"]),
+        ("DEW_POINT", vec!["臺北", "阿里山", "玉山", "板橋"]),
+        ("HEAT_INDEX", vec!["臺北", "阿里山", "玉山", "板橋"]),
+        ("HUMIDEX", vec!["臺北", "阿里山", "玉山", "板橋"]),
+        ("WIND_CHILL", vec!["玉山", "臺北", "板橋", "阿里山"]),
+        ("UNKNOWN", vec!["臺北", "玉山", "板橋", "阿里山"]),
+    ];
+
+    for (key, expected) in cases {
+        let data = service.json(&format!("/weather?order_by={}", key)).await;
+        assert_eq!(names(&data), expected, "order_by={}", key);
+    }
+}
+
+#[tokio::test]
+async fn filters_by_min_and_max() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/weather?min_TEMP=12").await;
+    assert_eq!(names(&data), ["臺北", "板橋", "阿里山"]);
+
+    let data = service.json("/weather?max_TEMP=12").await;
+    assert_eq!(names(&data), ["玉山", "阿里山"]);
+
+    let data = service.json("/weather?min_H_24R=10&max_H_24R=20").await;
+    assert_eq!(names(&data), ["臺北"]);
+
+    // 沒有風寒指數的測站不符合條件
+    let data = service.json("/weather?max_WIND_CHILL=100").await;
+    assert_eq!(names(&data), ["玉山"]);
+
+    for key in ["DEW_POINT", "HEAT_INDEX", "HUMIDEX"] {
+        let data = service.json(&format!("/weather?min_{}=-100", key)).await;
+        assert_eq!(names(&data), ["臺北", "玉山", "阿里山"], "min_{}", key);
+    }
+
+    let res = service.get("/weather?min_TEMP=hot").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn limit_and_combined_queries() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/weather?limit=2").await;
+    assert_eq!(names(&data), ["臺北", "玉山"]);
+
+    let data = service.json("/weather?order_by=TEMP&limit=1").await;
+    assert_eq!(names(&data), ["玉山"]);
+
+    let data = service
+        .json("/weather?min_TEMP=10&order_by=H_24R&limit=2")
+        .await;
+    assert_eq!(names(&data), ["阿里山", "臺北"]);
+
+    let res = service.get("/weather?limit=many").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn group_by_elevation() {
+    let (_mock, service) = start(&[]).await;
+
+    let data = service.json("/weather?group_by=ELEV").await;
+
+    // 回傳 [區間, 該區間最低溫的測站] 的陣列
+    let mut groups: Vec<_> = data
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|pair| (pair[0].as_str().unwrap(), pair[1]["name"].as_str().unwrap()))
+        .collect();
+    groups.sort();
+
+    assert_eq!(
+        groups,
+        [
+            ("0-500", "板橋"),
+            ("2000-2500", "阿里山"),
+            ("> 3000", "玉山")
+        ]
+    );
+
+    let data = service.json("/weather?group_by=CITY").await;
+    assert!(data.as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn converts_units() {
+    let (_mock, service) = start(&[]).await;
+
+    let res = service.get("/weather?units=imperial&limit=1").await;
+    assert!(res.headers()["x-units"]
+        .to_str()
+        .unwrap()
+        .starts_with("system=imperial; temperature=degF"));
+
+    let data: Value = res.json().await.unwrap();
+    assert!((data[0]["temp"].as_f64().unwrap() - 86.9).abs() < 0.01);
+
+    let data = service.json("/weather?units=si&limit=1").await;
+    assert!((data[0]["temp"].as_f64().unwrap() - 303.65).abs() < 0.01);
+
+    // 篩選門檻使用指定的單位制
+    let data = service.json("/weather?units=imperial&min_TEMP=54").await;
+    assert_eq!(names(&data), ["臺北", "板橋"]);
+
+    let data = service.json("/weather?units=si&max_TEMP=290").await;
+    assert_eq!(names(&data), ["玉山", "阿里山"]);
+
+    let res = service.get("/weather?units=kelvin").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn translates_city_and_town() {
+    let (_mock, service) = start(&[]).await;
+
+    let res = service.get("/weather?lang=en&limit=1").await;
+    assert_eq!(res.headers()["content-language"], "en");
+
+    let data: Value = res.json().await.unwrap();
+    assert_eq!(data[0]["city"], "Taipei City");
+    assert_eq!(data[0]["town"], "Zhongzheng District");
+    // 測站名稱沒有英譯
+    assert_eq!(data[0]["name"], "臺北");
+
+    let res = reqwest::Client::new()
+        .get(service.url("/weather?limit=1"))
+        .header("accept-language", "en-US,en;q=0.9,zh-TW;q=0.8")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["content-language"], "en");
+
+    let res = service.get("/weather?lang=fr").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
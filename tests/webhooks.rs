@@ -0,0 +1,397 @@
+mod common;
+
+use common::{warmer_taipei, MockCwb, Service, WEATHER_DATA, WEATHER_FORECAST};
+use hmac::{Hmac, KeyInit, Mac};
+use hyper::{
+    header::LOCATION,
+    service::{make_service_fn, service_fn},
+    Body, HeaderMap, Request, Response, Server,
+};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::{
+    collections::VecDeque,
+    convert::Infallible,
+    net::{SocketAddr, TcpListener},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+const ADMIN_TOKEN: &str = "ADMIN-TEST-TOKEN";
+
+/// 收到的 webhook 請求
+struct Received {
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl Received {
+    fn header(&self, name: &str) -> &str {
+        self.headers[name].to_str().unwrap()
+    }
+
+    fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+}
+
+/// 本機接收 webhook 的伺服器，依序回傳 `statuses`，用完後回傳 200；3xx 導向同一伺服器的 `/redirected`
+struct Receiver {
+    addr: SocketAddr,
+    received: mpsc::UnboundedReceiver<Received>,
+}
+
+impl Receiver {
+    async fn start(statuses: &[StatusCode]) -> Self {
+        let statuses = Arc::new(Mutex::new(
+            statuses.iter().copied().collect::<VecDeque<_>>(),
+        ));
+        let (sender, received) = mpsc::unbounded_channel();
+
+        let service = make_service_fn(move |_| {
+            let statuses = statuses.clone();
+            let sender = sender.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |req: Request<Body>| {
+                    let statuses = statuses.clone();
+                    let sender = sender.clone();
+                    async move {
+                        let (parts, body) = req.into_parts();
+                        let body = hyper::body::to_bytes(body).await.unwrap().to_vec();
+                        let _ = sender.send(Received {
+                            headers: parts.headers,
+                            body,
+                        });
+
+                        let status = statuses.lock().unwrap().pop_front();
+                        let status = status.unwrap_or(StatusCode::OK);
+                        let mut res = Response::builder().status(status);
+                        if status.is_redirection() {
+                            res = res.header(LOCATION, "/redirected");
+                        }
+                        let res = res.body(Body::empty()).unwrap();
+                        Ok::<_, Infallible>(res)
+                    }
+                }))
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(Server::from_tcp(listener).unwrap().serve(service));
+
+        Receiver { addr, received }
+    }
+
+    fn url(&self) -> String {
+        format!("http://{}/hook", self.addr)
+    }
+
+    async fn next(&mut self) -> Received {
+        tokio::time::timeout(Duration::from_secs(10), self.received.recv())
+            .await
+            .expect("no webhook within 10 seconds")
+            .unwrap()
+    }
+}
+
+async fn start() -> (MockCwb, Service) {
+    common::start(&[
+        ("ADMIN_TOKEN", ADMIN_TOKEN),
+        ("REFRESH_INTERVAL", "1"),
+        ("WEBHOOK_RETRY_BASE_DELAY_MS", "10"),
+    ])
+    .await
+}
+
+async fn admin(
+    service: &Service,
+    method: Method,
+    path: &str,
+    body: Option<Value>,
+) -> reqwest::Response {
+    let mut req = reqwest::Client::new()
+        .request(method, service.url(path))
+        .bearer_auth(ADMIN_TOKEN);
+    if let Some(body) = body {
+        req = req.json(&body);
+    }
+
+    req.send().await.unwrap()
+}
+
+async fn register(service: &Service, body: Value) -> Value {
+    let res = admin(service, Method::POST, "/admin/webhooks", Some(body)).await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+    res.json().await.unwrap()
+}
+
+/// 等待最新的傳送紀錄不再是 `pending`
+async fn delivery(service: &Service, id: &str) -> Value {
+    let path = format!("/admin/webhooks/{}/deliveries", id);
+
+    for _ in 0..100 {
+        let deliveries: Value = admin(service, Method::GET, &path, None)
+            .await
+            .json()
+            .await
+            .unwrap();
+        if deliveries[0]["status"] != "pending" {
+            return deliveries[0].clone();
+        }
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    panic!("delivery is still pending");
+}
+
+fn signature(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{}.", timestamp).as_bytes());
+    mac.update(body);
+
+    let digest: String = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha256={}", digest)
+}
+
+#[tokio::test]
+async fn delivers_signed_alerts_when_a_station_crosses_a_threshold() {
+    let (mock, service) = start().await;
+    let mut receiver = Receiver::start(&[]).await;
+
+    let webhook = register(
+        &service,
+        json!({
+            "url": receiver.url(),
+            "secret": "s3cret",
+            "rules": [{"id": "hot", "field": "temperature", "op": ">", "value": 31, "locations": ["C07014"]}],
+        }),
+    )
+    .await;
+    assert_eq!(webhook["secret"], "s3cret");
+    assert_eq!(webhook["rules"][0]["cooldown"], 3600);
+
+    let id = webhook["id"].as_str().unwrap();
+    let listed = admin(&service, Method::GET, "/admin/webhooks", None)
+        .await
+        .json::<Value>()
+        .await
+        .unwrap();
+    assert_eq!(listed[0]["id"], id);
+    assert!(listed[0].get("secret").is_none());
+
+    mock.reply(WEATHER_DATA, warmer_taipei());
+
+    let received = receiver.next().await;
+    let alert = received.json();
+    assert_eq!(alert["webhook"], id);
+    assert_eq!(alert["rule"]["id"], "hot");
+    assert_eq!(alert["matches"][0]["name"], "臺北");
+    assert_eq!(alert["matches"][0]["id"], "C07014");
+    assert_eq!(alert["matches"][0]["value"], 31.5);
+
+    assert_eq!(received.header("x-webhook-id"), alert["id"]);
+    assert_eq!(
+        received.header("x-webhook-signature"),
+        signature(
+            "s3cret",
+            received.header("x-webhook-timestamp"),
+            &received.body
+        )
+    );
+
+    let delivery = delivery(&service, id).await;
+    assert_eq!(delivery["id"], alert["id"]);
+    assert_eq!(delivery["status"], "delivered");
+    assert_eq!(delivery["attempts"][0]["status"], 200);
+}
+
+#[tokio::test]
+async fn retries_with_backoff_and_does_not_repeat_alerts() {
+    let (_mock, service) = start().await;
+    let mut receiver = Receiver::start(&[
+        StatusCode::INTERNAL_SERVER_ERROR,
+        StatusCode::SERVICE_UNAVAILABLE,
+    ])
+    .await;
+
+    // 玉山 已達門檻，第一次更新即通知
+    let webhook = register(
+        &service,
+        json!({
+            "url": receiver.url(),
+            "rules": [{"id": "cold", "field": "temperature", "op": "<=", "value": 2}],
+        }),
+    )
+    .await;
+    let secret = webhook["secret"].as_str().unwrap();
+    assert_eq!(secret.len(), 64);
+
+    let attempts = [
+        receiver.next().await,
+        receiver.next().await,
+        receiver.next().await,
+    ];
+    for received in &attempts {
+        assert_eq!(
+            received.header("x-webhook-id"),
+            attempts[0].header("x-webhook-id")
+        );
+        assert_eq!(
+            received.header("x-webhook-signature"),
+            signature(
+                secret,
+                received.header("x-webhook-timestamp"),
+                &received.body
+            )
+        );
+    }
+    assert_eq!(attempts[2].json()["matches"][0]["name"], "玉山");
+
+    let delivery = delivery(&service, webhook["id"].as_str().unwrap()).await;
+    assert_eq!(delivery["status"], "delivered");
+    let statuses: Vec<_> = delivery["attempts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|attempt| attempt["status"].as_u64().unwrap())
+        .collect();
+    assert_eq!(statuses, [500, 503, 200]);
+
+    // 仍達門檻的測站在之後的更新不重複通知
+    tokio::time::sleep(Duration::from_millis(2500)).await;
+    assert!(receiver.received.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn alerts_on_forecast_probability_of_precipitation() {
+    let (mock, service) = start().await;
+    let mut receiver = Receiver::start(&[StatusCode::BAD_REQUEST]).await;
+
+    let webhook = register(
+        &service,
+        json!({
+            "url": receiver.url(),
+            "rules": [{"id": "rain", "field": "pop", "op": ">=", "value": 60, "locations": ["板橋區"]}],
+        }),
+    )
+    .await;
+
+    let alert = receiver.next().await.json();
+    assert_eq!(alert["matches"][0]["name"], "板橋區");
+    assert_eq!(alert["matches"][0]["city"], "新北市");
+    assert_eq!(alert["matches"][0]["value"], 60.0);
+
+    // 取得規則所列鄉鎮所屬縣市的完整預報，而非服務預設縣市的第一個鄉鎮
+    let query = mock.query(WEATHER_FORECAST).unwrap();
+    assert!(query.contains("locationId=F-D0047-071"), "{}", query);
+    assert!(!query.contains("limit="), "{}", query);
+
+    // 4xx 不重試
+    let delivery = delivery(&service, webhook["id"].as_str().unwrap()).await;
+    assert_eq!(delivery["status"], "failed");
+    assert_eq!(delivery["attempts"].as_array().unwrap().len(), 1);
+
+    // 只有預報規則時不更新觀測資料
+    assert_eq!(mock.hits(WEATHER_DATA), 0);
+}
+
+#[tokio::test]
+async fn does_not_follow_redirects() {
+    let (_mock, service) = start().await;
+    let mut receiver = Receiver::start(&[StatusCode::TEMPORARY_REDIRECT]).await;
+
+    let webhook = register(
+        &service,
+        json!({
+            "url": receiver.url(),
+            "rules": [{"id": "cold", "field": "temperature", "op": "<=", "value": 2}],
+        }),
+    )
+    .await;
+    receiver.next().await;
+
+    let delivery = delivery(&service, webhook["id"].as_str().unwrap()).await;
+    assert_eq!(delivery["status"], "failed");
+    assert_eq!(delivery["attempts"].as_array().unwrap().len(), 1);
+    assert_eq!(delivery["attempts"][0]["status"], 307);
+
+    // 未轉送到導向的網址
+    assert!(receiver.received.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn manages_webhooks_with_the_admin_token() {
+    let (_mock, service) = start().await;
+
+    let res = service.get("/admin/webhooks").await;
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = reqwest::Client::new()
+        .get(service.url("/admin/webhooks"))
+        .bearer_auth("wrong")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    for body in [
+        json!({"url": "ftp://example.com", "rules": [{"id": "a", "field": "pop", "op": ">", "value": 1}]}),
+        json!({"url": "http://example.com", "rules": [{"id": "a", "field": "snow", "op": ">", "value": 1}]}),
+        json!({"url": "http://example.com", "rules": []}),
+    ] {
+        let res = admin(&service, Method::POST, "/admin/webhooks", Some(body)).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let webhook = register(
+        &service,
+        json!({
+            "url": "http://127.0.0.1:9/hook",
+            "rules": [{"id": "gust", "field": "wind_gust", "op": ">=", "value": 17.2, "cooldown": 60}],
+        }),
+    )
+    .await;
+    let path = format!("/admin/webhooks/{}", webhook["id"].as_str().unwrap());
+
+    let res = admin(&service, Method::GET, &path, None).await;
+    assert_eq!(
+        res.json::<Value>().await.unwrap()["rules"][0]["cooldown"],
+        60
+    );
+
+    let res = admin(&service, Method::DELETE, &path, None).await;
+    assert_eq!(res.status(), StatusCode::NO_CONTENT);
+
+    for path in [path.clone(), format!("{}/deliveries", path)] {
+        let res = admin(&service, Method::GET, &path, None).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND, "{}", path);
+    }
+
+    // 不存在的 webhook 仍以路由樣式記錄
+    let text = service.get("/metrics").await.text().await.unwrap();
+    for route in ["/admin/webhooks/:id", "/admin/webhooks/:id/deliveries"] {
+        let series = format!(
+            r#"http_requests_total{{method="GET",route="{}",status="404"}} 1"#,
+            route
+        );
+        assert!(text.contains(&series), "{}", series);
+    }
+}
+
+#[tokio::test]
+async fn admin_api_is_disabled_without_a_token() {
+    let mock = MockCwb::start().await;
+    let service = Service::start(&mock.url(), &[]).await;
+
+    let res = service.get("/admin/webhooks").await;
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,160 @@
+mod common;
+
+use common::{start, warmer_taipei, Service, WEATHER_DATA};
+use futures::{SinkExt, StreamExt};
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn connect(service: &Service, query: &str) -> Socket {
+    let url = format!("ws://{}/weather/ws{}", service.addr, query);
+    let (socket, _) = connect_async(url).await.unwrap();
+    socket
+}
+
+async fn send(socket: &mut Socket, message: Value) {
+    socket
+        .send(Message::text(message.to_string()))
+        .await
+        .unwrap();
+}
+
+/// 下一個 JSON 訊息，略過 ping
+async fn next(socket: &mut Socket) -> Value {
+    let read = async {
+        loop {
+            match socket.next().await.expect("socket closed").unwrap() {
+                Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+                Message::Ping(_) | Message::Pong(_) => continue,
+                message => panic!("unexpected message: {:?}", message),
+            }
+        }
+    };
+
+    tokio::time::timeout(Duration::from_secs(10), read)
+        .await
+        .expect("no message within 10 seconds")
+}
+
+fn names(records: &Value) -> Vec<&str> {
+    records
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn pushes_updates_for_subscribed_stations_and_towns() {
+    let (mock, service) = start(&[("REFRESH_INTERVAL", "1")]).await;
+    let mut socket = connect(&service, "").await;
+
+    send(
+        &mut socket,
+        json!({"type": "subscribe", "stations": ["C07014"], "towns": ["板橋區"]}),
+    )
+    .await;
+
+    let current = next(&mut socket).await;
+    assert_eq!(current["type"], "update");
+    assert!(current.get("id").is_none());
+    assert_eq!(names(&current["records"]), ["板橋", "臺北"]);
+    assert_eq!(current["records"][1]["id"], "C07014");
+
+    let subscriptions = next(&mut socket).await;
+    assert_eq!(subscriptions["type"], "subscriptions");
+    assert_eq!(subscriptions["stations"], json!(["C07014"]));
+    assert_eq!(subscriptions["towns"], json!(["板橋區"]));
+
+    mock.reply(WEATHER_DATA, warmer_taipei());
+
+    let update = next(&mut socket).await;
+    assert_eq!(update["type"], "update");
+    assert_eq!(update["id"], 1);
+    assert_eq!(names(&update["records"]), ["臺北"]);
+    assert_eq!(update["records"][0]["temp"], 31.5);
+
+    send(
+        &mut socket,
+        json!({"type": "unsubscribe", "towns": ["板橋區"]}),
+    )
+    .await;
+    let subscriptions = next(&mut socket).await;
+    assert_eq!(subscriptions["towns"], json!([]));
+}
+
+#[tokio::test]
+async fn alerts_when_a_rule_starts_to_match() {
+    let (mock, service) = start(&[("REFRESH_INTERVAL", "1")]).await;
+    let mut socket = connect(&service, "?lang=en").await;
+
+    send(
+        &mut socket,
+        json!({"type": "add_rule", "id": "hot", "field": "TEMP", "op": ">", "value": 31}),
+    )
+    .await;
+    let subscriptions = next(&mut socket).await;
+    assert_eq!(subscriptions["rules"][0]["id"], "hot");
+
+    // 已達門檻的測站於註冊時立即通知
+    send(
+        &mut socket,
+        json!({"type": "add_rule", "id": "cold", "field": "TEMP", "op": "<=", "value": 2}),
+    )
+    .await;
+    assert_eq!(next(&mut socket).await["type"], "subscriptions");
+    let alert = next(&mut socket).await;
+    assert_eq!(alert["type"], "alert");
+    assert_eq!(alert["rule"], "cold");
+    assert_eq!(alert["record"]["name"], "玉山");
+    assert_eq!(alert["record"]["city"], "Nantou County");
+
+    mock.reply(WEATHER_DATA, warmer_taipei());
+
+    let alert = next(&mut socket).await;
+    assert_eq!(alert["type"], "alert");
+    assert_eq!(alert["rule"], "hot");
+    assert_eq!(alert["op"], ">");
+    assert_eq!(alert["threshold"], 31.0);
+    assert_eq!(alert["value"], 31.5);
+    assert_eq!(alert["record"]["name"], "臺北");
+}
+
+#[tokio::test]
+async fn reports_invalid_messages() {
+    let (_mock, service) = start(&[("REFRESH_INTERVAL", "1")]).await;
+    let mut socket = connect(&service, "").await;
+
+    send(&mut socket, json!({"type": "dance"})).await;
+    assert_eq!(next(&mut socket).await["type"], "error");
+
+    send(
+        &mut socket,
+        json!({"type": "add_rule", "id": "x", "field": "SNOW", "op": ">", "value": 1}),
+    )
+    .await;
+    let error = next(&mut socket).await;
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["message"], "unknown field: SNOW");
+    assert_eq!(next(&mut socket).await["rules"], json!([]));
+
+    // 連線仍可繼續使用
+    send(&mut socket, json!({"type": "remove_rule", "id": "x"})).await;
+    assert_eq!(next(&mut socket).await["type"], "subscriptions");
+}
+
+#[tokio::test]
+async fn requires_an_upgrade_request() {
+    let (_mock, service) = start(&[("REFRESH_INTERVAL", "1")]).await;
+
+    let res = service.get("/weather/ws").await;
+    assert_eq!(res.status(), StatusCode::UPGRADE_REQUIRED);
+
+    let res = service.get("/weather/ws?units=kelvin").await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}